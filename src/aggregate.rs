@@ -0,0 +1,133 @@
+use std::thread;
+
+use serde::Serialize;
+
+use crate::cli::Cli;
+use crate::i18n::{t, tf};
+use crate::output::Format;
+use crate::render::print_table;
+use crate::report::{self, CollectOptions, Report};
+
+// --sample-count：连续采集 N 次，对经常变化的指标给出 min/max/avg/p95，而不是某一次的读数
+#[derive(Serialize)]
+struct Aggregate {
+    samples: u32,
+    interval_secs: f64,
+    metrics: Vec<MetricStats>,
+}
+
+#[derive(Serialize)]
+struct MetricStats {
+    metric: String,
+    unit: &'static str,
+    count: usize,
+    min: f64,
+    max: f64,
+    avg: f64,
+    p95: f64,
+}
+
+// 按首次出现的顺序记录每个指标的所有读数；读不到的那一次不计入
+#[derive(Default)]
+struct Samples {
+    metrics: Vec<(String, &'static str, Vec<f64>)>,
+}
+
+impl Samples {
+    fn add(&mut self, metric: String, unit: &'static str, value: Option<f64>) {
+        let Some(value) = value else {
+            return;
+        };
+        match self.metrics.iter_mut().find(|(name, _, _)| *name == metric) {
+            Some((_, _, values)) => values.push(value),
+            None => self.metrics.push((metric, unit, vec![value])),
+        }
+    }
+
+    fn record(&mut self, report: &Report) {
+        let system = &report.system;
+        self.add("system.average_cpu_usage".to_string(), "%", Some(system.average_cpu_usage as f64));
+        self.add("system.used_memory".to_string(), "GB", Some(system.used_memory));
+        self.add("system.used_swap".to_string(), "GB", Some(system.used_swap));
+
+        for gpu in &report.gpus {
+            let path = |field: &str| format!("gpus.{}.{}", gpu.index, field);
+            self.add(path("utilization"), "%", gpu.utilization.map(f64::from));
+            self.add(path("gpu_temperature"), "C", gpu.gpu_temperature.map(f64::from));
+            self.add(path("power_usage"), "W", gpu.power_usage);
+            self.add(path("memory_used"), "GB", gpu.memory_used);
+        }
+    }
+
+    fn stats(self) -> Vec<MetricStats> {
+        self.metrics
+            .into_iter()
+            .map(|(metric, unit, mut values)| {
+                values.sort_by(f64::total_cmp);
+                let count = values.len();
+                // 最近秩法：第 ceil(0.95 * n) 小的值
+                let p95 = values[(count * 95).div_ceil(100) - 1];
+                MetricStats {
+                    metric,
+                    unit,
+                    count,
+                    min: values[0],
+                    max: values[count - 1],
+                    avg: values.iter().sum::<f64>() / count as f64,
+                    p95,
+                }
+            })
+            .collect()
+    }
+}
+
+// 每次采集本身包含 1 秒的 CPU 采样窗口，两次之间再等 --sample-interval
+pub fn run(cli: &Cli, count: u32) {
+    let options = CollectOptions {
+        weather: false,
+        ..cli.collect_options()
+    };
+    let mut samples = Samples::default();
+
+    for n in 1..=count {
+        eprintln!("{}", tf("aggregate.progress", &[&n, &count]));
+        samples.record(&report::collect_report(&options));
+        if n < count {
+            thread::sleep(cli.sample_interval);
+        }
+    }
+
+    let aggregate = Aggregate {
+        samples: count,
+        interval_secs: cli.sample_interval.as_secs_f64(),
+        metrics: samples.stats(),
+    };
+
+    if cli.json || cli.format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&aggregate).unwrap());
+        return;
+    }
+
+    println!("{}", tf("aggregate.section", &[&count, &humantime::format_duration(cli.sample_interval)]));
+    let value = |value: f64, unit: &str| match unit {
+        "GB" => format!("{:.2} {}", value, unit),
+        _ => format!("{:.1} {}", value, unit),
+    };
+    let rows: Vec<Vec<String>> = aggregate
+        .metrics
+        .iter()
+        .map(|stats| {
+            vec![
+                stats.metric.clone(),
+                value(stats.min, stats.unit),
+                value(stats.max, stats.unit),
+                value(stats.avg, stats.unit),
+                value(stats.p95, stats.unit),
+            ]
+        })
+        .collect();
+    print_table(
+        &[t("aggregate.metric"), t("aggregate.min"), t("aggregate.max"), t("aggregate.avg"), "p95"],
+        &rows,
+    );
+}
@@ -0,0 +1,119 @@
+use std::collections::hash_map::RandomState;
+use std::fs::{self, OpenOptions};
+use std::hash::BuildHasher;
+use std::io::{ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::config;
+use crate::gpu::GpuInfo;
+use crate::i18n::t;
+use crate::report::Report;
+
+const SALT_FILE: &str = "anonymize-salt";
+pub const REDACTED: &str = "<redacted>";
+
+// 识别信息换成什么：--anonymize 是加盐哈希，同一个盐下同一个值的哈希不变，重复提交的报告可以关联起来；
+// --redact 一律换成 "<redacted>"，贴到公开的 issue 里也看不出任何原始值
+#[derive(Debug, Clone)]
+pub enum Scrub {
+    Hash(String),
+    Redact,
+}
+
+impl Scrub {
+    pub fn value(&self, value: &str) -> String {
+        match self {
+            Scrub::Hash(salt) => salted_hash(salt, value),
+            Scrub::Redact => REDACTED.to_string(),
+        }
+    }
+}
+
+// 去掉报告中的个人信息：主机名和域名、GPU UUID 和序列号、磁盘名和挂载点换掉，
+// IP 和天气（城市）直接丢弃；数值指标不动
+pub fn anonymize(report: &mut Report, scrub: &Scrub) {
+    report.system.host_name = report.system.host_name.as_deref().map(|name| scrub.value(name));
+    report.system.fqdn = report.system.fqdn.as_deref().map(|name| scrub.value(name));
+    report.system.primary_ip = None;
+
+    anonymize_gpus(&mut report.gpus, scrub);
+
+    for disk in &mut report.system.disks {
+        disk.name = scrub.value(&disk.name);
+        disk.mount_point = scrub.value(&disk.mount_point);
+    }
+
+    report.weather = None;
+    report.errors.retain(|error| error.section != "weather");
+}
+
+pub fn anonymize_gpus(gpus: &mut [GpuInfo], scrub: &Scrub) {
+    for gpu in gpus {
+        gpu.uuid = scrub.value(&gpu.uuid);
+        gpu.serial = gpu.serial.as_deref().map(|serial| scrub.value(serial));
+        // 命令行里常有用户目录、数据集路径之类的信息，只保留进程名
+        for process in &mut gpu.processes {
+            process.cmd = None;
+            process.parent_cmd = None;
+        }
+    }
+}
+
+// 没给 --anonymize-salt 时的盐：第一次用时随机生成，存在配置目录的 anonymize-salt 里（仅本人可读），之后一直用它。
+// 同一台机器重复提交的报告仍能关联起来；盐不公开，别人也没法拿常见的主机名、挂载点逐个算哈希来反查
+pub fn install_salt() -> Result<String, String> {
+    let dir = config::config_dir().ok_or_else(|| t("anonymize.no_config_dir").to_string())?;
+    let path = dir.join(SALT_FILE);
+    if let Some(salt) = read_salt(&path)? {
+        return Ok(salt);
+    }
+
+    let salt = random_salt();
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    match options.open(&path) {
+        Ok(mut file) => file.write_all(salt.as_bytes()).map_err(|e| format!("{}: {}", path.display(), e))?,
+        // 另一个进程刚刚抢先生成了，用它的
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+            return read_salt(&path)?.ok_or_else(|| format!("{}: {}", path.display(), t("anonymize.empty_salt")));
+        }
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    }
+
+    Ok(salt)
+}
+
+fn read_salt(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(salt) if !salt.trim().is_empty() => Ok(Some(salt.trim().to_string())),
+        Ok(_) => Err(format!("{}: {}", path.display(), t("anonymize.empty_salt"))),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+// 标准库的 RandomState 用操作系统提供的随机数做种子，不为这一处引入 rand
+fn random_salt() -> String {
+    let mut hasher = Sha256::new();
+    for i in 0..4u8 {
+        hasher.update(RandomState::new().hash_one(i).to_le_bytes());
+    }
+
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn salted_hash(salt: &str, value: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(value.as_bytes());
+
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,88 @@
+// 启动安全状态，合规盘点用；判断不了（传统 BIOS、没有权限）时为 None
+#[cfg(target_os = "linux")]
+pub fn secure_boot() -> Option<bool> {
+    use std::io::ErrorKind;
+    use std::path::Path;
+
+    use crate::privileges;
+
+    // 传统 BIOS 启动没有 efi 目录
+    if !Path::new("/sys/firmware/efi").exists() {
+        return None;
+    }
+
+    // 变量内容是 4 字节属性加 1 字节取值；UEFI 固件没有这个变量说明不支持安全启动
+    match std::fs::read("/sys/firmware/efi/efivars/SecureBoot-8be4df61-93ca-11d2-aa0d-00e098032b8c") {
+        Ok(data) => data.get(4).map(|&value| value == 1),
+        Err(e) if e.kind() == ErrorKind::NotFound => Some(false),
+        Err(e) => {
+            if e.kind() == ErrorKind::PermissionDenied {
+                privileges::denied("system.secure_boot");
+            }
+            None
+        }
+    }
+}
+
+// 内核注册了 TPM 设备就会有 /sys/class/tpm/tpm0；没加载 TPM 驱动时整个目录都没有
+#[cfg(target_os = "linux")]
+pub fn tpm_present() -> Option<bool> {
+    let mut devices = std::fs::read_dir("/sys/class/tpm").ok()?;
+    Some(devices.next().is_some())
+}
+
+#[cfg(windows)]
+pub fn secure_boot() -> Option<bool> {
+    use crate::privileges;
+
+    // 传统 BIOS 上 Confirm-SecureBootUEFI 报 "not supported"，没有管理员权限时报 "Access was denied"
+    let output = powershell("Confirm-SecureBootUEFI")?;
+    match output.trim() {
+        "True" => Some(true),
+        "False" => Some(false),
+        _ => {
+            if output.to_lowercase().contains("access was denied") {
+                privileges::denied("system.secure_boot");
+            }
+            None
+        }
+    }
+}
+
+// Win32_Tpm 需要管理员权限；没有 TPM 时查询结果为空
+#[cfg(windows)]
+pub fn tpm_present() -> Option<bool> {
+    use crate::privileges;
+
+    let output = powershell(
+        "Get-CimInstance -Namespace root/cimv2/security/microsofttpm -ClassName Win32_Tpm \
+         | Select-Object -ExpandProperty IsEnabled_InitialValue",
+    )?;
+    if output.to_lowercase().contains("access denied") {
+        privileges::denied("system.tpm_present");
+        return None;
+    }
+
+    Some(!output.trim().is_empty())
+}
+
+// stdout 和 stderr 合在一起返回，错误信息里的原因也要用来判断
+#[cfg(windows)]
+fn powershell(command: &str) -> Option<String> {
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", command])
+        .output()
+        .ok()?;
+
+    Some(format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn secure_boot() -> Option<bool> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn tpm_present() -> Option<bool> {
+    None
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+// 容器里 sysinfo 报告的是宿主机的内存和 CPU，实际可用的上限由 cgroup 决定；没有限制的项为 None
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct CgroupLimits {
+    pub memory_limit_bytes: Option<u64>,
+    // CPU 配额折算成核数，例如 cpu.max 为 "200000 100000" 时是 2.0
+    pub cpu_limit: Option<f64>,
+}
+
+// 两项都没有限制时返回 None
+#[cfg(target_os = "linux")]
+pub fn read() -> Option<CgroupLimits> {
+    let membership = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    let limits = read_v2(&membership).unwrap_or_else(|| read_v1(&membership));
+    (limits.memory_limit_bytes.is_some() || limits.cpu_limit.is_some()).then_some(limits)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read() -> Option<CgroupLimits> {
+    None
+}
+
+// cgroup v2：/proc/self/cgroup 里是 "0::/路径"，容器里一般就是 "/"
+#[cfg(target_os = "linux")]
+fn read_v2(membership: &str) -> Option<CgroupLimits> {
+    let path = membership.lines().find_map(|line| line.strip_prefix("0::"))?;
+    let dir = format!("/sys/fs/cgroup{}", path.trim_end_matches('/'));
+    // 不在 v2 层级里（混合模式）时没有 cgroup.controllers
+    if !std::path::Path::new(&dir).join("cgroup.controllers").exists() {
+        return None;
+    }
+
+    let memory_limit_bytes = read_value(&format!("{}/memory.max", dir)).and_then(|value| value.parse().ok());
+    // "max 100000" 表示不限制
+    let cpu_limit = read_value(&format!("{}/cpu.max", dir)).and_then(|value| {
+        let (quota, period) = value.split_once(' ')?;
+        cores(quota.parse().ok()?, period.parse().ok()?)
+    });
+
+    Some(CgroupLimits {
+        memory_limit_bytes,
+        cpu_limit,
+    })
+}
+
+// cgroup v1：不限制时 memory.limit_in_bytes 是一个接近 i64::MAX 的数，cfs_quota_us 是 -1
+#[cfg(target_os = "linux")]
+fn read_v1(membership: &str) -> CgroupLimits {
+    let memory_limit_bytes = read_v1_value(membership, "memory", "memory.limit_in_bytes")
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|&limit| limit < i64::MAX as u64 / 2);
+    let quota = read_v1_value(membership, "cpu", "cpu.cfs_quota_us").and_then(|value| value.parse::<u64>().ok());
+    let period = read_v1_value(membership, "cpu", "cpu.cfs_period_us").and_then(|value| value.parse().ok());
+
+    CgroupLimits {
+        memory_limit_bytes,
+        cpu_limit: quota.zip(period).and_then(|(quota, period)| cores(quota, period)),
+    }
+}
+
+// v1 每个控制器一行 "编号:控制器列表:路径"；容器里挂载点一般就是自己的 cgroup，路径下没有文件时读挂载点根目录
+#[cfg(target_os = "linux")]
+fn read_v1_value(membership: &str, controller: &str, file: &str) -> Option<String> {
+    let (controllers, path) = membership.lines().find_map(|line| {
+        let (_, rest) = line.split_once(':')?;
+        let (controllers, path) = rest.split_once(':')?;
+        controllers.split(',').any(|name| name == controller).then_some((controllers, path))
+    })?;
+    let mount = format!("/sys/fs/cgroup/{}", controllers);
+
+    read_value(&format!("{}{}/{}", mount, path.trim_end_matches('/'), file))
+        .or_else(|| read_value(&format!("{}/{}", mount, file)))
+}
+
+#[cfg(target_os = "linux")]
+fn cores(quota: u64, period: u64) -> Option<f64> {
+    (period > 0).then(|| quota as f64 / period as f64)
+}
+
+#[cfg(target_os = "linux")]
+fn read_value(path: &str) -> Option<String> {
+    std::fs::read_to_string(path).ok().map(|text| text.trim().to_string())
+}
@@ -0,0 +1,675 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono_tz::Tz;
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::anonymize::Scrub;
+use crate::config::{Config, ResolvedValue};
+use crate::fields;
+use crate::gpu::GpuBackend;
+use crate::i18n::Lang;
+use crate::log_file::RotatingLog;
+use crate::metrics;
+use crate::output::{self, Format};
+use crate::processes::ProcessSort;
+use crate::report::CollectOptions;
+use crate::system::{Refresh, Units};
+use crate::weather::{Provider, WeatherQuery};
+
+/// Print GPU, system, disk and weather details for this machine.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Config file (TOML); defaults to rust-system-details/config.toml in the user config directory.
+    /// weather_provider, weather_fallback, weather_url, city_id, lang, timezone, interval and units can
+    /// be set there, in SYSDETAILS_* environment variables or on the command line; the command line
+    /// wins, then the environment, then the file
+    #[arg(long, value_name = "PATH", env = "SYSDETAILS_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Settings loaded from the config file
+    #[arg(skip)]
+    pub settings: Config,
+
+    /// Effective value and source of each layered setting
+    #[arg(skip)]
+    pub resolved: Vec<ResolvedValue>,
+
+    /// Print the effective configuration and where each value came from, then exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Output language; defaults to Chinese when LANG/LC_ALL starts with "zh", English otherwise
+    #[arg(long, value_enum, env = "SYSDETAILS_LANG")]
+    pub lang: Option<Lang>,
+
+    /// Time zone for displayed times (IANA name such as America/New_York); defaults to the
+    /// system's local time zone
+    #[arg(long, value_name = "ZONE", value_parser = parse_timezone, env = "SYSDETAILS_TIMEZONE")]
+    pub timezone: Option<Tz>,
+
+    /// Where the weather comes from; meizu and oioweb only cover China
+    #[arg(long, value_enum, default_value_t = Provider::Meizu, env = "SYSDETAILS_WEATHER_PROVIDER")]
+    pub weather_provider: Provider,
+
+    /// Weather providers to try in order when the main one fails or returns something that can't
+    /// be parsed, e.g. --weather-fallback openweathermap,meizu; oioweb can't be a fallback
+    #[arg(
+        long,
+        value_name = "PROVIDER,...",
+        value_enum,
+        value_delimiter = ',',
+        env = "SYSDETAILS_WEATHER_FALLBACK"
+    )]
+    pub weather_fallback: Vec<Provider>,
+
+    /// Send weather requests for the main provider here instead of its public endpoint, e.g. to a
+    /// caching proxy: the full listWeather URL for meizu, the prefix that weather and forecast are
+    /// appended to for openweathermap, the full GetWeather URL for oioweb. Must be an http:// or
+    /// https:// URL; a trailing / is ignored
+    #[arg(long, value_name = "URL", env = "SYSDETAILS_WEATHER_URL")]
+    pub weather_url: Option<String>,
+
+    /// City for the weather forecast (meizu weather API city ID)
+    #[arg(long, value_name = "ID", default_value = "101200105", env = "SYSDETAILS_CITY_ID")]
+    pub city_id: String,
+
+    /// OpenWeatherMap API key
+    #[arg(long, value_name = "KEY", env = "OWM_API_KEY", hide_env_values = true)]
+    pub owm_api_key: Option<String>,
+
+    /// City name for OpenWeatherMap, e.g. "Berlin" or "London,GB"
+    #[arg(long, value_name = "NAME", conflicts_with = "lat")]
+    pub owm_city: Option<String>,
+
+    /// Latitude for OpenWeatherMap
+    #[arg(long, requires = "lon", allow_hyphen_values = true)]
+    pub lat: Option<f64>,
+
+    /// Longitude for OpenWeatherMap
+    #[arg(long, requires = "lat", allow_hyphen_values = true)]
+    pub lon: Option<f64>,
+
+    /// Reuse weather fetched within this long from the on-disk cache
+    /// ($XDG_CACHE_HOME/rust-system-details/weather-<city>.json; 0 = always fetch)
+    #[arg(long, value_name = "DURATION", default_value = "10m", value_parser = parse_duration)]
+    pub weather_cache_ttl: Duration,
+
+    /// Fetch the weather even when the cached copy is still fresh (the cache is still updated)
+    #[arg(long, visible_alias = "refresh-weather")]
+    pub no_cache: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Shorthand for --format json
+    #[arg(long, conflicts_with = "format")]
+    pub json: bool,
+
+    /// Render a report previously saved with --json instead of collecting a live one
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["watch", "serve"])]
+    pub from_json: Option<PathBuf>,
+
+    /// Compare against a report previously saved with --json and print only the fields that changed
+    /// (with --json, as a list of {path, old, new}); combine with --from-json to compare two saved reports
+    #[arg(long, value_name = "OLD.json", conflicts_with_all = ["watch", "serve"])]
+    pub diff: Option<PathBuf>,
+
+    /// Write the JSON/CSV report to this file instead of stdout (a text report is written as JSON);
+    /// {hostname}, {timestamp} and {date} are expanded per report, e.g. /mnt/reports/{hostname}-{timestamp}.json
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Write one section (gpus, system or weather) of the JSON report somewhere else: stdout,
+    /// stderr or a file, e.g. --section-output weather=weather.jsonl (repeatable; adds to
+    /// `section_output` in the config file). The section is left out of the main report
+    #[arg(long, value_name = "SECTION=DEST", value_parser = parse_section_output)]
+    pub section_output: Vec<(String, String)>,
+
+    /// Show the text report through $PAGER (default `less -R`) when stdout is a terminal
+    #[arg(long, overrides_with = "no_pager")]
+    pub pager: bool,
+
+    /// Never use a pager, overriding --pager (e.g. one set in a shell alias)
+    #[arg(long, overrides_with = "pager")]
+    pub no_pager: bool,
+
+    /// Indent JSON output (default when writing to --output), including /report.json with --serve
+    #[arg(long, overrides_with = "compact")]
+    pub pretty: bool,
+
+    /// Print JSON on a single line (default on stdout)
+    #[arg(long, overrides_with = "pretty")]
+    pub compact: bool,
+
+    /// Only output these comma-separated dotted paths, e.g. gpus.0.gpu_temperature,system.used_memory,
+    /// system.disks./.available_space (array items can be picked by index, `*`, mount point, UUID or name).
+    /// Only the collectors these paths need are run: e.g. gpus.* alone skips the 1 s CPU sample and weather
+    #[arg(long, value_name = "PATHS", value_delimiter = ',', value_parser = fields::check_top_level)]
+    pub fields: Vec<String>,
+
+    /// Print a shareable JSON report: hostname, disk names and mount points are replaced by
+    /// salted SHA-256 hashes and weather (city) is not collected
+    #[arg(long)]
+    pub anonymize: bool,
+
+    /// Salt for the --anonymize hashes; keep it the same to correlate repeat submissions. Defaults to a
+    /// random salt generated on first use and kept in the config directory (anonymize-salt)
+    #[arg(long, value_name = "SALT")]
+    pub anonymize_salt: Option<String>,
+
+    /// Replace the hostname, IP, disk names and mount points, GPU UUIDs and serials with "<redacted>"
+    /// in every output format; numeric metrics are kept and weather (city) is not collected
+    #[arg(long, conflicts_with = "anonymize")]
+    pub redact: bool,
+
+    /// Only report these GPUs, e.g. 0,2,4-6 (indices as reported by NVML)
+    #[arg(long, value_name = "LIST", value_parser = parse_gpu_list)]
+    pub gpu: Option<GpuList>,
+
+    /// Only refresh these kinds of system data (default: memory,cpu,disks). Leaving out cpu
+    /// skips the 1 s CPU sampling; processes resolves GPU process names and parents, which
+    /// scans the whole process table; networks adds per-interface traffic totals
+    #[arg(long, value_enum, value_name = "KINDS", value_delimiter = ',')]
+    pub refresh: Vec<RefreshKind>,
+
+    /// Also list installed RAM modules (slot, size, speed, manufacturer); uses dmidecode on
+    /// Linux, which usually needs root
+    #[arg(long)]
+    pub memory_modules: bool,
+
+    /// Also report the kernel command line, vm.swappiness, vm.overcommit_memory and the
+    /// transparent hugepage mode (Linux only)
+    #[arg(long)]
+    pub kernel_params: bool,
+
+    /// Also report whether Secure Boot is enabled and a TPM is present (Linux and Windows;
+    /// Windows needs an elevated prompt)
+    #[arg(long)]
+    pub security: bool,
+
+    /// Also list the N processes with the most TCP traffic during the 1 s sampling window
+    /// (Linux only, uses `ss`; run as root to see other users' processes)
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    pub net_top: Option<usize>,
+
+    /// Active test: time a directory read on each mount point and report the latency in ms
+    #[arg(long)]
+    pub disk_latency: bool,
+
+    /// Also let --disk-latency write and fsync a 4 KiB temp file on each writable mount (removed afterwards)
+    #[arg(long, requires = "disk_latency")]
+    pub yes: bool,
+
+    /// Only report the N largest disks and count the rest (useful with hundreds of mounts)
+    #[arg(long, value_name = "N", overrides_with = "all_disks")]
+    pub max_disks: Option<usize>,
+
+    /// List disks from largest to smallest instead of in mount order
+    #[arg(long)]
+    pub sort_disks: bool,
+
+    /// Order of the GPU process list: mem (VRAM, the default) and cpu (CPU usage, sampled for
+    /// 200 ms) from highest to lowest, name alphabetically; ties are ordered by PID. cpu and name
+    /// scan the process table like --refresh processes
+    #[arg(long, value_enum, value_name = "KEY", default_value_t = ProcessSort::Mem)]
+    pub sort: ProcessSort,
+
+    /// Only list the first N processes of each GPU in --sort order
+    #[arg(long, value_name = "N")]
+    pub max_processes: Option<usize>,
+
+    /// Report every disk, overriding --max-disks (e.g. one set in a shell alias)
+    #[arg(long, overrides_with = "max_disks")]
+    pub all_disks: bool,
+
+    /// Also list bind mounts and extra btrfs subvolumes of a device that is already listed (marked,
+    /// and never counted twice in the disk totals) and mounts with no capacity such as empty
+    /// CD-ROM drives
+    #[arg(long)]
+    pub all_mounts: bool,
+
+    /// Exit with status 0 even when no GPU could be queried (default); with --strict, GPU errors
+    /// are then not treated as failures
+    #[arg(long, overrides_with = "gpu_required")]
+    pub gpu_optional: bool,
+
+    /// Exit with status 3 when no GPU could be queried; in serve mode /healthz returns 503
+    #[arg(long, overrides_with = "gpu_optional")]
+    pub gpu_required: bool,
+
+    /// On exit, print this tool's own CPU time and peak memory (RSS) to stderr, e.g. to judge how
+    /// often it can run in watch mode
+    #[arg(long)]
+    pub self_stats: bool,
+
+    /// Collect once and print how long each phase took instead of the report, e.g. "gpu: 8 ms,
+    /// system: 1003 ms"; a JSON object with --json. Compare with --refresh to see what a
+    /// section costs
+    #[arg(long, conflicts_with_all = ["watch", "serve", "from_json"])]
+    pub bench: bool,
+
+    /// Exit with status 4 and name the failed section as soon as any part of the collection fails,
+    /// instead of printing a partial report. GPU errors count too unless --gpu-optional is given.
+    /// Without it collection is best-effort: a failed section is shown with its error (and listed
+    /// under "errors" in JSON) and the rest of the report is still printed
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Exit with status 5 when any GPU reports a power or thermal throttle (power cap, hardware
+    /// slowdown, thermal slowdown, power brake), naming the GPU and reason; in watch mode at the end
+    /// of the run
+    #[arg(long)]
+    pub crit_on_throttle: bool,
+
+    /// Exit with status 6 when the report's health is warning and 7 when it is critical (memory
+    /// pressure, GPU throttling or temperature near its slowdown point, nearly full disks; failed
+    /// sections are left to --strict); the same rollup is always in the JSON report as "health"
+    #[arg(long)]
+    pub health_exit: bool,
+
+    /// Sample GPU utilization this many times over the 1 s CPU sampling window and report the
+    /// average and peak (0 = single reading only)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub gpu_util_samples: u32,
+
+    /// Don't wake a GPU that is suspended by runtime power management (hybrid/Optimus laptops);
+    /// only its name and UUID are reported
+    #[arg(long)]
+    pub no_wake: bool,
+
+    /// How to read GPU information: NVML, the nvidia-smi command (fewer fields), or NVML with
+    /// nvidia-smi as a fallback when the NVML library cannot be loaded. With auto and no --gpu,
+    /// AMD and Intel GPUs found under /sys/class/drm are listed too
+    #[arg(long, value_enum, default_value_t = GpuBackend::Auto)]
+    pub gpu_backend: GpuBackend,
+
+    /// Report fixed fake data instead of reading hardware or the network (one RTX 4090 at 61 C and
+    /// 97%, host mock-host with 8/32 GB RAM, disks / and /data, sunny 25 C in Wuhan), for demos
+    /// and testing output formats; the full fixture is listed in src/mock.rs
+    #[arg(long)]
+    pub mock: bool,
+
+    /// Static label attached to every exported metric and to the JSON meta, e.g. --label
+    /// datacenter=dc1 (repeatable; names follow Prometheus label rules; adds to `labels` in the config file)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_label)]
+    pub label: Vec<(String, String)>,
+
+    /// Print the multi-day forecast as one block per day instead of a compact table
+    #[arg(long)]
+    pub weather_verbose: bool,
+
+    /// Add a plain-language explanation after each metric in the text report
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Check that NVML loads and matches the driver, print the versions and exit
+    #[arg(long, conflicts_with_all = ["watch", "serve", "list_gpus"])]
+    pub verify_nvml: bool,
+
+    /// List GPUs with their immutable identifiers (UUID, serial number, VBIOS version) for
+    /// asset tracking, one per line or as a JSON array with --format json
+    #[arg(long, conflicts_with_all = ["watch", "serve"])]
+    pub list_gpus: bool,
+
+    /// Print a static hardware inventory (CPU, RAM, GPUs, disks, OS, hostname) for asset
+    /// databases, without usage sampling or weather; JSON with --format json
+    #[arg(long, conflicts_with_all = ["watch", "serve", "list_gpus"])]
+    pub inventory: bool,
+
+    /// Keep refreshing the report until interrupted; on Unix, SIGHUP or SIGUSR1 triggers an
+    /// immediate extra refresh (a signal received mid-collection refreshes again right after it).
+    /// The screen is only redrawn in place on a terminal; otherwise each report is appended
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Stop watch mode after N refreshes and print the session summary (0 = until interrupted)
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "watch")]
+    pub count: u32,
+
+    /// Print one line per GPU every --interval (time, temperature, power, utilization, memory,
+    /// clocks) like `nvidia-smi dmon`, appending instead of redrawing
+    #[arg(long, conflicts_with_all = ["watch", "serve", "from_json"])]
+    pub gpu_dmon: bool,
+
+    /// Sample GPU temperature, clocks, power and throttle reasons every --capture-interval for
+    /// --duration and write one CSV row per GPU per sample to this file, for clock-vs-temperature
+    /// plots
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "duration",
+        conflicts_with_all = ["watch", "serve", "from_json", "gpu_dmon"]
+    )]
+    pub capture: Option<PathBuf>,
+
+    /// How long --capture records (e.g. 60, 5m)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "capture")]
+    pub duration: Option<Duration>,
+
+    /// Time between --capture samples; must be greater than zero
+    #[arg(long, value_name = "DURATION", default_value = "100ms", value_parser = parse_nonzero_duration)]
+    pub capture_interval: Duration,
+
+    /// Collect N reports and print min/max/avg/p95 of CPU, memory and GPU utilization,
+    /// temperature, power and memory instead of a single reading
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["watch", "serve", "from_json", "gpu_dmon"]
+    )]
+    pub sample_count: Option<u32>,
+
+    /// Pause between samples with --sample-count (each sample also takes the 1 s CPU window)
+    #[arg(
+        long,
+        value_name = "DURATION",
+        default_value = "1s",
+        value_parser = parse_duration,
+        requires = "sample_count"
+    )]
+    pub sample_interval: Duration,
+
+    /// Time between refreshes in watch mode, e.g. 5, 1m30s or 500ms (a bare number is seconds);
+    /// 0 collects once and exits
+    #[arg(
+        long,
+        value_name = "DURATION",
+        default_value = "5s",
+        value_parser = parse_duration,
+        env = "SYSDETAILS_INTERVAL"
+    )]
+    pub interval: Duration,
+
+    /// In watch mode, highlight values that changed since the previous refresh (text output only)
+    #[arg(long, requires = "watch")]
+    pub watch_diff_highlight: bool,
+
+    /// In watch mode, show a sparkline (▁▂▃▅▇) of the last N samples next to each GPU's
+    /// temperature, utilization and clocks (text output only; 0 = off)
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "watch")]
+    pub sparkline: usize,
+
+    /// Units for rates in the text report: si (kB/s, MB/s) or iec (KiB/s, MiB/s)
+    #[arg(long, value_enum, default_value_t = Units::Si, env = "SYSDETAILS_UNITS")]
+    pub units: Units,
+
+    /// Decimal places for GB, W and usage values in the text report; JSON always keeps full precision
+    #[arg(long, value_name = "N", default_value_t = 2, value_parser = clap::value_parser!(u8).range(0..=9))]
+    pub precision: u8,
+
+    /// When to use colors: auto (only on a terminal and when NO_COLOR is unset), always or never
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// In watch mode, track per-GPU VRAM min/max/current and flag VRAM that keeps growing
+    #[arg(long, requires = "watch")]
+    pub watch_vram_growth: bool,
+
+    /// Window over which VRAM has to grow monotonically to be flagged
+    #[arg(long, value_name = "DURATION", default_value = "5m", value_parser = parse_duration)]
+    pub vram_growth_window: Duration,
+
+    /// In watch mode, print one JSON line per refresh containing only the fields that changed
+    /// (as JSON patch operations) instead of the whole report
+    #[arg(long, requires = "watch")]
+    pub delta: bool,
+
+    /// With --delta, emit a full snapshot every N refreshes (0 = only the first one)
+    #[arg(long, value_name = "N", default_value_t = 60)]
+    pub full_every: u64,
+
+    /// In watch mode, also append each refresh to the `readings` table of this SQLite database
+    /// (created if missing)
+    #[arg(long, value_name = "PATH", requires = "watch")]
+    pub sqlite: Option<PathBuf>,
+
+    /// In --watch mode, append a timestamped one-line summary per interval to this file
+    #[arg(long, value_name = "PATH", requires = "watch")]
+    pub log_file: Option<PathBuf>,
+
+    /// Append one compact JSON line (timestamp, CPU, memory and per-GPU temperature, utilization,
+    /// power and memory) per run, or per interval in --watch mode, to this file
+    #[arg(long, value_name = "PATH")]
+    pub history: Option<PathBuf>,
+
+    /// Rotate --log-file and --history once they would grow past this many MiB
+    #[arg(long, value_name = "MIB", default_value_t = 10)]
+    pub log_max_size: u64,
+
+    /// Number of rotated --log-file and --history copies to keep (file.1 … file.N)
+    #[arg(long, value_name = "N", default_value_t = 5)]
+    pub log_keep: u32,
+
+    /// Serve /report.json, /metrics (Prometheus) and /healthz on this port instead of printing
+    /// (needs the serve feature, on by default)
+    #[arg(long, value_name = "PORT", conflicts_with = "watch")]
+    pub serve: Option<u16>,
+
+    /// In serve mode, reuse a report collected within this long instead of collecting again
+    /// (0 = collect on every request)
+    #[arg(long, value_name = "DURATION", default_value = "2s", value_parser = parse_duration)]
+    pub cache_ttl: Duration,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print a single value from the report, e.g. `get gpus.0.gpu_temperature` (or `get gpu.0.temperature`);
+    /// memory.*, swap.*, disks.*, cpu.average and cpu.power are short for the same paths under system.
+    /// Exits with status 1 when the path does not exist or has no value
+    Get {
+        /// Dotted path as accepted by --fields
+        path: String,
+    },
+    /// Only fetch and print the weather (current conditions and the daily forecast), without
+    /// collecting anything about the GPU or the system. The weather options, cache and fallbacks
+    /// apply as usual; JSON with --json. Exits with 1 when the forecast can't be fetched, with 2
+    /// when the raw oioweb request fails
+    Weather,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RefreshKind {
+    Memory,
+    Cpu,
+    Disks,
+    Processes,
+    Networks,
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuList(Vec<u32>);
+
+impl Cli {
+    // --anonymize/--redact 不拉天气：天气里带着城市；给了 --fields 时只采集这些字段用得到的部分
+    pub fn collect_options(&self) -> CollectOptions {
+        let needs = (!self.fields.is_empty()).then(|| fields::needs(&self.fields));
+        CollectOptions {
+            gpu: needs.is_none_or(|needs| needs.gpu),
+            weather: self.scrub().is_none() && cfg!(feature = "weather") && needs.is_none_or(|needs| needs.weather),
+            gpu_indices: self.gpu_indices(),
+            memory_modules: self.memory_modules,
+            kernel_params: self.kernel_params,
+            security: self.security,
+            refresh: match needs {
+                Some(needs) if self.refresh.is_empty() => Refresh {
+                    memory: needs.memory,
+                    cpu: needs.cpu,
+                    disks: needs.disks,
+                    processes: false,
+                    networks: needs.networks,
+                },
+                _ => self.refresh(),
+            },
+            gpu_util_samples: self.gpu_util_samples,
+            wake_gpus: !self.no_wake,
+            gpu_backend: self.gpu_backend,
+            weather_query: WeatherQuery {
+                provider: self.weather_provider,
+                city_id: self.city_id.clone(),
+                api_key: self.owm_api_key.clone(),
+                city: self.owm_city.clone(),
+                coordinates: self.lat.zip(self.lon),
+                // --no-cache 时 TTL 为 0：照常写缓存，但读到的总是过期的
+                cache_ttl: match (self.weather_cache_ttl.is_zero(), self.no_cache) {
+                    (true, _) => None,
+                    (false, true) => Some(Duration::ZERO),
+                    (false, false) => Some(self.weather_cache_ttl),
+                },
+                fallbacks: self.weather_fallback.clone(),
+                base_url: self.weather_url.clone(),
+            },
+            custom_metrics: self.settings.custom_metrics.clone(),
+            disk_latency: self.disk_latency,
+            disk_latency_write: self.yes,
+            max_disks: self.max_disks.filter(|_| !self.all_disks),
+            process_sort: self.sort,
+            max_processes: self.max_processes,
+            all_mounts: self.all_mounts,
+            sort_disks: self.sort_disks,
+            net_top: self.net_top,
+            mock: self.mock,
+            labels: self.labels(),
+        }
+    }
+
+    pub fn open_history(&self) -> Result<Option<RotatingLog>, String> {
+        self.history
+            .as_deref()
+            .map(|path| RotatingLog::open(path, self.log_max_size * 1024 * 1024, self.log_keep))
+            .transpose()
+    }
+
+    pub fn scrub(&self) -> Option<Scrub> {
+        if self.redact {
+            Some(Scrub::Redact)
+        } else {
+            self.anonymize.then(|| Scrub::Hash(self.anonymize_salt.clone().expect("salt set by config::resolve")))
+        }
+    }
+
+    // 配置文件的 section_output 加上 --section-output，同一部分以命令行为准
+    pub fn section_outputs(&self) -> BTreeMap<String, String> {
+        let mut outputs = self.settings.section_output.clone();
+        outputs.extend(self.section_output.iter().cloned());
+        outputs
+    }
+
+    // 配置文件的 labels 加上 --label，同名时以命令行为准
+    pub fn labels(&self) -> BTreeMap<String, String> {
+        let mut labels = self.settings.labels.clone();
+        labels.extend(self.label.iter().cloned());
+        labels
+    }
+
+    // 没有 --refresh 时用默认的 memory,cpu,disks
+    pub fn refresh(&self) -> Refresh {
+        if self.refresh.is_empty() {
+            return Refresh::default();
+        }
+
+        let has = |kind| self.refresh.contains(&kind);
+        Refresh {
+            memory: has(RefreshKind::Memory),
+            cpu: has(RefreshKind::Cpu),
+            disks: has(RefreshKind::Disks),
+            processes: has(RefreshKind::Processes),
+            networks: has(RefreshKind::Networks),
+        }
+    }
+
+    // 没有 --gpu 时返回空列表，表示全部 GPU
+    pub fn gpu_indices(&self) -> Vec<u32> {
+        self.gpu.as_ref().map(|list| list.0.clone()).unwrap_or_default()
+    }
+}
+
+// 0,2,4-6 => [0, 2, 4, 5, 6]，去重并排序
+fn parse_gpu_list(value: &str) -> Result<GpuList, String> {
+    let mut indices = Vec::new();
+
+    for part in value.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let parse = |index: &str| index.trim().parse::<u32>().map_err(|_| format!("'{}' is not a GPU index", index));
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(format!("range '{}' is reversed", part));
+                }
+                indices.extend(start..=end);
+            }
+            None => indices.push(parse(part)?),
+        }
+    }
+
+    if indices.is_empty() {
+        return Err("expected at least one GPU index".to_string());
+    }
+    indices.sort_unstable();
+    indices.dedup();
+
+    Ok(GpuList(indices))
+}
+
+fn parse_label(value: &str) -> Result<(String, String), String> {
+    let (key, value) = value.split_once('=').ok_or_else(|| format!("expected KEY=VALUE, got '{}'", value))?;
+    metrics::check_label_name(key)?;
+
+    Ok((key.to_string(), value.to_string()))
+}
+
+fn parse_section_output(value: &str) -> Result<(String, String), String> {
+    let (section, destination) =
+        value.split_once('=').ok_or_else(|| format!("expected SECTION=DEST, got '{}'", value))?;
+    output::check_section(section)?;
+
+    Ok((section.to_string(), destination.to_string()))
+}
+
+fn parse_timezone(value: &str) -> Result<Tz, String> {
+    value.parse().map_err(|_| format!("unknown time zone '{}' (expected e.g. Europe/Berlin or UTC)", value))
+}
+
+// 为了兼容旧用法，不带单位的数字按秒处理，其它交给 humantime（1m30s、500ms ...）
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    if let Ok(secs) = value.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    humantime::parse_duration(value)
+        .map_err(|e| format!("{} (expected e.g. 30, 1m30s or 500ms)", e))
+}
+
+// 采样间隔为 0 时定时器没法推进节拍
+fn parse_nonzero_duration(value: &str) -> Result<Duration, String> {
+    match parse_duration(value)? {
+        duration if duration.is_zero() => Err("must be greater than zero".to_string()),
+        duration => Ok(duration),
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+// 魅族天气接口返回的时间是北京时间
+const WEATHER_TIMEZONE: Tz = chrono_tz::Asia::Shanghai;
+
+static TIMEZONE: OnceLock<Option<Tz>> = OnceLock::new();
+
+// --timezone 指定显示用的时区，没有指定时用系统本地时区
+pub fn init(timezone: Option<Tz>) {
+    let _ = TIMEZONE.set(timezone);
+}
+
+pub fn now() -> DateTime<FixedOffset> {
+    to_display(Utc::now())
+}
+
+fn to_display(time: DateTime<Utc>) -> DateTime<FixedOffset> {
+    match TIMEZONE.get().copied().flatten() {
+        Some(timezone) => time.with_timezone(&timezone).fixed_offset(),
+        None => time.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+// 魅族天气接口的 "YYYY-MM-DD HH:MM:SS"；格式不认识时返回 None
+pub fn china_time(value: &str) -> Option<DateTime<FixedOffset>> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .and_then(|time| WEATHER_TIMEZONE.from_local_datetime(&time).single())
+        .map(|time| time.fixed_offset())
+}
+
+// 换算到显示时区
+pub fn display(time: DateTime<FixedOffset>) -> String {
+    to_display(time.with_timezone(&Utc)).format("%Y-%m-%d %H:%M:%S").to_string()
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches};
+use serde::Deserialize;
+
+use crate::anonymize;
+use crate::cli::Cli;
+use crate::i18n::{t, tf};
+use crate::metrics;
+use crate::output;
+
+// 配置文件（TOML），命令行参数之外的长期设置放在这里。
+// 下面这几项也可以用命令行参数或 SYSDETAILS_* 环境变量设置，优先级：命令行 > 环境变量 > 配置文件 > 默认值
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub weather_provider: Option<String>,
+    // weather_fallback = "openweathermap,meizu"
+    pub weather_fallback: Option<String>,
+    pub weather_url: Option<String>,
+    pub city_id: Option<String>,
+    pub lang: Option<String>,
+    pub timezone: Option<String>,
+    pub interval: Option<String>,
+    pub units: Option<String>,
+    // 也可以写成 [[custom]] 表
+    #[serde(default, alias = "custom")]
+    pub custom_metrics: Vec<CustomMetric>,
+    // labels = { datacenter = "dc1", role = "trainer" }，和 --label 合并，同名时以命令行为准
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    // section_output = { weather = "/var/log/weather.jsonl" }，和 --section-output 合并
+    #[serde(default)]
+    pub section_output: BTreeMap<String, String>,
+}
+
+impl Config {
+    fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "weather_provider" => self.weather_provider.as_deref(),
+            "weather_fallback" => self.weather_fallback.as_deref(),
+            "weather_url" => self.weather_url.as_deref(),
+            "city_id" => self.city_id.as_deref(),
+            "lang" => self.lang.as_deref(),
+            "timezone" => self.timezone.as_deref(),
+            "interval" => self.interval.as_deref(),
+            "units" => self.units.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+// 可以分层设置的项，名称和命令行参数的 id 一致
+const LAYERED: &[&str] =
+    &["weather_provider", "weather_fallback", "weather_url", "city_id", "lang", "timezone", "interval", "units"];
+
+#[derive(Debug, Clone)]
+pub enum Source {
+    CommandLine,
+    Env(String),
+    File(PathBuf),
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::CommandLine => write!(f, "{}", t("config.source_cli")),
+            Source::Env(name) => write!(f, "{}", tf("config.source_env", &[name])),
+            Source::File(path) => write!(f, "{}", tf("config.source_file", &[&path.display()])),
+            Source::Default => write!(f, "{}", t("config.source_default")),
+        }
+    }
+}
+
+// 最终生效的一项设置和它的来源，--print-config 用
+#[derive(Debug, Clone)]
+pub struct ResolvedValue {
+    pub key: &'static str,
+    pub value: Option<String>,
+    pub source: Source,
+}
+
+// 先按命令行和环境变量解析一遍；命令行和环境变量都没给的项，把配置文件里的值当作参数插到最前面再解析一遍，
+// 这样配置文件里的值和命令行参数走同样的校验
+pub fn resolve(args: Vec<OsString>) -> Result<Cli, String> {
+    let matches = Cli::command().get_matches_from(args.clone());
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let path = cli.config.clone().or_else(default_path);
+    let settings = load(cli.config.as_deref())?;
+
+    let command = Cli::command();
+    let mut injected = Vec::new();
+    let mut sources = Vec::new();
+    for &key in LAYERED {
+        let arg = command.get_arguments().find(|arg| arg.get_id() == key).expect("layered setting");
+        let source = match matches.value_source(key) {
+            Some(ValueSource::CommandLine) => Source::CommandLine,
+            Some(ValueSource::EnvVariable) => {
+                let name = arg.get_env().map(|name| name.to_string_lossy().into_owned());
+                Source::Env(name.unwrap_or_default())
+            }
+            _ => match (settings.get(key), &path) {
+                (Some(value), Some(path)) => {
+                    let long = arg.get_long().unwrap_or(key);
+                    injected.push(OsString::from(format!("--{}={}", long, value)));
+                    Source::File(path.clone())
+                }
+                _ => Source::Default,
+            },
+        };
+        sources.push((key, source));
+    }
+
+    let (mut cli, matches) = if injected.is_empty() {
+        (cli, matches)
+    } else {
+        let mut args = args.into_iter();
+        let args: Vec<OsString> = args.next().into_iter().chain(injected).chain(args).collect();
+        let matches = Cli::command().try_get_matches_from(args).map_err(|e| config_error(&path, &e))?;
+        (Cli::from_arg_matches(&matches).map_err(|e| config_error(&path, &e))?, matches)
+    };
+    cli.resolved = resolved_values(&matches, sources);
+    cli.settings = settings;
+    if cli.anonymize && cli.anonymize_salt.is_none() {
+        cli.anonymize_salt = Some(anonymize::install_salt()?);
+    }
+
+    Ok(cli)
+}
+
+fn resolved_values(matches: &clap::ArgMatches, sources: Vec<(&'static str, Source)>) -> Vec<ResolvedValue> {
+    sources
+        .into_iter()
+        .map(|(key, source)| ResolvedValue {
+            key,
+            // 逗号分隔的列表项合回一个字符串
+            value: matches.get_raw(key).map(|values| {
+                values.map(|value| value.to_string_lossy().into_owned()).collect::<Vec<_>>().join(",")
+            }),
+            source,
+        })
+        .collect()
+}
+
+// clap 的错误信息第一行已经说明了哪个值不对，后面的用法提示对配置文件没有意义
+fn config_error(path: &Option<PathBuf>, e: &clap::Error) -> String {
+    let message = e.to_string();
+    let message = message.lines().next().unwrap_or_default().trim_start_matches("error: ");
+    match path {
+        Some(path) => format!("{}: {}", path.display(), message),
+        None => message.to_string(),
+    }
+}
+
+// --print-config：每项最终的值和来源，格式上就是一份可以直接用的配置文件
+pub fn print(cli: &Cli) {
+    let path = cli.config.clone().or_else(default_path);
+    match &path {
+        Some(path) => println!("# {}", tf("config.file", &[&path.display()])),
+        None => println!("# {}", t("config.no_file")),
+    }
+    println!("# {}", t("config.precedence"));
+    for value in &cli.resolved {
+        let text = match &value.value {
+            Some(text) => format!("{} = {:?}", value.key, text),
+            None => format!("# {} =", value.key),
+        };
+        println!("{:<32} # {}", text, value.source);
+    }
+    if !cli.settings.custom_metrics.is_empty() {
+        println!("# {}", tf("config.custom_metrics", &[&cli.settings.custom_metrics.len()]));
+    }
+    let labels = cli.labels();
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels.iter().map(|(key, value)| format!("{} = {:?}", key, value)).collect();
+        println!("labels = {{ {} }}", labels.join(", "));
+    }
+}
+
+// custom_metrics = [{ name = "fan_rpm", command = "cat /sys/...", timeout = "2s" }]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomMetric {
+    pub name: String,
+    pub command: String,
+    pub timeout: Option<String>,
+}
+
+// 指定了 --config 时文件必须存在；否则读默认位置，不存在就用空配置
+pub fn load(path: Option<&Path>) -> Result<Config, String> {
+    let (path, required) = match path {
+        Some(path) => (path.to_path_buf(), true),
+        None => match default_path() {
+            Some(path) => (path, false),
+            None => return Ok(Config::default()),
+        },
+    };
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+
+    let config: Config = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    for name in config.labels.keys() {
+        metrics::check_label_name(name).map_err(|e| format!("{}: labels: {}", path.display(), e))?;
+    }
+    for name in config.section_output.keys() {
+        output::check_section(name).map_err(|e| format!("{}: section_output: {}", path.display(), e))?;
+    }
+
+    Ok(config)
+}
+
+fn default_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+// Linux/macOS: $XDG_CONFIG_HOME 或 ~/.config 下的 rust-system-details；
+// Windows: %APPDATA%\rust-system-details
+pub fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        PathBuf::from(env::var_os("APPDATA")?)
+    } else {
+        match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        }
+    };
+
+    Some(base.join("rust-system-details"))
+}
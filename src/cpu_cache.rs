@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+// 一个核心能用到的各级缓存；L3 通常是整颗 CPU 共享的，这里给出的也是它的总量
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CacheInfo {
+    pub level: u8,
+    // Data / Instruction / Unified
+    pub kind: String,
+    pub size_bytes: u64,
+}
+
+impl CacheInfo {
+    // 和 lscpu 一样写成 L1d、L1i、L2、L3
+    pub fn label(&self) -> String {
+        let suffix = match self.kind.as_str() {
+            "Data" => "d",
+            "Instruction" => "i",
+            _ => "",
+        };
+        format!("L{}{}", self.level, suffix)
+    }
+}
+
+// 只看 cpu0：各核心的 L1/L2 大小一样，按核心累加反而和 lscpu 的含义不同
+#[cfg(target_os = "linux")]
+pub fn read() -> Vec<CacheInfo> {
+    use std::fs;
+
+    let Ok(entries) = fs::read_dir("/sys/devices/system/cpu/cpu0/cache") else {
+        return Vec::new();
+    };
+    let mut caches: Vec<CacheInfo> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("index"))
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let read = |name: &str| fs::read_to_string(dir.join(name)).ok().map(|text| text.trim().to_string());
+            Some(CacheInfo {
+                level: read("level")?.parse().ok()?,
+                kind: read("type")?,
+                size_bytes: parse_size(&read("size")?)?,
+            })
+        })
+        .collect();
+
+    caches.sort_by(|a, b| (a.level, &a.kind).cmp(&(b.level, &b.kind)));
+    caches
+}
+
+// sysfs 里的大小形如 "32K"、"1024K"、"32768K"
+#[cfg(target_os = "linux")]
+fn parse_size(size: &str) -> Option<u64> {
+    let (number, multiplier) = match size.chars().last()? {
+        'K' => (&size[..size.len() - 1], 1024),
+        'M' => (&size[..size.len() - 1], 1024 * 1024),
+        'G' => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+        _ => (size, 1),
+    };
+    Some(number.parse::<u64>().ok()? * multiplier)
+}
+
+// 每个核心的每一级缓存各有一条记录，按 (级别, 类型) 去重后保留一条
+#[cfg(target_os = "windows")]
+pub fn read() -> Vec<CacheInfo> {
+    use std::mem;
+    use std::ptr;
+
+    use windows_sys::Win32::System::SystemInformation::{
+        CacheData, CacheInstruction, GetLogicalProcessorInformation, RelationCache,
+        SYSTEM_LOGICAL_PROCESSOR_INFORMATION,
+    };
+
+    let mut length = 0u32;
+    // SAFETY: 第一次调用只取需要的缓冲区大小
+    unsafe { GetLogicalProcessorInformation(ptr::null_mut(), &mut length) };
+    let count = length as usize / mem::size_of::<SYSTEM_LOGICAL_PROCESSOR_INFORMATION>();
+    if count == 0 {
+        return Vec::new();
+    }
+    let mut buffer = vec![SYSTEM_LOGICAL_PROCESSOR_INFORMATION::default(); count];
+    // SAFETY: buffer 的长度正好是上面返回的 length
+    if unsafe { GetLogicalProcessorInformation(buffer.as_mut_ptr(), &mut length) } == 0 {
+        return Vec::new();
+    }
+
+    let mut caches: Vec<CacheInfo> = Vec::new();
+    for info in buffer.iter().filter(|info| info.Relationship == RelationCache) {
+        // SAFETY: Relationship 为 RelationCache 时联合体里是 CACHE_DESCRIPTOR
+        let cache = unsafe { info.Anonymous.Cache };
+        let kind = if cache.Type == CacheData {
+            "Data"
+        } else if cache.Type == CacheInstruction {
+            "Instruction"
+        } else {
+            "Unified"
+        };
+        if !caches.iter().any(|known| known.level == cache.Level && known.kind == kind) {
+            caches.push(CacheInfo {
+                level: cache.Level,
+                kind: kind.to_string(),
+                size_bytes: cache.Size as u64,
+            });
+        }
+    }
+
+    caches.sort_by(|a, b| (a.level, &a.kind).cmp(&(b.level, &b.kind)));
+    caches
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn read() -> Vec<CacheInfo> {
+    Vec::new()
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+// 开机以来 CPU 因过热被降频的次数，来自 /sys/devices/system/cpu/cpu*/thermal_throttle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CpuThrottle {
+    pub core_throttle_count: u64,
+    pub package_throttle_count: u64,
+}
+
+// package 的计数在同一颗 CPU 的每个核心下都有一份，按 physical_package_id 去重后再相加；
+// 没有 thermal_throttle 目录（虚拟机、非 Intel 平台）时返回 None
+#[cfg(target_os = "linux")]
+pub fn read() -> Option<CpuThrottle> {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::Path;
+
+    let read_count = |path: &Path| -> Option<u64> { fs::read_to_string(path).ok()?.trim().parse().ok() };
+
+    let mut found = false;
+    let mut core_throttle_count = 0;
+    let mut packages = BTreeMap::new();
+
+    for entry in fs::read_dir("/sys/devices/system/cpu").ok()?.flatten() {
+        let name = entry.file_name();
+        let Some(id) = name.to_str().and_then(|name| name.strip_prefix("cpu")) else {
+            continue;
+        };
+        if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+
+        let dir = entry.path();
+        let throttle = dir.join("thermal_throttle");
+        if let Some(count) = read_count(&throttle.join("core_throttle_count")) {
+            found = true;
+            core_throttle_count += count;
+        }
+        if let Some(count) = read_count(&throttle.join("package_throttle_count")) {
+            found = true;
+            let package = read_count(&dir.join("topology/physical_package_id")).unwrap_or(0);
+            packages.insert(package, count);
+        }
+    }
+
+    found.then(|| CpuThrottle {
+        core_throttle_count,
+        package_throttle_count: packages.values().sum(),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read() -> Option<CpuThrottle> {
+    None
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+// 一段时间内各类 CPU 时间占的百分比，来自前后两次 /proc/stat 的差值
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CpuTimes {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CpuBreakdown {
+    pub overall: CpuTimes,
+    pub per_core: Vec<CpuTimes>,
+}
+
+// /proc/stat 里一行 cpu 的累计节拍数
+#[derive(Clone, Copy, Default)]
+struct Ticks([u64; 8]);
+
+pub struct StatSnapshot {
+    overall: Ticks,
+    per_core: Vec<Ticks>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn snapshot() -> Option<StatSnapshot> {
+    let stat = std::fs::read_to_string("/proc/stat").ok()?;
+    let mut overall = None;
+    let mut per_core = Vec::new();
+
+    for line in stat.lines() {
+        let mut columns = line.split_whitespace();
+        let Some(label) = columns.next() else {
+            continue;
+        };
+        if !label.starts_with("cpu") {
+            continue;
+        }
+
+        // user nice system idle iowait irq softirq steal，后面的 guest 已经算在 user 里了
+        let mut ticks = Ticks::default();
+        for (slot, value) in ticks.0.iter_mut().zip(columns) {
+            *slot = value.parse().unwrap_or(0);
+        }
+
+        if label == "cpu" {
+            overall = Some(ticks);
+        } else {
+            per_core.push(ticks);
+        }
+    }
+
+    Some(StatSnapshot {
+        overall: overall?,
+        per_core,
+    })
+}
+
+// 其它平台没有 /proc/stat，只用 sysinfo 的总使用率
+#[cfg(not(target_os = "linux"))]
+pub fn snapshot() -> Option<StatSnapshot> {
+    None
+}
+
+pub fn breakdown(before: &StatSnapshot, after: &StatSnapshot) -> CpuBreakdown {
+    CpuBreakdown {
+        overall: percentages(before.overall, after.overall),
+        per_core: before
+            .per_core
+            .iter()
+            .zip(&after.per_core)
+            .map(|(before, after)| percentages(*before, *after))
+            .collect(),
+    }
+}
+
+fn percentages(before: Ticks, after: Ticks) -> CpuTimes {
+    let mut delta = [0u64; 8];
+    for (i, slot) in delta.iter_mut().enumerate() {
+        *slot = after.0[i].saturating_sub(before.0[i]);
+    }
+
+    let total = delta.iter().sum::<u64>().max(1) as f32;
+    let percent = |i: usize| delta[i] as f32 * 100.0 / total;
+
+    CpuTimes {
+        user: percent(0),
+        nice: percent(1),
+        system: percent(2),
+        idle: percent(3),
+        iowait: percent(4),
+        irq: percent(5),
+        softirq: percent(6),
+        steal: percent(7),
+    }
+}
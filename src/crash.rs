@@ -0,0 +1,52 @@
+use std::cell::{Cell, RefCell};
+use std::panic;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::i18n::t;
+
+// 当前线程正在采集的报告里已经拿到的部分；collect_report 开始时清空，每完成一部分记一次
+thread_local! {
+    static PARTIAL: RefCell<Map<String, Value>> = RefCell::new(Map::new());
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn reset() {
+    PARTIAL.with(|partial| partial.borrow_mut().clear());
+}
+
+pub fn record(name: &str, value: &impl Serialize) {
+    let Ok(value) = serde_json::to_value(value) else {
+        return;
+    };
+    PARTIAL.with(|partial| {
+        partial.borrow_mut().insert(name.to_string(), value);
+    });
+}
+
+// section 里的 panic 会被 catch_unwind 接住记成错误，报告照常输出，这时不用再打印一遍
+pub fn catching<T>(collect: impl FnOnce() -> T) -> T {
+    let previous = CATCHING.with(|catching| catching.replace(true));
+    let result = collect();
+    CATCHING.with(|catching| catching.set(previous));
+    result
+}
+
+// 先照常打印 panic 信息，再把已经采集到的部分以 JSON 写到 stderr，崩溃时诊断信息不至于全丢
+pub fn install_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        if CATCHING.with(Cell::get) {
+            return;
+        }
+
+        let partial = PARTIAL.with(|partial| partial.try_borrow().ok().map(|partial| partial.clone()));
+        if let Some(partial) = partial.filter(|partial| !partial.is_empty()) {
+            if let Ok(json) = serde_json::to_string_pretty(&Value::Object(partial)) {
+                eprintln!("{}\n{}", t("crash.partial_report"), json);
+            }
+        }
+    }));
+}
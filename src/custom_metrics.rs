@@ -0,0 +1,112 @@
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::CustomMetric;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CustomMetricValue {
+    pub name: String,
+    // 输出能解析成数字时填 value，是 JSON 对象或数组时放在 json 里，否则原样放在 text 里
+    pub value: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+// 逐个执行配置里的命令；失败或超时只记在这一项的 error 里，不影响其它指标
+pub fn collect(metrics: &[CustomMetric]) -> Vec<CustomMetricValue> {
+    metrics.iter().map(run_metric).collect()
+}
+
+fn run_metric(metric: &CustomMetric) -> CustomMetricValue {
+    let mut value = CustomMetricValue {
+        name: metric.name.clone(),
+        value: None,
+        json: None,
+        text: None,
+        error: None,
+    };
+
+    let timeout = match metric.timeout.as_deref().map(humantime::parse_duration).transpose() {
+        Ok(timeout) => timeout.unwrap_or(DEFAULT_TIMEOUT),
+        Err(e) => {
+            value.error = Some(format!("timeout: {}", e));
+            return value;
+        }
+    };
+
+    match run_with_timeout(&metric.command, timeout) {
+        Ok(stdout) => {
+            let stdout = stdout.trim();
+            match (stdout.parse::<f64>(), serde_json::from_str::<Value>(stdout)) {
+                (Ok(number), _) => value.value = Some(number),
+                (_, Ok(json @ (Value::Object(_) | Value::Array(_)))) => value.json = Some(json),
+                _ => value.text = Some(stdout.to_string()),
+            }
+        }
+        Err(e) => value.error = Some(e),
+    }
+
+    value
+}
+
+fn run_with_timeout(command: &str, timeout: Duration) -> Result<String, String> {
+    let mut child = shell(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    // 输出放到单独的线程里读，避免管道写满后子进程卡住
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("timed out after {}", humantime::format_duration(timeout)));
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    let output = reader
+        .join()
+        .map_err(|_| "failed to read output".to_string())?
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("exited with {}", status));
+    }
+
+    Ok(output)
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    shell
+}
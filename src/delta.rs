@@ -0,0 +1,85 @@
+use serde_json::{json, Map, Value};
+
+// watch 模式下的增量输出：第一次和每隔 full_every 次输出完整快照，其余只输出变化的字段
+pub struct DeltaEncoder {
+    full_every: u64,
+    emitted: u64,
+    previous: Option<Value>,
+}
+
+impl DeltaEncoder {
+    pub fn new(full_every: u64) -> Self {
+        DeltaEncoder {
+            full_every,
+            emitted: 0,
+            previous: None,
+        }
+    }
+
+    pub fn encode(&mut self, current: Value) -> Value {
+        let full = match &self.previous {
+            None => true,
+            Some(_) => self.full_every > 0 && self.emitted.is_multiple_of(self.full_every),
+        };
+
+        let message = match (&self.previous, full) {
+            (Some(previous), false) => json!({ "type": "delta", "patch": json_patch(previous, &current) }),
+            _ => json!({ "type": "full", "report": &current }),
+        };
+
+        self.previous = Some(current);
+        self.emitted += 1;
+
+        message
+    }
+}
+
+// 生成 RFC 6902 风格的 add/remove/replace 操作列表
+pub fn json_patch(old: &Value, new: &Value) -> Vec<Value> {
+    let mut ops = Vec::new();
+    diff(old, new, "", &mut ops);
+    ops
+}
+
+fn diff(old: &Value, new: &Value, path: &str, ops: &mut Vec<Value>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => diff_objects(old, new, path, ops),
+        (Value::Array(old), Value::Array(new)) => {
+            for (i, (a, b)) in old.iter().zip(new.iter()).enumerate() {
+                diff(a, b, &format!("{}/{}", path, i), ops);
+            }
+            // 从后往前删除，保证下标在应用补丁时仍然有效
+            for i in (new.len()..old.len()).rev() {
+                ops.push(json!({ "op": "remove", "path": format!("{}/{}", path, i) }));
+            }
+            for (i, value) in new.iter().enumerate().skip(old.len()) {
+                ops.push(json!({ "op": "add", "path": format!("{}/{}", path, i), "value": value }));
+            }
+        }
+        (old, new) if old != new => {
+            ops.push(json!({ "op": "replace", "path": path, "value": new }));
+        }
+        _ => {}
+    }
+}
+
+fn diff_objects(old: &Map<String, Value>, new: &Map<String, Value>, path: &str, ops: &mut Vec<Value>) {
+    for (key, old_value) in old {
+        let child = format!("{}/{}", path, escape_pointer(key));
+        match new.get(key) {
+            Some(new_value) => diff(old_value, new_value, &child, ops),
+            None => ops.push(json!({ "op": "remove", "path": child })),
+        }
+    }
+
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            let child = format!("{}/{}", path, escape_pointer(key));
+            ops.push(json!({ "op": "add", "path": child, "value": new_value }));
+        }
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
@@ -0,0 +1,72 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::system::DiskInfo;
+
+// 写入探测用的数据量，一个页大小
+const PROBE_SIZE: usize = 4096;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DiskLatency {
+    pub read_ms: Option<f64>,
+    pub write_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// 主动测试：读是列一次挂载点目录（同步的元数据读取，可能命中缓存）；
+// write 为 true 时再在挂载点下建临时文件，写 4 KiB 并 fsync，测完删除
+pub fn probe(disks: &mut [DiskInfo], write: bool) {
+    for disk in disks {
+        let mount_point = Path::new(&disk.mount_point);
+        let mut latency = DiskLatency {
+            read_ms: None,
+            write_ms: None,
+            error: None,
+        };
+
+        match timed(|| fs::read_dir(mount_point).map(|entries| entries.count())) {
+            Ok(ms) => latency.read_ms = Some(ms),
+            Err(e) => latency.error = Some(e.to_string()),
+        }
+
+        if write {
+            match probe_write(mount_point) {
+                Ok(ms) => latency.write_ms = Some(ms),
+                // 只读或没有写权限的挂载点不测写入
+                Err(e) if matches!(e.kind(), ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem) => {}
+                Err(e) => latency.error = Some(e.to_string()),
+            }
+        }
+
+        disk.latency = Some(latency);
+    }
+}
+
+fn probe_write(mount_point: &Path) -> std::io::Result<f64> {
+    let path = mount_point.join(format!(".rust-system-details-latency-{}", process::id()));
+    let mut file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+
+    let result = timed(|| {
+        file.write_all(&[0u8; PROBE_SIZE])?;
+        file.sync_all()
+    });
+    drop(file);
+    // 测量失败也要删掉临时文件
+    let removed = fs::remove_file(&path);
+
+    let ms = result?;
+    removed?;
+    Ok(ms)
+}
+
+fn timed<T>(run: impl FnOnce() -> std::io::Result<T>) -> std::io::Result<f64> {
+    let start = Instant::now();
+    run()?;
+    Ok(start.elapsed().as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+// 每个块设备累计的 I/O 忙碌时间（毫秒），/proc/diskstats 第 10 个统计字段（io_ticks），
+// 以及开机以来读写的扇区数（第 3、7 个统计字段）
+pub struct IoSnapshot {
+    io_ticks: HashMap<String, u64>,
+    sectors: HashMap<String, (u64, u64)>,
+}
+
+// /proc/diskstats 的扇区固定按 512 字节计，和设备实际的扇区大小无关
+const SECTOR_SIZE: u64 = 512;
+
+#[cfg(target_os = "linux")]
+pub fn snapshot() -> Option<IoSnapshot> {
+    let stats = std::fs::read_to_string("/proc/diskstats").ok()?;
+    let mut io_ticks = HashMap::new();
+    let mut sectors = HashMap::new();
+
+    // 每行：major minor name 之后是统计字段，读扇区数是第 6 列，写扇区数是第 10 列，io_ticks 是第 13 列
+    for line in stats.lines() {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let column = |index: usize| columns.get(index).and_then(|value| value.parse::<u64>().ok());
+        let Some(name) = columns.get(2) else {
+            continue;
+        };
+        if let Some(ticks) = column(12) {
+            io_ticks.insert(name.to_string(), ticks);
+        }
+        if let (Some(read), Some(written)) = (column(5), column(9)) {
+            sectors.insert(name.to_string(), (read, written));
+        }
+    }
+
+    Some(IoSnapshot { io_ticks, sectors })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn snapshot() -> Option<IoSnapshot> {
+    None
+}
+
+// 和 iostat 的 %util 算法一样：窗口内忙碌时间占窗口长度的比例
+pub fn busy_percent(before: &IoSnapshot, after: &IoSnapshot, window: Duration, device: &str) -> Option<f64> {
+    let name = kernel_name(device)?;
+    let busy = after.io_ticks.get(&name)?.saturating_sub(*before.io_ticks.get(&name)?);
+    let window_ms = window.as_secs_f64() * 1000.0;
+    if window_ms <= 0.0 {
+        return None;
+    }
+
+    Some((busy as f64 * 100.0 / window_ms).min(100.0))
+}
+
+// 开机以来读、写的字节数
+pub fn total_bytes(snapshot: &IoSnapshot, device: &str) -> Option<(u64, u64)> {
+    let (read, written) = *snapshot.sectors.get(&kernel_name(device)?)?;
+    Some((read * SECTOR_SIZE, written * SECTOR_SIZE))
+}
+
+// /dev/vda1 → vda1；/dev/mapper/root 这类符号链接先解析成 /dev/dm-0
+fn kernel_name(device: &str) -> Option<String> {
+    let path = std::fs::canonicalize(device).unwrap_or_else(|_| device.into());
+    Some(path.file_name()?.to_str()?.to_string())
+}
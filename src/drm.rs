@@ -0,0 +1,99 @@
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+
+use crate::gpu::GpuInfo;
+
+// NVML 只认 NVIDIA 的卡；AMD 和 Intel 的显卡从 /sys/class/drm 读，amdgpu 驱动能给出利用率、显存、温度和功耗，
+// Intel 核显一般只有频率。first_index 接在 NVIDIA 卡的序号后面，保证同一份报告里序号不重复
+#[cfg(target_os = "linux")]
+pub fn get_gpus_info(first_index: u32) -> Vec<GpuInfo> {
+    use std::fs;
+
+    use crate::system::bytes_to_gb;
+
+    let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    // card0、card1……；card0-DP-1 之类是显示接口，不是显卡
+    let mut cards: Vec<(u32, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let number = entry.file_name().to_str()?.strip_prefix("card")?.parse().ok()?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    cards.sort_by_key(|(number, _)| *number);
+
+    let mut gpus = Vec::new();
+    for (_, card) in cards {
+        let device = card.join("device");
+        let vendor = match read(&device.join("vendor")).as_deref() {
+            Some("0x1002") => "AMD",
+            Some("0x8086") => "Intel",
+            _ => continue,
+        };
+        // 没有 UUID，用 PCI 地址代替（0000:03:00.0）
+        let Some(address) = fs::canonicalize(&device)
+            .ok()
+            .and_then(|path| Some(path.file_name()?.to_str()?.to_string()))
+        else {
+            continue;
+        };
+        let hwmon = fs::read_dir(device.join("hwmon")).ok().and_then(|mut dirs| Some(dirs.next()?.ok()?.path()));
+        let hwmon_value = |file: &str| hwmon.as_ref().and_then(|dir| number(&dir.join(file)));
+        let name = read(&device.join("product_name"))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("{} GPU {}", vendor, read(&device.join("device")).unwrap_or_default()));
+
+        let memory_used = number(&device.join("mem_info_vram_used"));
+        let memory_total = number(&device.join("mem_info_vram_total"));
+
+        gpus.push(GpuInfo {
+            index: first_index + gpus.len() as u32,
+            pci_bus_id: Some(address.clone()),
+            uuid: address,
+            vendor: Some(vendor.to_string()),
+            name: Some(name),
+            // 温度是毫摄氏度，功耗是微瓦，显存是字节
+            gpu_temperature: hwmon_value("temp1_input").map(|millis| (millis / 1000) as u32),
+            power_usage: hwmon_value("power1_average").map(|micros| micros as f64 / 1_000_000.0),
+            utilization: number(&device.join("gpu_busy_percent")).map(|percent| percent as u32),
+            memory_used: memory_used.map(bytes_to_gb),
+            memory_total: memory_total.map(bytes_to_gb),
+            memory_used_bytes: memory_used,
+            memory_total_bytes: memory_total,
+            core_clock: core_clock(&card, &device),
+            ..Default::default()
+        });
+    }
+
+    gpus
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_gpus_info(_first_index: u32) -> Vec<GpuInfo> {
+    Vec::new()
+}
+
+// Intel 的 i915 直接给出当前频率；amdgpu 的 pp_dpm_sclk 每行一个档位，当前档位后面带 *，例如 "1: 1800Mhz *"
+#[cfg(target_os = "linux")]
+fn core_clock(card: &Path, device: &Path) -> Option<u32> {
+    if let Some(clock) = number(&card.join("gt_cur_freq_mhz")) {
+        return Some(clock as u32);
+    }
+
+    let levels = read(&device.join("pp_dpm_sclk"))?;
+    let current = levels.lines().find(|line| line.trim_end().ends_with('*'))?;
+    let (_, clock) = current.split_once(':')?;
+    clock.trim_end_matches(['*', ' ']).trim().to_lowercase().strip_suffix("mhz")?.parse().ok()
+}
+
+#[cfg(target_os = "linux")]
+fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok().map(|text| text.trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn number(path: &Path) -> Option<u64> {
+    read(path)?.parse().ok()
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+// 判断磁盘是否加密：Linux 看 device-mapper 的 UUID 是否以 CRYPT- 开头（LUKS/dm-crypt），
+// Windows 调 manage-bde，macOS 调 fdesetup；判断不了时返回 None
+#[cfg(target_os = "linux")]
+pub fn is_encrypted(device: &str, _mount_point: &Path) -> Option<bool> {
+    use std::fs;
+
+    let device = fs::canonicalize(device).ok()?;
+    let name = device.file_name()?.to_str()?.to_string();
+    let block = Path::new("/sys/class/block").join(&name);
+    if !block.exists() {
+        return None;
+    }
+
+    Some(dm_crypt_in_stack(&block, 0))
+}
+
+// LVM 之类建在 LUKS 之上的设备，要顺着 slaves 往下找
+#[cfg(target_os = "linux")]
+fn dm_crypt_in_stack(block: &Path, depth: usize) -> bool {
+    use std::fs;
+
+    if depth > 8 {
+        return false;
+    }
+
+    if let Ok(uuid) = fs::read_to_string(block.join("dm/uuid")) {
+        if uuid.starts_with("CRYPT-") {
+            return true;
+        }
+    }
+
+    // 分区本身没有 slaves，但所在的整块磁盘可能是 dm 设备
+    let Ok(slaves) = fs::read_dir(block.join("slaves")) else {
+        return false;
+    };
+
+    slaves
+        .flatten()
+        .any(|slave| dm_crypt_in_stack(&Path::new("/sys/class/block").join(slave.file_name()), depth + 1))
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_encrypted(_device: &str, mount_point: &Path) -> Option<bool> {
+    use std::process::Command;
+
+    // mount_point 形如 C:\，manage-bde 需要 C:
+    let volume = mount_point.to_str()?.trim_end_matches('\\');
+    let output = Command::new("manage-bde").args(["-status", volume]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let status = stdout
+        .lines()
+        .find(|line| line.trim_start().starts_with("Conversion Status"))?
+        .split(':')
+        .nth(1)?
+        .trim()
+        .to_string();
+
+    Some(status != "Fully Decrypted")
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_encrypted(_device: &str, mount_point: &Path) -> Option<bool> {
+    use std::process::Command;
+
+    // fdesetup 只报告启动卷
+    if mount_point != Path::new("/") {
+        return None;
+    }
+
+    let output = Command::new("fdesetup").arg("status").output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.contains("FileVault is On") {
+        Some(true)
+    } else if stdout.contains("FileVault is Off") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub fn is_encrypted(_device: &str, _mount_point: &Path) -> Option<bool> {
+    None
+}
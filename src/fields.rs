@@ -0,0 +1,270 @@
+use serde_json::{Map, Value};
+
+use crate::i18n::tf;
+
+// 数组元素除了按下标，也可以按这些字段的值来选，例如 disks./ 选挂载点为 / 的磁盘
+const ID_KEYS: &[&str] = &["mount_point", "uuid", "name", "section"];
+
+// 路径里找不到原名时再试的简写，例如 gpu.0.temperature 等同于 gpus.0.gpu_temperature
+const ALIASES: &[(&str, &str)] = &[("gpu", "gpus"), ("temperature", "gpu_temperature")];
+
+// 不在报告顶层的常用数据的简写，开头整段替换成完整路径，例如 memory.used_percent 等同于 system.memory.used_percent
+const SHORTCUTS: &[(&str, &str)] = &[
+    ("cpu.average", "system.average_cpu_usage"),
+    ("cpu.power", "system.cpu_power_watts"),
+    ("memory", "system.memory"),
+    ("swap", "system.swap"),
+    ("disks", "system.disks"),
+];
+
+fn expand(path: &str) -> String {
+    for (short, full) in SHORTCUTS {
+        if let Some(rest) = path.strip_prefix(short).filter(|rest| rest.is_empty() || rest.starts_with('.')) {
+            return format!("{}{}", full, rest);
+        }
+    }
+    path.to_string()
+}
+
+// 报告顶层的字段，--fields 的第一段必须是其中之一（或 ALIASES 里的简写）
+pub const TOP_LEVEL: &[&str] = &[
+    "collected_at",
+    "collected_at_utc",
+    "gpus",
+    "gpu_driver",
+    "system",
+    "weather",
+    "memory_modules",
+    "kernel_params",
+    "net_top",
+    "custom_metrics",
+    "requires_privileges",
+    "errors",
+    "health",
+    "meta",
+];
+
+// --fields 只用到其中一部分数据时，其它的采集步骤（1 秒 CPU 采样、NVML、天气请求）可以跳过
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Needs {
+    pub gpu: bool,
+    pub memory: bool,
+    pub cpu: bool,
+    pub disks: bool,
+    pub networks: bool,
+    pub weather: bool,
+}
+
+// system 下各字段由哪一项刷新得到；不在表里的（主机名、运行时间等）不需要额外刷新
+const SYSTEM_MEMORY: &[&str] = &[
+    "total_memory",
+    "used_memory",
+    "available_memory",
+    "used_memory_no_cache",
+    "total_swap",
+    "used_swap",
+    "memory",
+    "swap",
+    "memory_pressure",
+];
+const SYSTEM_CPU: &[&str] = &["average_cpu_usage", "cpu_power_watts", "cpu_times"];
+const SYSTEM_DISKS: &[&str] = &["disks", "disks_omitted", "duplicate_mounts", "empty_mounts", "disk_summary"];
+
+pub fn needs(fields: &[String]) -> Needs {
+    let mut needs = Needs {
+        gpu: false,
+        memory: false,
+        cpu: false,
+        disks: false,
+        networks: false,
+        weather: false,
+    };
+
+    for field in fields {
+        let field = expand(field);
+        let mut segments = field.split('.');
+        match (segments.next().unwrap_or_default(), segments.next()) {
+            ("gpus" | "gpu" | "gpu_driver", _) => needs.gpu = true,
+            ("weather", _) => needs.weather = true,
+            ("system", Some(key)) if SYSTEM_MEMORY.contains(&key) => needs.memory = true,
+            ("system", Some(key)) if SYSTEM_CPU.contains(&key) => needs.cpu = true,
+            ("system", Some(key)) if SYSTEM_DISKS.contains(&key) => needs.disks = true,
+            // 网卡速率在 CPU 的采样窗口里测
+            ("system", Some("networks")) => (needs.networks, needs.cpu) = (true, true),
+            ("system", Some(_)) => {}
+            ("system", None) => (needs.memory, needs.cpu, needs.disks) = (true, true, true),
+            // 这几项由各自的参数控制采集
+            ("memory_modules" | "kernel_params" | "net_top" | "custom_metrics", _) => {}
+            ("collected_at" | "collected_at_utc", _) => {}
+            // meta、errors 之类的汇总信息和整份报告有关，按默认的范围采集
+            _ => {
+                needs.gpu = true;
+                (needs.memory, needs.cpu, needs.disks) = (true, true, true);
+            }
+        }
+    }
+
+    needs
+}
+
+// --fields 的参数检查：第一段不是报告的顶层字段时列出可用的名称
+pub fn check_top_level(field: &str) -> Result<String, String> {
+    let expanded = expand(field);
+    let first = expanded.split('.').next().unwrap_or_default();
+    let known = TOP_LEVEL.contains(&first) || ALIASES.iter().any(|(short, _)| *short == first);
+    if !known {
+        return Err(tf("fields.unknown_top_level", &[&first, &TOP_LEVEL.join(", ")]));
+    }
+
+    Ok(field.to_string())
+}
+
+// 按点号路径取值：对象按 key，数组按下标、`*`（全部元素）或 ID_KEYS 里的字段值
+pub fn resolve(value: &Value, path: &str) -> Option<Value> {
+    let path = expand(path);
+    let segments: Vec<&str> = path.split('.').collect();
+    resolve_segments(value, &segments)
+}
+
+fn resolve_segments(value: &Value, segments: &[&str]) -> Option<Value> {
+    let Some((first, rest)) = segments.split_first() else {
+        return Some(value.clone());
+    };
+
+    match value {
+        Value::Object(map) => {
+            let child = map.get(*first).or_else(|| {
+                let (_, alias) = ALIASES.iter().find(|(short, _)| short == first)?;
+                map.get(*alias)
+            })?;
+            resolve_segments(child, rest)
+        }
+        Value::Array(items) if *first == "*" => items
+            .iter()
+            .map(|item| resolve_segments(item, rest))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        Value::Array(items) => {
+            let item = match first.parse::<usize>() {
+                Ok(index) => items.get(index)?,
+                Err(_) => items.iter().find(|item| {
+                    ID_KEYS
+                        .iter()
+                        .any(|key| item.get(key).and_then(Value::as_str) == Some(*first))
+                })?,
+            };
+            resolve_segments(item, rest)
+        }
+        _ => None,
+    }
+}
+
+// 只保留请求的字段，结果是 { "路径": 值 } 的扁平对象
+pub fn project(report: &Value, fields: &[String]) -> Result<Value, String> {
+    let mut projected = Map::new();
+
+    for field in fields {
+        match resolve(report, field) {
+            Some(value) => {
+                projected.insert(field.clone(), value);
+            }
+            None => return Err(unknown_field(report, field)),
+        }
+    }
+
+    Ok(Value::Object(projected))
+}
+
+pub fn unknown_field(report: &Value, field: &str) -> String {
+    let mut paths = Vec::new();
+    collect_paths(report, "", &mut paths);
+
+    // 先比较包含多少个相同的单词（system.memory.used 对 system.used_memory），再比较编辑距离
+    let words = |path: &str| -> Vec<String> { path.split(['.', '_']).map(str::to_lowercase).collect() };
+    let wanted = words(field);
+
+    let suggestion = paths
+        .iter()
+        .map(|path| {
+            let candidate = words(path);
+            let shared = wanted.iter().filter(|word| candidate.contains(word)).count();
+            (shared, levenshtein(field, path), path)
+        })
+        .filter(|(shared, distance, _)| *shared > 0 || *distance <= 3)
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    match suggestion {
+        Some((_, _, path)) => tf("fields.unknown_suggest", &[&field, path]),
+        None => tf("fields.unknown", &[&field]),
+    }
+}
+
+fn collect_paths(value: &Value, prefix: &str, paths: &mut Vec<String>) {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => map.iter().map(|(key, child)| (key.clone(), child)).collect(),
+        Value::Array(items) => items.iter().enumerate().map(|(i, child)| (i.to_string(), child)).collect(),
+        _ => return,
+    };
+
+    for (key, child) in children {
+        let path = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+        collect_paths(child, &path, paths);
+        paths.push(path);
+    }
+}
+
+// 展开成 (点号路径, 标量) 列表，供 CSV 等扁平格式使用
+pub fn flatten(value: &Value) -> Vec<(String, Value)> {
+    let mut out = Vec::new();
+    flatten_into(value, String::new(), &mut out);
+    out
+}
+
+fn flatten_into(value: &Value, prefix: String, out: &mut Vec<(String, Value)>) {
+    let join = |key: &str| if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                flatten_into(child, join(key), out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                flatten_into(child, join(&i.to_string()), out);
+            }
+        }
+        scalar => out.push((prefix, scalar.clone())),
+    }
+}
+
+// 数组元素在路径里的名字：有 ID_KEYS 里的字段时用它的值（disks./data），否则用下标；
+// 值里有点号时 resolve 没法解析，也用下标
+pub fn item_key(item: &Value, index: usize) -> String {
+    ID_KEYS
+        .iter()
+        .find_map(|key| item.get(key).and_then(Value::as_str))
+        .filter(|id| !id.is_empty() && !id.contains('.'))
+        .map_or(index.to_string(), str::to_string)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(row[j + 1])
+            };
+            previous = current;
+        }
+    }
+
+    row[b.len()]
+}
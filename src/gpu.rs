@@ -0,0 +1,867 @@
+use std::collections::{HashMap, HashSet};
+#[cfg(target_os = "linux")]
+use std::ffi::c_ulong;
+use std::fmt;
+use std::mem;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use nvml_wrapper::bitmasks::device::ThrottleReasons;
+use nvml_wrapper::{cuda_driver_version_major, cuda_driver_version_minor, Device, Nvml};
+use nvml_wrapper::enum_wrappers;
+use nvml_wrapper::enums::device::{SampleValue, UsedGpuMemory};
+use nvml_wrapper::error::NvmlError;
+use nvml_wrapper::structs::device::FieldId;
+use nvml_wrapper_sys::bindings::{
+    field_id, nvmlMemory_v2_t, nvmlReturn_enum_NVML_SUCCESS, NvmlLib, NVML_FAN_POLICY_MANUAL,
+};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{t, tf};
+use crate::nvidia_smi;
+use crate::privileges;
+use crate::processes::{self, GpuProcess, ProcessSort};
+use crate::system::bytes_to_gb;
+
+#[cfg(target_os = "windows")]
+const NVML_LIB_PATH: &str = "nvml.dll";
+#[cfg(not(target_os = "windows"))]
+const NVML_LIB_PATH: &str = "libnvidia-ml.so";
+
+// nvml-wrapper 没有封装的接口从这里取符号；整个进程只加载一次，watch/dmon 每轮采样都会用到
+static NVML_LIB: OnceLock<Option<NvmlLib>> = OnceLock::new();
+// 已经提示过的 (GPU 序号, 字段)；dmon、--watch 每轮都会读到同样的错值，只提示一次
+static IMPLAUSIBLE: Mutex<Option<HashSet<(u32, String)>>> = Mutex::new(None);
+
+fn nvml_lib() -> Option<&'static NvmlLib> {
+    // SAFETY: 和 nvml-wrapper 加载的是同一个库，只在需要时按名称取符号
+    NVML_LIB.get_or_init(|| unsafe { NvmlLib::new(NVML_LIB_PATH) }.ok()).as_ref()
+}
+
+// NVML_STRUCT_VERSION(Memory, 2) = sizeof(nvmlMemory_v2_t) | (2 << 24)
+const NVML_MEMORY_V2_VERSION: u32 = mem::size_of::<nvmlMemory_v2_t>() as u32 | (2 << 24);
+
+const THROTTLE_REASON_NAMES: &[(ThrottleReasons, &str)] = &[
+    (ThrottleReasons::GPU_IDLE, "GPU Idle"),
+    (ThrottleReasons::APPLICATIONS_CLOCKS_SETTING, "Applications Clocks Setting"),
+    (ThrottleReasons::SW_POWER_CAP, "Power Cap"),
+    (ThrottleReasons::HW_SLOWDOWN, "Hardware Slowdown"),
+    (ThrottleReasons::SYNC_BOOST, "Sync Boost"),
+    (ThrottleReasons::SW_THERMAL_SLOWDOWN, "Thermal Slowdown"),
+    (ThrottleReasons::HW_THERMAL_SLOWDOWN, "Hardware Thermal Slowdown"),
+    (ThrottleReasons::HW_POWER_BRAKE_SLOWDOWN, "Hardware Power Brake Slowdown"),
+    (ThrottleReasons::DISPLAY_CLOCK_SETTING, "Display Clock Setting"),
+];
+
+// 真正说明散热或供电有问题的降频原因，--crit-on-throttle 只看这些；空闲、应用时钟之类的不算
+pub const CRITICAL_THROTTLE_REASONS: &[&str] = &[
+    "Power Cap",
+    "Hardware Slowdown",
+    "Thermal Slowdown",
+    "Hardware Thermal Slowdown",
+    "Hardware Power Brake Slowdown",
+];
+
+pub const NVIDIA: &str = "NVIDIA";
+
+// 还没有样本时是空字符串
+#[derive(Debug, Clone, Default)]
+pub struct GpuSparklines {
+    pub temperature: String,
+    pub utilization: String,
+    pub core_clock: String,
+    pub memory_clock: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct GpuInfo {
+    pub index: u32,
+    pub uuid: String,
+    // 不换插槽就不会变，序号重启后可能变；NVML 和 nvidia-smi 写成 00000000:01:00.0，
+    // AMD/Intel 的卡是 sysfs 里的 0000:03:00.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pci_bus_id: Option<String>,
+    // "NVIDIA"、"AMD" 或 "Intel"；旧版本保存的报告里没有这一项，都是 NVIDIA 的卡
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    pub serial: Option<String>,
+    pub vbios_version: Option<String>,
+    pub name: Option<String>,
+    pub power_state: Option<PowerState>,
+    pub num_cores: Option<u32>,
+    pub memory_bus_width: Option<u32>,
+    pub core_clock: Option<u32>,
+    // 默认的应用时钟，当作基础频率和 core_clock 比较，看出是在加速还是降频；不支持应用时钟的卡为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_core_clock: Option<u32>,
+    pub memory_clock: Option<u32>,
+    pub application_clocks_set: Option<bool>,
+    // P0（最高性能）到 P15（最低），空闲的卡一般在 P8 左右
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performance_state: Option<u8>,
+    pub auto_boost: Option<bool>,
+    pub gpu_temperature: Option<u32>,
+    // 驱动给出的降频温度和关机温度，和当前温度比较就知道还有多少余量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temp_slowdown_threshold: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temp_shutdown_threshold: Option<u32>,
+    // 相对上一次采样的温度变化速度（C/s），只有 watch 模式会填
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_trend: Option<f64>,
+    // watch --sparkline 时最近几次采样的走势，只在文本输出里显示
+    #[serde(skip)]
+    pub sparklines: GpuSparklines,
+    // 第一个风扇的转速和目标转速（%）以及控制方式；被动散热的卡都是 None
+    pub fan_speed: Option<u32>,
+    pub fan_target: Option<u32>,
+    pub fan_policy: Option<FanPolicy>,
+    // 每个风扇的转速（%）；拿不到风扇数量的老驱动上只有第一个
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fan_speeds: Vec<u32>,
+    pub memory_temperature: Option<u32>,
+    pub utilization: Option<u32>,
+    // NVENC 编码会话数和所有会话的平均帧率、延迟（微秒）；不支持编码或被驱动限制的卡是 None
+    pub active_encoder_sessions: Option<u32>,
+    pub encoder_fps: Option<u32>,
+    pub encoder_latency_us: Option<u32>,
+    pub gpu_util_avg: Option<f64>,
+    pub gpu_util_peak: Option<u32>,
+    pub power_usage: Option<f64>,
+    pub power_limit: Option<u32>,
+    pub power_limit_min: Option<u32>,
+    pub power_limit_max: Option<u32>,
+    pub power_limit_default: Option<u32>,
+    pub memory_used: Option<f64>,
+    pub memory_reserved: Option<f64>,
+    pub memory_total: Option<f64>,
+    // 显存的精确字节数，JSON 里和上面的 GB 值一起给出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_used_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_total_bytes: Option<u64>,
+    // 最大的连续空闲显存块，排查“还有空闲显存却分配失败”用；NVML、nvidia-smi 和 cudaMemGetInfo
+    // 都只给空闲总量，目前总是 null，fragmentation 标明数据不可用
+    #[serde(default)]
+    pub largest_free_block_bytes: Option<u64>,
+    #[serde(default)]
+    pub fragmentation: Fragmentation,
+    // 驱动加载以来整张卡的 ECC 错误数，只有开启了 ECC 的卡才有；不可纠正的错误增多是硬件要坏的信号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecc_corrected: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecc_uncorrected: Option<u64>,
+    pub cpu_affinity: Vec<usize>,
+    pub throttle_reasons: Vec<String>,
+    pub processes: Vec<GpuProcess>,
+}
+
+// PCI 运行时电源管理的状态；混合显卡笔记本上独显不用时会被驱动挂起
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerState {
+    Active,
+    Suspended,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FanPolicy {
+    Auto,
+    Manual,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Fragmentation {
+    Available,
+    #[default]
+    Unavailable,
+}
+
+impl GpuInfo {
+    // 只有 NVIDIA 的卡能用 NVML 按序号再查询
+    pub fn is_nvidia(&self) -> bool {
+        self.vendor.as_deref().is_none_or(|vendor| vendor == NVIDIA)
+    }
+
+    pub fn suspended(&self) -> bool {
+        self.power_state == Some(PowerState::Suspended)
+    }
+
+    pub fn apply(&mut self, dynamic: GpuDynamic) {
+        self.gpu_temperature = dynamic.gpu_temperature;
+        self.core_clock = dynamic.core_clock;
+        self.memory_clock = dynamic.memory_clock;
+        self.utilization = dynamic.utilization;
+        self.power_usage = dynamic.power_usage;
+        self.memory_used = dynamic.memory_used;
+        self.memory_used_bytes = dynamic.memory_used_bytes;
+        self.throttle_reasons = dynamic.throttle_reasons;
+    }
+
+    // 总量为 0 或读不到时没有意义，返回 None
+    // v2 接口下 used 不含预留部分，百分比按程序实际能用的 total - reserved 算；v1 的 used 本身就含预留
+    pub fn memory_usage_percent(&self) -> Option<f64> {
+        let (used, total) = (self.memory_used?, self.memory_total?);
+        let usable = total - self.memory_reserved.unwrap_or(0.0);
+        (usable > 0.0).then(|| used * 100.0 / usable)
+    }
+}
+
+// 多卡机器上所有 GPU 的合计；读不到的卡不计入对应的和
+#[derive(Debug, Serialize)]
+pub struct GpuSummary {
+    pub count: usize,
+    pub memory_total: f64,
+    pub memory_used: f64,
+    pub power_usage: f64,
+    pub max_temperature: Option<u32>,
+}
+
+impl GpuSummary {
+    pub fn new(gpus: &[GpuInfo]) -> Self {
+        GpuSummary {
+            count: gpus.len(),
+            memory_total: gpus.iter().filter_map(|gpu| gpu.memory_total).sum(),
+            memory_used: gpus.iter().filter_map(|gpu| gpu.memory_used).sum(),
+            power_usage: gpus.iter().filter_map(|gpu| gpu.power_usage).sum(),
+            max_temperature: gpus.iter().filter_map(|gpu| gpu.gpu_temperature).max(),
+        }
+    }
+}
+
+// 每次采样都会变的几项，高频轮询时只查这些
+#[derive(Debug, Default)]
+pub struct GpuDynamic {
+    pub gpu_temperature: Option<u32>,
+    pub core_clock: Option<u32>,
+    pub memory_clock: Option<u32>,
+    pub utilization: Option<u32>,
+    pub power_usage: Option<f64>,
+    pub memory_used: Option<f64>,
+    pub memory_used_bytes: Option<u64>,
+    pub throttle_reasons: Vec<String>,
+}
+
+struct VramInfo {
+    total: u64,
+    used: u64,
+    reserved: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum GpuError {
+    Nvml(NvmlError),
+    NoSuchGpu { index: u32, count: u32 },
+    Smi(String),
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuError::Nvml(e) => match nvml_hint(e) {
+                Some(hint) => write!(f, "{} ({})", t(hint), e),
+                None => write!(f, "{}", e),
+            },
+            GpuError::NoSuchGpu { index, count } => write!(f, "{}", tf("gpu.no_such_gpu", &[index, count])),
+            GpuError::Smi(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl GpuError {
+    // 机器上没有 NVIDIA 的驱动或者 NVML 库
+    pub fn no_driver(&self) -> bool {
+        matches!(
+            self,
+            GpuError::Nvml(NvmlError::LibloadingError(_) | NvmlError::LibraryNotFound | NvmlError::DriverNotLoaded)
+        )
+    }
+}
+
+// 常见的 NVML 错误统一在这里换成可以照着处理的提示，原始错误放在括号里
+fn nvml_hint(e: &NvmlError) -> Option<&'static str> {
+    match e {
+        NvmlError::LibloadingError(_) | NvmlError::LibraryNotFound => Some("nvml.library_not_found"),
+        NvmlError::DriverNotLoaded => Some("nvml.driver_not_loaded"),
+        NvmlError::FailedToLoadSymbol(_) | NvmlError::FunctionNotFound | NvmlError::LibRmVersionMismatch => {
+            Some("nvml.version_mismatch")
+        }
+        NvmlError::NoPermission => Some("nvml.no_permission"),
+        NvmlError::GpuLost => Some("nvml.gpu_lost"),
+        NvmlError::ResetRequired => Some("nvml.reset_required"),
+        NvmlError::InsufficientPower => Some("nvml.insufficient_power"),
+        NvmlError::CorruptedInfoROM => Some("nvml.corrupted_inforom"),
+        _ => None,
+    }
+}
+
+impl From<NvmlError> for GpuError {
+    fn from(e: NvmlError) -> Self {
+        GpuError::Nvml(e)
+    }
+}
+
+// 驱动版本和它支持的最高 CUDA 版本对整台机器都一样，不放在每张卡里
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GpuDriver {
+    pub driver_version: String,
+    // 例如 "12.4"
+    pub cuda_version: Option<String>,
+}
+
+// NVML 用不了（没有 NVIDIA 驱动、--gpu-backend smi）时为 None
+pub fn driver_info() -> Option<GpuDriver> {
+    let nvml = Nvml::init().ok()?;
+    let cuda_version = nvml
+        .sys_cuda_driver_version()
+        .ok()
+        .map(|version| format!("{}.{}", cuda_driver_version_major(version), cuda_driver_version_minor(version)));
+
+    Some(GpuDriver {
+        driver_version: nvml.sys_driver_version().ok()?,
+        cuda_version,
+    })
+}
+
+pub struct NvmlStatus {
+    pub driver_version: String,
+    pub nvml_version: String,
+    pub device_count: u32,
+}
+
+// 只初始化 NVML 读版本号，用来确认驱动和 NVML 库能不能正常配合
+pub fn verify_nvml() -> Result<NvmlStatus, GpuError> {
+    let nvml = Nvml::init()?;
+
+    Ok(NvmlStatus {
+        driver_version: nvml.sys_driver_version()?,
+        nvml_version: nvml.sys_nvml_version()?,
+        device_count: nvml.device_count()?,
+    })
+}
+
+// 查询失败都当作查不到；其中没有权限的记下字段路径（按 NVML 序号），报告开头统一提示
+fn allowed<T>(index: u32, field: &str, result: Result<T, NvmlError>) -> Option<T> {
+    if let Err(NvmlError::NoPermission) = result {
+        privileges::denied(format!("gpus.{}.{}", index, field));
+    }
+    result.ok()
+}
+
+// 0 MHz 的显存频率或 0 位的显存位宽只可能是 NVML 读错了，当作查不到，免得据此算出的带宽也是错的
+fn nonzero(index: u32, field: &str, value: Option<u32>) -> Option<u32> {
+    if value == Some(0) {
+        implausible(index, field);
+        return None;
+    }
+    value
+}
+
+// 读取 GPU 信息的方式；auto 先用 NVML，库加载失败时改用 nvidia-smi
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum GpuBackend {
+    #[default]
+    Auto,
+    Nvml,
+    Smi,
+}
+
+// indices 为空时报告所有 GPU，否则只报告指定序号的卡；
+// wake 为 false 时已挂起的卡只报告 UUID 和名称，其它查询会把它唤醒
+pub fn get_gpus_info(indices: &[u32], wake: bool, backend: GpuBackend) -> Result<Vec<GpuInfo>, GpuError> {
+    match backend {
+        GpuBackend::Nvml => nvml_gpus_info(&Nvml::init()?, indices, wake),
+        GpuBackend::Smi => nvidia_smi::get_gpus_info(indices).map_err(GpuError::Smi),
+        // nvidia-smi 也用不了时报告 NVML 的错误，它的提示更有用
+        GpuBackend::Auto => match Nvml::init() {
+            Ok(nvml) => nvml_gpus_info(&nvml, indices, wake),
+            Err(e) => nvidia_smi::get_gpus_info(indices).map_err(|_| GpuError::Nvml(e)),
+        },
+    }
+}
+
+// dmon、--capture 之类的高频轮询：第一轮完整查询，之后每轮只刷新 GpuDynamic 里的几项。
+// 用 nvidia-smi 时（或查询出错后的下一轮）照常完整查询
+pub struct GpuPoller {
+    nvml: Option<Nvml>,
+    indices: Vec<u32>,
+    wake: bool,
+    backend: GpuBackend,
+    processes: bool,
+    gpus: Vec<GpuInfo>,
+}
+
+impl GpuPoller {
+    pub fn new(indices: &[u32], wake: bool, backend: GpuBackend) -> Self {
+        GpuPoller {
+            nvml: (backend != GpuBackend::Smi).then(Nvml::init).and_then(Result::ok),
+            indices: indices.to_vec(),
+            wake,
+            backend,
+            processes: false,
+            gpus: Vec::new(),
+        }
+    }
+
+    // --watch 的报告里有进程列表，每轮和 GpuDynamic 一起刷新
+    pub fn with_processes(mut self) -> Self {
+        self.processes = true;
+        self
+    }
+
+    pub fn poll(&mut self) -> Result<&[GpuInfo], GpuError> {
+        let refreshed = match &self.nvml {
+            Some(nvml) if !self.gpus.is_empty() => refresh_all(nvml, &mut self.gpus, self.wake, self.processes),
+            _ => get_gpus_info(&self.indices, self.wake, self.backend).map(|gpus| self.gpus = gpus),
+        };
+        if let Err(e) = refreshed {
+            self.gpus.clear();
+            return Err(e);
+        }
+
+        Ok(&self.gpus)
+    }
+}
+
+// 不唤醒已挂起的卡，和 nvml_gpus_info 一样
+fn refresh_all(nvml: &Nvml, gpus: &mut [GpuInfo], wake: bool, processes: bool) -> Result<(), GpuError> {
+    for gpu in gpus.iter_mut().filter(|gpu| wake || !gpu.suspended()) {
+        let device = nvml.device_by_index(gpu.index)?;
+        gpu.apply(refresh_gpu_dynamic(gpu.index, &device));
+        if processes {
+            gpu.processes = gpu_processes(gpu.index, &device);
+        }
+    }
+    Ok(())
+}
+
+fn nvml_gpus_info(nvml: &Nvml, indices: &[u32], wake: bool) -> Result<Vec<GpuInfo>, GpuError> {
+    let count = nvml.device_count()?;
+
+    if let Some(&index) = indices.iter().find(|&&index| index >= count) {
+        return Err(GpuError::NoSuchGpu { index, count });
+    }
+    let indices: Vec<u32> = if indices.is_empty() { (0..count).collect() } else { indices.to_vec() };
+
+    let mut gpus = Vec::with_capacity(indices.len());
+    for index in indices {
+        let device = nvml.device_by_index(index)?;
+        let power_state = power_state(&device);
+        let gpu = if !wake && power_state == Some(PowerState::Suspended) {
+            GpuInfo {
+                index,
+                uuid: device.uuid()?,
+                vendor: Some(NVIDIA.to_string()),
+                name: device.name().ok(),
+                power_state,
+                ..Default::default()
+            }
+        } else {
+            GpuInfo {
+                power_state,
+                ..get_gpu_info(index, &device)?
+            }
+        };
+        gpus.push(gpu);
+    }
+
+    Ok(gpus)
+}
+
+// 不查名称、核心数、位宽这些不会变的信息
+pub fn refresh_gpu_dynamic(index: u32, device: &Device) -> GpuDynamic {
+    read_dynamic(index, device, checked_vram_info(index, device).as_ref())
+}
+
+fn read_dynamic(index: u32, device: &Device, memory_info: Option<&VramInfo>) -> GpuDynamic {
+    let memory_clock = allowed(
+        index,
+        "memory_clock",
+        device.clock(enum_wrappers::device::Clock::Memory, enum_wrappers::device::ClockId::Current),
+    );
+
+    GpuDynamic {
+        gpu_temperature: allowed(
+            index,
+            "gpu_temperature",
+            device.temperature(enum_wrappers::device::TemperatureSensor::Gpu),
+        ),
+        core_clock: allowed(
+            index,
+            "core_clock",
+            device.clock(enum_wrappers::device::Clock::Graphics, enum_wrappers::device::ClockId::Current),
+        ),
+        memory_clock: nonzero(index, "memory_clock", memory_clock),
+        utilization: allowed(index, "utilization", device.utilization_rates()).map(|rates| rates.gpu),
+        power_usage: allowed(index, "power_usage", device.power_usage()).map(|power| power as f64 / 1000.0),
+        memory_used: memory_info.map(|info| bytes_to_gb(info.used)),
+        memory_used_bytes: memory_info.map(|info| info.used),
+        // 不支持查询降频原因的卡当作没有降频，不影响其它数据
+        throttle_reasons: allowed(index, "throttle_reasons", device.current_throttle_reasons())
+            .map(decode_throttle_reasons)
+            .unwrap_or_default(),
+    }
+}
+
+// 每一项单独查询，查不到的记为 None；比如开了动态加速的笔记本常常读不到功耗上限，
+// 但温度和频率还是能读到的。只有 UUID 是必须的，watch 模式靠它区分不同的卡
+fn get_gpu_info(index: u32, device: &Device) -> Result<GpuInfo, NvmlError> {
+    let uuid = device.uuid()?;
+    let memory_info = checked_vram_info(index, device);
+    let dynamic = read_dynamic(index, device, memory_info.as_ref());
+    // 可调的功耗上限范围，不支持调节功耗的卡（多数笔记本）查不到
+    let power_constraints = allowed(index, "power_limit_min", device.power_management_limit_constraints());
+    // 消费级显卡通常不支持查询序列号
+    let (fan_policy, fan_target) = fan_control(device);
+    let encoder_stats = device.encoder_stats().ok();
+    let serial = allowed(index, "serial", device.serial());
+    let vbios_version = allowed(index, "vbios_version", device.vbios_version());
+    let (ecc_corrected, ecc_uncorrected) = ecc_errors(device);
+
+    Ok(GpuInfo {
+        index,
+        uuid,
+        vendor: Some(NVIDIA.to_string()),
+        pci_bus_id: device.pci_info().ok().map(|info| info.bus_id),
+        serial,
+        vbios_version,
+        name: device.name().ok(),
+        power_state: None,
+        num_cores: device.num_cores().ok(),
+        memory_bus_width: nonzero(
+            index,
+            "memory_bus_width",
+            allowed(index, "memory_bus_width", device.memory_bus_width()),
+        ),
+        core_clock: dynamic.core_clock,
+        base_core_clock: device.default_applications_clock(enum_wrappers::device::Clock::Graphics).ok(),
+        temp_slowdown_threshold: device
+            .temperature_threshold(enum_wrappers::device::TemperatureThreshold::Slowdown)
+            .ok(),
+        temp_shutdown_threshold: device
+            .temperature_threshold(enum_wrappers::device::TemperatureThreshold::Shutdown)
+            .ok(),
+        memory_clock: dynamic.memory_clock,
+        application_clocks_set: application_clocks_set(device),
+        // 驱动给不出时是 NVML_PSTATE_UNKNOWN（32）
+        performance_state: device
+            .performance_state()
+            .ok()
+            .and_then(|state| u8::try_from(state.as_c()).ok())
+            .filter(|&state| state <= 15),
+        auto_boost: device.auto_boosted_clocks_enabled().ok().map(|info| info.is_enabled),
+        gpu_temperature: dynamic.gpu_temperature,
+        temperature_trend: None,
+        sparklines: GpuSparklines::default(),
+        fan_speed: device.fan_speed(0).ok(),
+        fan_target,
+        fan_policy,
+        fan_speeds: fan_speeds(device),
+        memory_temperature: memory_temperature(device),
+        utilization: dynamic.utilization,
+        active_encoder_sessions: encoder_stats.as_ref().map(|stats| stats.session_count),
+        encoder_fps: encoder_stats.as_ref().map(|stats| stats.average_fps),
+        encoder_latency_us: encoder_stats.as_ref().map(|stats| stats.average_latency),
+        gpu_util_avg: None,
+        gpu_util_peak: None,
+        power_usage: dynamic.power_usage,
+        power_limit: allowed(index, "power_limit", device.enforced_power_limit()).map(|limit| limit / 1000),
+        power_limit_min: power_constraints.as_ref().map(|constraints| constraints.min_limit / 1000),
+        power_limit_max: power_constraints.as_ref().map(|constraints| constraints.max_limit / 1000),
+        power_limit_default: device.power_management_limit_default().ok().map(|limit| limit / 1000),
+        memory_used: dynamic.memory_used,
+        memory_reserved: memory_info.as_ref().and_then(|info| info.reserved).map(bytes_to_gb),
+        memory_total: memory_info.as_ref().map(|info| bytes_to_gb(info.total)),
+        memory_used_bytes: dynamic.memory_used_bytes,
+        memory_total_bytes: memory_info.as_ref().map(|info| info.total),
+        largest_free_block_bytes: None,
+        fragmentation: Fragmentation::Unavailable,
+        cpu_affinity: cpu_affinity(device),
+        ecc_corrected,
+        ecc_uncorrected,
+        throttle_reasons: dynamic.throttle_reasons,
+        processes: gpu_processes(index, device),
+    })
+}
+
+// 计算和图形进程合在一起，同一个 PID 两边都有时只保留一条；按显存占用从大到小排
+fn gpu_processes(index: u32, device: &Device) -> Vec<GpuProcess> {
+    let compute = allowed(index, "processes", device.running_compute_processes()).unwrap_or_default();
+    let graphics = allowed(index, "processes", device.running_graphics_processes()).unwrap_or_default();
+
+    let mut processes: Vec<GpuProcess> = Vec::new();
+    for info in compute.into_iter().chain(graphics) {
+        let used_memory = match info.used_gpu_memory {
+            UsedGpuMemory::Used(bytes) => Some(bytes_to_gb(bytes)),
+            UsedGpuMemory::Unavailable => None,
+        };
+
+        match processes.iter_mut().find(|process| process.pid == info.pid) {
+            Some(process) => {
+                if used_memory > process.used_memory {
+                    process.used_memory = used_memory;
+                }
+            }
+            None => processes.push(GpuProcess::new(info.pid, used_memory)),
+        }
+    }
+
+    // 默认按显存排；--sort 选了别的排序方式时再在 report 里重排
+    processes::sort(&mut processes, ProcessSort::Mem);
+    processes
+}
+
+// 在 window 时间内均匀采样 samples 次利用率，返回 GPU 序号 => (平均值, 峰值)；
+// 单次读数波动很大，短时间的任务很容易读到 0
+pub fn sample_utilization(gpus: &[u32], samples: u32, window: Duration) -> HashMap<u32, (f64, u32)> {
+    let Ok(nvml) = Nvml::init() else {
+        return HashMap::new();
+    };
+    let devices: Vec<(u32, Device)> = gpus
+        .iter()
+        .filter_map(|&index| nvml.device_by_index(index).ok().map(|device| (index, device)))
+        .collect();
+
+    let mut readings: HashMap<u32, Vec<u32>> = HashMap::new();
+    let step = window / samples.max(1);
+    for sample in 0..samples {
+        if sample > 0 {
+            thread::sleep(step);
+        }
+        for (index, device) in &devices {
+            if let Ok(rates) = device.utilization_rates() {
+                readings.entry(*index).or_default().push(rates.gpu);
+            }
+        }
+    }
+
+    readings
+        .into_iter()
+        .map(|(index, values)| {
+            let average = values.iter().sum::<u32>() as f64 / values.len() as f64;
+            let peak = values.iter().copied().max().unwrap_or_default();
+            (index, (average, peak))
+        })
+        .collect()
+}
+
+// 足够覆盖 1024 个逻辑 CPU 的掩码长度
+#[cfg(target_os = "linux")]
+const AFFINITY_WORDS: usize = 1024 / c_ulong::BITS as usize;
+
+// NVML 返回离这块卡最近的 CPU 掩码，展开成逻辑 CPU 编号；不支持时为空
+#[cfg(target_os = "linux")]
+fn cpu_affinity(device: &Device) -> Vec<usize> {
+    let Ok(mask) = device.cpu_affinity(AFFINITY_WORDS) else {
+        return Vec::new();
+    };
+    let bits = c_ulong::BITS as usize;
+
+    mask.iter()
+        .enumerate()
+        .flat_map(|(word, &value)| {
+            (0..bits)
+                .filter(move |bit| value & (1 << bit) != 0)
+                .map(move |bit| word * bits + bit)
+        })
+        .collect()
+}
+
+// NVML 只在 Linux 上提供 CPU 亲和性查询
+#[cfg(not(target_os = "linux"))]
+fn cpu_affinity(_device: &Device) -> Vec<usize> {
+    Vec::new()
+}
+
+// NVML 的总线号是 00000000:01:00.0，sysfs 里是 0000:01:00.0
+#[cfg(target_os = "linux")]
+fn power_state(device: &Device) -> Option<PowerState> {
+    let bus_id = device.pci_info().ok()?.bus_id.to_lowercase();
+    let bus_id = bus_id.get(bus_id.len().checked_sub(12)?..)?;
+    let path = format!("/sys/bus/pci/devices/{}/power/runtime_status", bus_id);
+
+    match std::fs::read_to_string(path).ok()?.trim() {
+        "active" | "resuming" => Some(PowerState::Active),
+        "suspended" | "suspending" => Some(PowerState::Suspended),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn power_state(_device: &Device) -> Option<PowerState> {
+    None
+}
+
+// 只读：核心或显存的应用频率和默认值不同，说明用 nvidia-smi -ac 之类锁过频率
+fn application_clocks_set(device: &Device) -> Option<bool> {
+    let differs = |clock: enum_wrappers::device::Clock| -> Option<bool> {
+        Some(device.applications_clock(clock.clone()).ok()? != device.default_applications_clock(clock).ok()?)
+    };
+
+    Some(differs(enum_wrappers::device::Clock::Graphics)? || differs(enum_wrappers::device::Clock::Memory)?)
+}
+
+// 显存结温只能通过 field value 查询，需要较新的驱动，GDDR6X/HBM 之外的卡通常不支持
+fn memory_temperature(device: &Device) -> Option<u32> {
+    let samples = device.field_values_for(&[FieldId(field_id::NVML_FI_DEV_MEMORY_TEMP)]).ok()?;
+
+    match samples.into_iter().next()?.ok()?.value.ok()? {
+        SampleValue::U32(value) => Some(value),
+        SampleValue::U64(value) => u32::try_from(value).ok(),
+        SampleValue::I64(value) => u32::try_from(value).ok(),
+        SampleValue::F64(value) => Some(value as u32),
+    }
+}
+
+fn decode_throttle_reasons(reasons: ThrottleReasons) -> Vec<String> {
+    THROTTLE_REASON_NAMES
+        .iter()
+        .filter(|(flag, _)| reasons.contains(*flag))
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+// 新驱动的 v2 接口把驱动/固件预留的显存单独列出来（此时 used 不再包含 reserved），
+// 老驱动只有 v1 的 memory_info()，reserved 为 None
+// 驱动偶尔会短暂返回全 0 的显存信息；总量为 0 时重试一次，仍为 0 就把显存各项当作查不到，
+// 免得显示 0.00 GB 的已用显存，或者按总量算百分比时除以 0
+fn checked_vram_info(index: u32, device: &Device) -> Option<VramInfo> {
+    let mut info = allowed(index, "memory_used", vram_info(device))?;
+    if info.total == 0 {
+        info = allowed(index, "memory_used", vram_info(device))?;
+    }
+    if info.total == 0 {
+        implausible(index, "memory_total");
+        return None;
+    }
+
+    Some(info)
+}
+
+// 第一次遇到时提示并返回 true
+fn implausible(index: u32, field: &str) -> bool {
+    let mut warned = IMPLAUSIBLE.lock().unwrap_or_else(|e| e.into_inner());
+    let first = warned.get_or_insert_with(HashSet::new).insert((index, field.to_string()));
+    if first {
+        eprintln!("{}", tf("gpu.implausible", &[&index, &field]));
+    }
+    first
+}
+
+fn vram_info(device: &Device) -> Result<VramInfo, NvmlError> {
+    if let Some(info) = memory_info_v2(device) {
+        return Ok(info);
+    }
+
+    let info = device.memory_info()?;
+    Ok(VramInfo {
+        total: info.total,
+        used: info.used,
+        reserved: None,
+    })
+}
+
+// 消费级显卡不支持 ECC，关闭了 ECC 的卡也查不到计数
+fn ecc_errors(device: &Device) -> (Option<u64>, Option<u64>) {
+    use enum_wrappers::device::{EccCounter, MemoryError};
+
+    if !device.is_ecc_enabled().is_ok_and(|state| state.currently_enabled) {
+        return (None, None);
+    }
+    let count = |error: MemoryError| device.total_ecc_errors(error, EccCounter::Volatile).ok();
+
+    (count(MemoryError::Corrected), count(MemoryError::Uncorrected))
+}
+
+// 读不到的风扇跳过，被动散热的卡是空的
+fn fan_speeds(device: &Device) -> Vec<u32> {
+    match device.num_fans() {
+        Ok(count) => (0..count).filter_map(|fan| device.fan_speed(fan).ok()).collect(),
+        Err(_) => device.fan_speed(0).ok().into_iter().collect(),
+    }
+}
+
+// 只读；nvml-wrapper 没有封装这两个接口，和 memory_info_v2 一样直接取符号调用
+fn fan_control(device: &Device) -> (Option<FanPolicy>, Option<u32>) {
+    let Some(lib) = nvml_lib() else {
+        return (None, None);
+    };
+
+    let policy = lib.nvmlDeviceGetFanControlPolicy_v2.as_ref().ok().and_then(|sym| {
+        let mut policy = 0;
+        // SAFETY: handle 来自同一个 NVML 库，policy 是有效的出参
+        if unsafe { sym(device.handle(), 0, &mut policy) } != nvmlReturn_enum_NVML_SUCCESS {
+            return None;
+        }
+        Some(if policy == NVML_FAN_POLICY_MANUAL { FanPolicy::Manual } else { FanPolicy::Auto })
+    });
+    let target = lib.nvmlDeviceGetTargetFanSpeed.as_ref().ok().and_then(|sym| {
+        let mut target = 0;
+        // SAFETY: 同上
+        (unsafe { sym(device.handle(), 0, &mut target) } == nvmlReturn_enum_NVML_SUCCESS).then_some(target)
+    });
+
+    (policy, target)
+}
+
+// nvml-wrapper 没有封装 nvmlDeviceGetMemoryInfo_v2，这里直接从同一个 NVML 动态库里取符号调用
+fn memory_info_v2(device: &Device) -> Option<VramInfo> {
+    let lib = nvml_lib()?;
+    let sym = lib.nvmlDeviceGetMemoryInfo_v2.as_ref().ok()?;
+
+    unsafe {
+        let mut info: nvmlMemory_v2_t = mem::zeroed();
+        info.version = NVML_MEMORY_V2_VERSION;
+
+        if sym(device.handle(), &mut info) != nvmlReturn_enum_NVML_SUCCESS {
+            return None;
+        }
+
+        Some(VramInfo {
+            total: info.total,
+            used: info.used,
+            reserved: Some(info.reserved),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn implausible_warns_once_per_field() {
+        assert_eq!(nonzero(7, "memory_clock", Some(0)), None);
+        assert!(!implausible(7, "memory_clock"));
+        assert!(implausible(7, "memory_bus_width"));
+        assert!(implausible(8, "memory_clock"));
+        assert_eq!(nonzero(7, "memory_clock", Some(5001)), Some(5001));
+    }
+
+    fn gpu(used: f64, total: f64, reserved: Option<f64>) -> GpuInfo {
+        GpuInfo {
+            memory_used: Some(used),
+            memory_total: Some(total),
+            memory_reserved: reserved,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn memory_usage_percent_excludes_reserved() {
+        assert_eq!(gpu(2.0, 8.0, None).memory_usage_percent(), Some(25.0));
+        assert_eq!(gpu(3.0, 8.0, Some(2.0)).memory_usage_percent(), Some(50.0));
+    }
+
+    #[test]
+    fn memory_usage_percent_without_usable_memory() {
+        assert_eq!(gpu(0.0, 0.0, None).memory_usage_percent(), None);
+        assert_eq!(gpu(0.0, 8.0, Some(8.0)).memory_usage_percent(), None);
+        assert_eq!(GpuInfo::default().memory_usage_percent(), None);
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use crate::gpu::{self, GpuInfo};
+use crate::memory_pressure::PressureLevel;
+use crate::report::Report;
+use crate::system::DiskInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    Ok,
+    Warning,
+    Critical,
+}
+
+// GPU 温度离降频阈值不到这么多度时算 warning
+const GPU_TEMP_MARGIN: u32 = 5;
+
+// 把报告里已有的几项判断汇总成一个等级，取其中最严重的：
+// critical：内存压力高、GPU 因供电或散热降频、GPU 温度到了降频阈值、磁盘剩余空间或 inode 不到 5%
+// warning：内存压力中等、GPU 温度离降频阈值不到 5 C、磁盘剩余空间不到 10%。
+// 采集失败的部分不算（没有驱动的机器上 GPU 总是失败，天气要联网），要求完整时用 --strict
+pub fn assess(report: &Report) -> Health {
+    let memory = match report.system.memory_pressure.map(|pressure| pressure.level) {
+        Some(PressureLevel::High) => Health::Critical,
+        Some(PressureLevel::Medium) => Health::Warning,
+        _ => Health::Ok,
+    };
+
+    report
+        .gpus
+        .iter()
+        .map(gpu_health)
+        .chain(report.system.disks.iter().map(disk_health))
+        .chain([memory])
+        .max()
+        .unwrap_or(Health::Ok)
+}
+
+fn gpu_health(gpu: &GpuInfo) -> Health {
+    let throttled = gpu
+        .throttle_reasons
+        .iter()
+        .any(|reason| gpu::CRITICAL_THROTTLE_REASONS.contains(&reason.as_str()));
+    match (gpu.gpu_temperature, gpu.temp_slowdown_threshold) {
+        _ if throttled => Health::Critical,
+        (Some(temperature), Some(slowdown)) if temperature >= slowdown => Health::Critical,
+        (Some(temperature), Some(slowdown)) if temperature + GPU_TEMP_MARGIN >= slowdown => Health::Warning,
+        _ => Health::Ok,
+    }
+}
+
+// 重复挂载和没有容量的挂载点不看
+fn disk_health(disk: &DiskInfo) -> Health {
+    if disk.duplicate_of.is_some() {
+        return Health::Ok;
+    }
+    match disk.free_percent() {
+        _ if disk.inodes_exhausted() => Health::Critical,
+        Some(free) if free < 5.0 => Health::Critical,
+        Some(free) if free < 10.0 => Health::Warning,
+        _ => Health::Ok,
+    }
+}
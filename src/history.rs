@@ -0,0 +1,52 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::clock;
+use crate::log_file::RotatingLog;
+use crate::report::Report;
+
+// --history 每次运行（watch 模式每一轮）追加的一行 JSON，只保留看趋势用的核心指标，容量单位是 GB
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HistoryRecord {
+    pub timestamp: DateTime<FixedOffset>,
+    pub cpu_usage: f32,
+    pub memory_used: f64,
+    pub memory_total: f64,
+    pub gpus: Vec<GpuRecord>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GpuRecord {
+    pub index: u32,
+    pub temperature: Option<u32>,
+    pub utilization: Option<u32>,
+    pub power_usage: Option<f64>,
+    pub memory_used: Option<f64>,
+}
+
+impl HistoryRecord {
+    pub fn new(report: &Report) -> Self {
+        HistoryRecord {
+            timestamp: clock::now(),
+            cpu_usage: report.system.average_cpu_usage,
+            memory_used: report.system.used_memory,
+            memory_total: report.system.total_memory,
+            gpus: report
+                .gpus
+                .iter()
+                .map(|gpu| GpuRecord {
+                    index: gpu.index,
+                    temperature: gpu.gpu_temperature,
+                    utilization: gpu.utilization,
+                    power_usage: gpu.power_usage,
+                    memory_used: gpu.memory_used,
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn record(log: &mut RotatingLog, report: &Report) -> Result<(), String> {
+    let line = serde_json::to_string(&HistoryRecord::new(report)).map_err(|e| e.to_string())?;
+    log.append_line(&line)
+}
@@ -0,0 +1,50 @@
+use std::net::{IpAddr, UdpSocket};
+
+// 对公网地址 connect 一个 UDP socket，只让内核选路由，不发送数据；离线（没有路由）时返回 None
+pub fn primary_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:53").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+
+    (!ip.is_unspecified()).then_some(ip)
+}
+
+// 用 getaddrinfo 的 AI_CANONNAME 解析主机名得到完整域名；解析不到或没有域名部分时返回 None
+#[cfg(unix)]
+pub fn fqdn(host_name: &str) -> Option<String> {
+    use std::ffi::{CStr, CString};
+    use std::ptr;
+
+    let name = CString::new(host_name).ok()?;
+    let hints = libc::addrinfo {
+        ai_flags: libc::AI_CANONNAME,
+        ai_family: libc::AF_UNSPEC,
+        ai_socktype: 0,
+        ai_protocol: 0,
+        ai_addrlen: 0,
+        ai_addr: ptr::null_mut(),
+        ai_canonname: ptr::null_mut(),
+        ai_next: ptr::null_mut(),
+    };
+    let mut result = ptr::null_mut();
+
+    // SAFETY: name 和 hints 在调用期间有效，成功时 result 由 freeaddrinfo 释放
+    let canonical = unsafe {
+        if libc::getaddrinfo(name.as_ptr(), ptr::null(), &hints, &mut result) != 0 || result.is_null() {
+            return None;
+        }
+        let canonname = (*result).ai_canonname;
+        let canonical = (!canonname.is_null()).then(|| CStr::from_ptr(canonname).to_string_lossy().into_owned());
+        libc::freeaddrinfo(result);
+        canonical
+    };
+
+    canonical.filter(|name| name.contains('.'))
+}
+
+// Windows 上加入域的机器会设置 USERDNSDOMAIN
+#[cfg(not(unix))]
+pub fn fqdn(host_name: &str) -> Option<String> {
+    let domain = std::env::var("USERDNSDOMAIN").ok().filter(|domain| !domain.is_empty())?;
+    Some(format!("{}.{}", host_name, domain.to_lowercase()))
+}
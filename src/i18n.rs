@@ -0,0 +1,397 @@
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    En,
+    Zh,
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+// 所有面向用户的文字都集中在这里：(key, English, 中文)
+const MESSAGES: &[(&str, &str, &str)] = &[
+    ("error", "Error", "错误"),
+    ("request_ok", "Request succeeded", "请求成功"),
+    ("request_failed", "Request failed", "请求失败"),
+    // GPU
+    ("capture.done", "wrote {} rows to {}", "已写入 {} 行到 {}"),
+    ("self_stats.line", "self: CPU time {} s (user {} s, system {} s), peak RSS {}, wall time {} s", "本程序: CPU 时间 {} s（用户态 {} s，内核态 {} s），内存峰值 {}，运行时间 {} s"),
+    ("throttle.critical", "GPU {} is throttled: {}", "GPU {} 降频: {}"),
+    ("strict.failed", "{} collection failed: {}", "{} 采集失败: {}"),
+    ("anonymize.no_config_dir", "no config directory to keep the --anonymize salt in (set HOME or pass --anonymize-salt)", "找不到保存 --anonymize 盐的配置目录（请设置 HOME 或指定 --anonymize-salt）"),
+    ("anonymize.empty_salt", "salt file is empty; delete it or pass --anonymize-salt", "盐文件是空的，请删除它或指定 --anonymize-salt"),
+    ("config.file", "config file: {}", "配置文件: {}"),
+    ("config.no_file", "no config file", "没有配置文件"),
+    ("config.precedence", "precedence: command line > environment > config file > default", "优先级：命令行 > 环境变量 > 配置文件 > 默认值"),
+    ("config.custom_metrics", "{} custom metrics defined in the config file", "配置文件中定义了 {} 个自定义指标"),
+    ("config.source_cli", "command line", "命令行"),
+    ("config.source_env", "environment ({})", "环境变量（{}）"),
+    ("config.source_file", "config file ({})", "配置文件（{}）"),
+    ("config.source_default", "default", "默认值"),
+    ("privileges.note", "Note: running as non-root; some fields unavailable", "注意：未以 root 运行，部分字段不可用"),
+    ("privileges.required", "requires elevated privileges", "需要更高权限"),
+    ("smi.not_found", "nvidia-smi not found — is the NVIDIA driver installed?", "找不到 nvidia-smi，是否安装了 NVIDIA 驱动？"),
+    ("gpu.section", "=> gpu {}:", "=> GPU {}:"),
+    ("gpu.name", "GPU Name", "GPU 名称"),
+    ("gpu.vendor", "Vendor", "厂商"),
+    ("gpu.active", "active", "运行中"),
+    ("gpu.suspended", "suspended", "已挂起"),
+    ("gpu.uuid", "GPU UUID", "GPU UUID"),
+    ("gpu.pci_bus_id", "PCI Bus ID", "PCI 总线号"),
+    ("gpu.serial", "Serial Number", "序列号"),
+    ("gpu.vbios_version", "VBIOS Version", "VBIOS 版本"),
+    ("gpu.cores", "Number of Cores", "核心数"),
+    ("gpu.bus_width", "Memory Bus Width: {}-bit bus width", "显存位宽: {} 位"),
+    ("gpu.core_clock", "GPU Core Clocks", "GPU 核心频率"),
+    ("gpu.clock_boosting", "boosting above the {} MHz base", "高于 {} MHz 的基础频率"),
+    ("gpu.clock_below_base", "below the {} MHz base", "低于 {} MHz 的基础频率"),
+    ("gpu.clock_at_base", "at the {} MHz base", "等于 {} MHz 的基础频率"),
+    ("gpu.memory_clock", "GPU Memory Clock", "GPU 显存频率"),
+    ("gpu.implausible", "GPU {}: NVML reported 0 for {}, treating it as unavailable", "GPU {}: NVML 返回的 {} 为 0，按查不到处理"),
+    ("gpu.application_clocks", "Application Clocks", "应用频率"),
+    ("gpu.clocks_custom", "set (differs from default)", "已设置（与默认值不同）"),
+    ("gpu.clocks_default", "default", "默认"),
+    ("gpu.throttling", "Throttling", "降频原因"),
+    ("gpu.ecc_errors", "ECC Errors (since driver load)", "ECC 错误（驱动加载以来）"),
+    ("gpu.ecc_counts", "{} corrected, {} uncorrected", "已纠正 {}，不可纠正 {}"),
+    ("gpu.not_throttling", "Not throttling", "未降频"),
+    ("gpu.auto_boost", "Auto Boost", "自动加速"),
+    ("gpu.auto_boost_on", "enabled", "开启"),
+    ("gpu.auto_boost_off", "disabled", "关闭"),
+    ("gpu.driver_version", "Driver Version", "驱动版本"),
+    ("gpu.fans", "Fans", "各风扇转速"),
+    ("gpu.performance_state", "Performance State", "性能状态"),
+    ("gpu.temperature", "GPU Temperature", "GPU 温度"),
+    ("gpu.temp_slowdown_at", "slowdown at {} C", "{} C 开始降频"),
+    ("gpu.temp_shutdown_at", "shutdown at {} C", "{} C 关机"),
+    ("gpu.temps", "GPU Temps", "GPU 各传感器温度"),
+    ("gpu.sensor_edge", "edge", "核心"),
+    ("gpu.sensor_memory", "memory", "显存"),
+    ("gpu.fan", "Fan", "风扇"),
+    ("gpu.encoder", "{} sessions, {} fps avg", "{} 个会话，平均 {} fps"),
+    ("gpu.fan_auto", "auto", "自动"),
+    ("gpu.fan_manual", "manual", "手动"),
+    ("gpu.fan_target", "target {}", "目标 {}"),
+    ("gpu.utilization", "GPU Utilization", "GPU 利用率"),
+    ("gpu.utilization_window", "GPU Utilization (1 s): avg {}%, peak {}%", "GPU 利用率（1 秒内）: 平均 {}%，峰值 {}%"),
+    ("gpu.power_usage", "Power Usage", "功耗"),
+    ("gpu.power_limit", "Power Limit", "功耗上限"),
+    ("gpu.power_limit_range", "range {}–{} W", "范围 {}–{} W"),
+    ("gpu.power_limit_default", "default {} W", "默认 {} W"),
+    ("gpu.memory_used", "Memory Used", "已用显存"),
+    ("gpu.memory_total", "Memory Total", "显存总量"),
+    ("gpu.largest_free_block", "Largest Free Block", "最大连续空闲块"),
+    ("gpu.fragmentation_unavailable", "Fragmentation data unavailable", "显存碎片数据不可用"),
+    (
+        "gpu.memory_breakdown",
+        "Memory Breakdown: used {} GB / reserved {} GB / total {} GB",
+        "显存分布: 已用 {} GB / 预留 {} GB / 总量 {} GB",
+    ),
+    ("gpu.required_missing", "no GPU found (--gpu-required)", "没有找到 GPU（--gpu-required）"),
+    ("gpu.no_such_gpu", "GPU {} does not exist (found {} GPUs)", "GPU {} 不存在（共 {} 块 GPU）"),
+    ("gpu.processes", "Processes:", "进程:"),
+    ("gpu.summary_section", "=> all {} gpus:", "=> 全部 {} 块 GPU:"),
+    ("gpu.max_temperature", "Max Temperature", "最高温度"),
+    ("gpu.cpu_affinity", "Optimal CPUs", "就近的 CPU"),
+    ("gpu.process_pid", "PID", "PID"),
+    ("gpu.process_memory", "VRAM", "显存"),
+    ("gpu.process_cpu", "CPU", "CPU"),
+    ("gpu.process_command", "Command", "命令"),
+    ("gpu.process_parent", "(parent {}: {})", "(父进程 {}: {})"),
+    // NVML 错误提示
+    ("nvml.ok", "NVML OK: driver {}, NVML {}, {} GPU(s)", "NVML 正常: 驱动 {}，NVML {}，{} 块 GPU"),
+    (
+        "nvml.library_not_found",
+        "NVML library not found — is the NVIDIA driver installed?",
+        "找不到 NVML 库，是否安装了 NVIDIA 驱动？",
+    ),
+    (
+        "nvml.driver_not_loaded",
+        "NVIDIA driver is not loaded — check that the kernel module is running (nvidia-smi)",
+        "NVIDIA 驱动没有加载，请检查内核模块是否正常（nvidia-smi）",
+    ),
+    (
+        "nvml.version_mismatch",
+        "version mismatch between driver and NVML — reboot after a driver update, or reinstall the driver",
+        "驱动和 NVML 版本不一致，更新驱动后请重启或重新安装驱动",
+    ),
+    (
+        "nvml.no_permission",
+        "permission denied talking to the GPU — try running as root or check /dev/nvidia* permissions",
+        "没有访问 GPU 的权限，请用 root 运行或检查 /dev/nvidia* 的权限",
+    ),
+    ("nvml.gpu_lost", "GPU has fallen off the bus — check power and seating, then reboot", "GPU 已从总线上掉线，请检查供电和插槽后重启"),
+    ("nvml.reset_required", "GPU needs a reset or reboot", "GPU 需要重置或重启"),
+    ("nvml.insufficient_power", "GPU power cables are not connected properly", "GPU 供电线没有接好"),
+    ("nvml.corrupted_inforom", "GPU infoROM is corrupted", "GPU infoROM 已损坏"),
+    // 系统
+    ("system.section", "=> system:", "=> 系统:"),
+    ("system.total_memory", "Total Memory", "内存总量"),
+    ("system.used_memory", "Used Memory", "已用内存"),
+    ("system.used_memory_no_cache", "Used Memory (total - available, as in free)", "已用内存（总量 - 可用，与 free 一致）"),
+    ("system.available_memory", "Available Memory", "可用内存"),
+    ("system.total_swap", "Total Swap", "交换区总量"),
+    ("system.used_swap", "Used Swap", "已用交换区"),
+    ("system.memory_pressure", "Memory Pressure", "内存压力"),
+    ("system.numa_node", "NUMA Node", "NUMA 节点"),
+    ("system.numa_memory", "{} GB free of {} GB", "可用 {} GB / 共 {} GB"),
+    ("system.processes", "Processes: {}", "进程数: {}"),
+    ("system.processes_threads", "Processes: {} ({} threads)", "进程数: {}（线程 {} 个）"),
+    ("system.cpu_power", "CPU Package Power", "CPU 封装功耗"),
+    ("system.total_power", "Total measured power (CPU + GPUs)", "实测总功耗（CPU + GPU）"),
+    ("system.pressure_low", "Low", "低"),
+    ("system.pressure_medium", "Medium", "中"),
+    ("system.pressure_high", "High", "高"),
+    ("system.pressure_plenty", "plenty of memory available", "可用内存充足"),
+    ("system.pressure_low_available", "little memory left available", "可用内存所剩不多"),
+    ("system.pressure_swap_heavy", "heavy swap use", "大量使用交换区"),
+    ("system.pressure_stalls", "processes are stalling while waiting for memory", "进程在等待内存时停顿"),
+    ("system.name", "System Name", "系统名称"),
+    ("system.kernel_version", "Kernel Version", "内核版本"),
+    ("system.os_version", "OS Version", "系统版本"),
+    ("system.host_name", "Host Name", "主机名"),
+    ("system.unknown", "unknown", "未知"),
+    ("system.arch", "Architecture", "架构"),
+    ("system.arch_bits", "{} ({}-bit, {} endian)", "{}（{} 位，{} 字节序）"),
+    ("system.virtualization", "Virtualization", "虚拟化"),
+    ("system.fqdn", "FQDN", "完整域名"),
+    ("system.cgroup_memory_limit", "Memory Limit (cgroup)", "内存上限（cgroup）"),
+    ("system.cgroup_cpu_limit", "CPU Limit (cgroup)", "CPU 上限（cgroup）"),
+    ("system.cgroup_cores", "{} cores", "{} 核"),
+    ("system.primary_ip", "Primary IP", "主 IP 地址"),
+    ("system.secure_boot", "Secure Boot", "安全启动"),
+    ("system.secure_boot_on", "enabled", "开启"),
+    ("system.secure_boot_off", "disabled", "关闭"),
+    ("system.tpm", "TPM", "TPM"),
+    ("system.tpm_yes", "present", "有"),
+    ("system.tpm_no", "not present", "无"),
+    (
+        "system.uptime",
+        "Uptime {} seconds is equivalent to {} days, {} hours, {} minutes, and {} seconds",
+        "已运行 {} 秒，即 {} 天 {} 小时 {} 分 {} 秒",
+    ),
+    ("system.cpu_throttle", "CPU thermal throttle events since boot: core {}, package {}", "开机以来 CPU 过热降频次数: 核心 {}，封装 {}"),
+    ("system.cpu_caches", "CPU Caches", "CPU 缓存"),
+    ("system.cpu_usage", "Average CPU Usage", "平均 CPU 使用率"),
+    (
+        "system.cpu_breakdown",
+        "CPU: {}% usr, {}% sys, {}% idle, {}% iowait",
+        "CPU: 用户 {}%，系统 {}%，空闲 {}%，IO 等待 {}%",
+    ),
+    ("networks.section", "=> networks:", "=> 网络:"),
+    ("networks.rates", "  now: ↓ {} ↑ {}", "  当前: ↓ {} ↑ {}"),
+    ("networks.totals", "{}: received {} GB, transmitted {} GB", "{}: 接收 {} GB，发送 {} GB"),
+    ("custom_metrics.section", "=> custom metrics:", "=> 自定义指标:"),
+    ("memory_modules.section", "=> memory modules:", "=> 内存条:"),
+    ("net_top.section", "=> network usage by process:", "=> 各进程网络流量:"),
+    ("net_top.unavailable", "Per-process network usage unavailable", "无法获取各进程网络流量"),
+    ("net_top.connections", "Connections", "连接数"),
+    ("net_top.received", "Received", "接收"),
+    ("net_top.transmitted", "Sent", "发送"),
+    ("kernel_params.section", "=> kernel parameters:", "=> 内核参数:"),
+    ("kernel_params.cmdline", "Kernel Command Line", "内核启动参数"),
+    ("kernel_params.transparent_hugepage", "Transparent Hugepages", "透明大页"),
+    ("aggregate.progress", "sample {}/{}", "第 {}/{} 次采样"),
+    ("aggregate.section", "=> {} samples, {} apart:", "=> {} 次采样，间隔 {}:"),
+    ("aggregate.metric", "Metric", "指标"),
+    ("aggregate.min", "Min", "最小"),
+    ("aggregate.max", "Max", "最大"),
+    ("aggregate.avg", "Avg", "平均"),
+    ("inventory.cpu", "CPU", "CPU"),
+    ("inventory.cores", "{} cores, {} threads", "{} 核 {} 线程"),
+    ("inventory.disk_model", "Model", "型号"),
+    ("disks.section", "=> disks:", "=> 磁盘:"),
+    ("disks.inodes_exhausted", "Warning: {} is almost out of inodes ({}% free) with {} of its space still free; new files cannot be created", "警告：{} 的 inode 快用完了（剩余 {}%），空间还剩 {}，但已经无法创建新文件"),
+    ("disks.omitted", "... and {} more (use --all-disks)", "……还有 {} 个（使用 --all-disks 显示全部）"),
+    (
+        "disks.duplicate_mounts",
+        "{} more mount point(s) of devices already listed were hidden (use --all-mounts)",
+        "另有 {} 个已列出设备的重复挂载点未显示（使用 --all-mounts 显示）",
+    ),
+    (
+        "disks.empty_mounts",
+        "{} mount point(s) with no capacity (e.g. empty CD-ROM drives) were hidden (use --all-mounts)",
+        "另有 {} 个容量为 0 的挂载点（例如空光驱）未显示（使用 --all-mounts 显示）",
+    ),
+    ("disks.duplicate_of", "{} (same device as {})", "{}（和 {} 是同一设备）"),
+    ("disks.none", "No disks detected", "未检测到磁盘"),
+    ("disks.summary", "{} disks: {} GB total, {} GB used, {} GB free ({} used)", "{} 个磁盘：共 {} GB，已用 {} GB，剩余 {} GB（已用 {}）"),
+    ("disks.latency", "latency read {} ms / write {} ms", "延迟 读 {} ms / 写 {} ms"),
+    ("disks.name", "Name", "名称"),
+    ("disks.kind", "Kind", "类型"),
+    ("disks.file_system", "File System", "文件系统"),
+    ("disks.mount_point", "Mount Point", "挂载点"),
+    ("disks.total", "Total", "总空间"),
+    ("disks.available", "Available", "可用空间"),
+    ("disks.inodes_column", "Inodes", "inode"),
+    ("disks.inodes_free", "{}/{} free", "剩余 {}/{}"),
+    ("disks.busy_column", "Busy", "忙碌"),
+    ("disks.busy", "busy {}%", "忙碌 {}%"),
+    ("disks.io_total_column", "Read/Written", "读/写"),
+    ("disks.io_total", "read/written since boot {}", "开机以来读/写 {}"),
+    ("disks.smart_column", "SMART", "SMART"),
+    ("disks.smart", "SMART {}", "SMART {}"),
+    ("disks.encrypted", "Encrypted", "加密"),
+    ("disks.latency_column", "Latency", "延迟"),
+    ("disks.inodes", "inodes: {}/{} free", "inode: 剩余 {}/{}"),
+    // watch
+    (
+        "watch.vram",
+        "VRAM GPU {}: current {} GB, min {} GB, max {} GB",
+        "GPU {} 显存: 当前 {} GB, 最低 {} GB, 最高 {} GB",
+    ),
+    ("watch.vram_growth", "VRAM growth detected on GPU {}: +{} GB over {}", "GPU {} 显存持续增长: +{} GB (历时 {})"),
+    ("output.no_dir", "output directory {} does not exist", "输出目录 {} 不存在"),
+    (
+        "output.sections_json_only",
+        "--section-output only works with JSON output",
+        "--section-output 只能和 JSON 输出一起使用",
+    ),
+    ("watch.summary", "=> session summary:", "=> 会话汇总:"),
+    ("bench.phase", "{}: {} ms", "{}: {} 毫秒"),
+    ("bench.total", "total", "总计"),
+    ("watch.runtime", "Ran for {} ({} refreshes)", "运行了 {}（刷新 {} 次）"),
+    ("watch.stat", "{}: min {} / max {} / avg {} {}", "{}: 最小 {} / 最大 {} / 平均 {} {}"),
+    ("watch.gpu_prefix", "GPU {}", "GPU {}"),
+    ("watch.no_throttle", "GPU {}: no throttling observed", "GPU {}: 未出现降频"),
+    ("watch.clock_spread", "{}: min {} / max {} MHz (spread {} MHz, {}%)", "{}: 最小 {} / 最大 {} MHz（波动 {} MHz，{}%）"),
+    ("watch.cpu_throttle", "CPU thermal throttling: +{} core / +{} package events since the last sample — check the cooling", "CPU 过热降频: 比上次采样增加了核心 {} 次 / 封装 {} 次，请检查散热"),
+    ("watch.throttle_active", "GPU {}: {} active {}% of session", "GPU {}: {} 出现在 {}% 的采样中"),
+    ("get.no_value", "'{}' has no value on this machine", "'{}' 在这台机器上没有值"),
+    ("report.generated", "Report generated: {}", "报告生成时间: {}"),
+    ("crash.partial_report", "Partial report collected before the crash:", "崩溃前已经采集到的部分报告："),
+    ("diff.no_changes", "No changes", "没有变化"),
+    ("diff.missing", "(none)", "（无）"),
+    ("diff.added", "added", "新增"),
+    ("diff.removed", "removed", "消失"),
+    ("fields.unknown", "unknown field '{}'", "未知字段 '{}'"),
+    ("fields.unknown_top_level", "unknown field '{}' (expected one of: {})", "未知字段 '{}'（可用: {}）"),
+    ("fields.unknown_suggest", "unknown field '{}', did you mean '{}'?", "未知字段 '{}'，是不是 '{}'？"),
+    // 天气
+    ("weather.provider", "Weather Provider", "天气数据来源"),
+    ("weather.oioweb_raw_only", "the oioweb provider only prints its raw response", "oioweb 接口只打印原始响应"),
+    ("weather.disabled", "built without the weather feature", "编译时没有启用 weather 特性"),
+    ("serve.disabled", "built without the serve feature", "编译时没有启用 serve 特性"),
+    ("weather.meizu_invalid_city", "unknown city ID (code {}: {}); check --city-id", "城市编号无效（code {}: {}），请检查 --city-id"),
+    ("weather.meizu_rate_limited", "rate limited by the weather API (code {}: {}); try again later", "天气接口限流（code {}: {}），请稍后再试"),
+    ("weather.meizu_unavailable", "weather API is unavailable or under maintenance (code {}: {})", "天气接口不可用或在维护（code {}: {}）"),
+    ("weather.meizu_failed", "weather API returned code {}: {}", "天气接口返回 code {}: {}"),
+    ("weather.no_data", "weather API returned no data for city {}", "天气接口没有返回城市 {} 的数据"),
+    ("weather.not_json", "weather API returned non-JSON (content-type: {})", "天气接口返回的不是 JSON（content-type: {}）"),
+    ("weather.bad_url", "invalid weather URL '{}': {}", "天气接口地址 '{}' 无效: {}"),
+    ("weather.bad_url_scheme", "weather URL '{}' must be an http:// or https:// URL", "天气接口地址 '{}' 必须是 http:// 或 https:// 开头的 URL"),
+    ("weather.owm_no_key", "OpenWeatherMap needs an API key (--owm-api-key or OWM_API_KEY)", "OpenWeatherMap 需要 API key（--owm-api-key 或 OWM_API_KEY）"),
+    ("weather.owm_no_location", "OpenWeatherMap needs a location (--owm-city or --lat/--lon)", "OpenWeatherMap 需要指定位置（--owm-city 或 --lat/--lon）"),
+    ("weather.alarm_content", "Alarm Content", "预警内容"),
+    ("weather.alarm_desc", "Alarm Description", "预警描述"),
+    ("weather.alarm_id", "Alarm ID", "预警编号"),
+    ("weather.alarm_level", "Alarm Level", "预警级别"),
+    ("weather.alarm_type", "Alarm Type", "预警类型"),
+    ("weather.precaution", "Precaution", "防御指南"),
+    ("weather.publish_time", "Publish Time", "发布时间"),
+    ("weather.city", "City", "城市"),
+    ("weather.city_id", "City ID", "城市编号"),
+    ("weather.index_name", "Index Name", "指数名称"),
+    ("weather.index_level", "Index Level", "指数等级"),
+    ("weather.index_content", "Index Content", "指数说明"),
+    ("weather.air_quality", "Air Quality", "空气质量"),
+    ("weather.aqi", "AQI", "AQI"),
+    ("weather.region", "Region", "地区"),
+    ("weather.realtime_weather", "Realtime Weather", "实时天气"),
+    ("weather.realtime_temp", "Realtime Temperature", "实时温度"),
+    ("weather.realtime_wind", "Realtime Wind", "实时风向风力"),
+    ("weather.realtime_humidity_feels", "Humidity: {}%, Feels like: {}°C", "湿度: {}%, 体感温度: {}°C"),
+    ("weather.realtime_uv", "UV Index", "紫外线强度"),
+    ("weather.sun", "Sunrise: {}, Sunset: {}", "日出: {}, 日落: {}"),
+    ("weather.daylight", "Daylight: {}h {}m", "白昼时长: {}小时{}分"),
+    ("weather.date", "Weather Date", "日期"),
+    ("weather.weather", "Weather", "天气"),
+    ("weather.day_temp", "Day Temperature", "白天温度"),
+    ("weather.night_temp", "Night Temperature", "夜间温度"),
+    ("weather.day_night_temp", "Day/Night", "白天/夜间"),
+    ("weather.wind", "Wind", "风"),
+];
+
+// --explain 时附在指标后面的说明，按 MESSAGES 里同名的 key 查找：(key, English, 中文)
+const EXPLANATIONS: &[(&str, &str, &str)] = &[
+    ("gpu.core_clock", "How fast the GPU's compute cores are running right now; it drops when the card is idle or throttling", "GPU 计算核心当前的运行速度；空闲或降频时会变低"),
+    ("gpu.memory_clock", "How fast the video memory is running; together with the bus width it sets how quickly data moves", "显存当前的运行速度；和位宽一起决定数据传输有多快"),
+    ("gpu.temperature", "GPU chip temperature; above about 85 C most cards slow themselves down to cool off", "GPU 芯片温度；大多数显卡超过约 85 C 会自动降速散热"),
+    ("gpu.utilization", "Share of time the GPU was busy; near 100% means it is the bottleneck", "GPU 忙碌的时间比例；接近 100% 说明它是瓶颈"),
+    ("gpu.power_usage", "Electricity the card is drawing right now", "显卡当前消耗的电功率"),
+    ("gpu.power_limit", "Highest power the card is allowed to draw; it slows down rather than exceed this", "显卡允许的最大功耗；到达后会降速而不是继续增加"),
+    ("gpu.memory_used", "Video memory taken by running programs; when it is full, new GPU work fails or slows down. On older drivers this also includes the part reserved by the driver", "正在运行的程序占用的显存；占满后新的 GPU 任务会失败或变慢。老驱动上还包含驱动预留的部分"),
+    ("system.total_memory", "Installed RAM the operating system can use", "操作系统可用的内存总量"),
+    ("system.used_memory", "RAM currently in use by programs and the system", "程序和系统当前占用的内存"),
+    ("system.available_memory", "RAM that programs can still get, including cache the system can drop", "程序还能申请到的内存，包括系统可以释放的缓存"),
+    ("system.total_swap", "Disk space set aside as overflow RAM", "硬盘上划作备用内存的空间"),
+    ("system.used_swap", "Swap is disk space used as overflow RAM; high usage means you're low on memory", "交换空间是硬盘上的备用内存；用得多说明内存不够"),
+    ("system.memory_pressure", "A rough read of how close the machine is to running out of memory, from free RAM, swap use and (on Linux) time spent waiting for memory; High means programs may soon be killed", "根据可用内存、交换区使用情况和（Linux 上）等待内存的时间粗略估计离内存耗尽有多近；高表示程序可能很快会被杀掉"),
+    ("system.uptime", "Time since the machine was last restarted", "距离上次重启过了多久"),
+    ("system.cpu_usage", "Average share of time the processor cores were busy over the last second", "最近一秒内处理器各核心平均的忙碌比例"),
+    ("disks.section", "Each line is one drive or partition with its total and free space; nearly full disks cause failures", "每行是一个磁盘或分区及其总空间和剩余空间；快满的磁盘会导致程序出错"),
+];
+
+// --lang 优先，否则看 LC_ALL / LC_MESSAGES / LANG 是否以 zh 开头，默认英文
+pub fn init(lang: Option<Lang>) {
+    let lang = lang.unwrap_or_else(|| {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+
+        if locale.starts_with("zh") {
+            Lang::Zh
+        } else {
+            Lang::En
+        }
+    });
+
+    let _ = LANG.set(lang);
+}
+
+pub fn lang() -> Lang {
+    LANG.get().copied().unwrap_or(Lang::En)
+}
+
+pub fn t(key: &str) -> &'static str {
+    let lang = lang();
+
+    match MESSAGES.iter().find(|(k, _, _)| *k == key) {
+        Some((_, en, zh)) => match lang {
+            Lang::En => en,
+            Lang::Zh => zh,
+        },
+        None => panic!("missing message key: {}", key),
+    }
+}
+
+// 没有说明的指标返回 None
+pub fn explain(key: &str) -> Option<&'static str> {
+    let lang = lang();
+
+    EXPLANATIONS.iter().find(|(k, _, _)| *k == key).map(|(_, en, zh)| match lang {
+        Lang::En => *en,
+        Lang::Zh => *zh,
+    })
+}
+
+// 按顺序替换模板里的 {}
+pub fn tf(key: &str, args: &[&dyn Display]) -> String {
+    let mut parts = t(key).split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+
+    out
+}
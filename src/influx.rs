@@ -0,0 +1,124 @@
+use std::fmt::Write;
+
+use crate::clock;
+use crate::report::Report;
+
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+// InfluxDB 行协议：每个 measurement 一行 "名称,标签 字段 纳秒时间戳"，整数字段带 i 后缀；
+// 所有行都带 host 标签和 --label 的标签，时间戳取报告的采集时间
+pub fn render(report: &Report) -> String {
+    let collected_at = report.collected_at.unwrap_or_else(clock::now);
+    let mut out = Lines {
+        text: String::new(),
+        tags: Vec::new(),
+        timestamp: collected_at.timestamp_nanos_opt().unwrap_or_default(),
+    };
+    if let Some(host) = &report.system.host_name {
+        out.tags.push(("host".to_string(), host.clone()));
+    }
+    if let Some(meta) = &report.meta {
+        out.tags.extend(meta.labels.iter().map(|(key, value)| (key.clone(), value.clone())));
+    }
+
+    let system = &report.system;
+    out.line(
+        "system",
+        &[],
+        &[
+            ("cpu_usage", Some(Field::Float(system.average_cpu_usage as f64))),
+            ("memory_used", Some(Field::Integer(system.memory.used_bytes))),
+            ("memory_total", Some(Field::Integer(system.memory.total_bytes))),
+            ("memory_available", Some(Field::Integer(system.memory.available_bytes))),
+            ("swap_used", Some(Field::Integer(system.swap.used_bytes))),
+            ("swap_total", Some(Field::Integer(system.swap.total_bytes))),
+            ("uptime", Some(Field::Integer(system.uptime))),
+        ],
+    );
+
+    for gpu in &report.gpus {
+        let bytes = |gb: Option<f64>| gb.map(|gb| Field::Integer((gb * GB) as u64));
+        let whole = |value: Option<u32>| value.map(|value| Field::Integer(value as u64));
+        out.line(
+            "gpu",
+            &[
+                ("index", gpu.index.to_string()),
+                ("uuid", gpu.uuid.clone()),
+                ("name", gpu.name.clone().unwrap_or_default()),
+            ],
+            &[
+                ("temperature", whole(gpu.gpu_temperature)),
+                ("utilization", whole(gpu.utilization)),
+                ("fan_speed", whole(gpu.fan_speed)),
+                ("power_usage", gpu.power_usage.map(Field::Float)),
+                ("power_limit", whole(gpu.power_limit)),
+                ("memory_used", bytes(gpu.memory_used)),
+                ("memory_total", bytes(gpu.memory_total)),
+                ("core_clock", whole(gpu.core_clock)),
+                ("memory_clock", whole(gpu.memory_clock)),
+            ],
+        );
+    }
+
+    for disk in &system.disks {
+        out.line(
+            "disk",
+            &[("device", disk.name.clone()), ("mount_point", disk.mount_point.clone())],
+            &[
+                ("total", Some(Field::Integer((disk.total_space * GB) as u64))),
+                ("available", Some(Field::Integer((disk.available_space * GB) as u64))),
+                ("inodes_total", disk.inodes_total.map(Field::Integer)),
+                ("inodes_free", disk.inodes_free.map(Field::Integer)),
+                ("busy_percent", disk.busy_percent.map(Field::Float)),
+            ],
+        );
+    }
+
+    out.text
+}
+
+enum Field {
+    Integer(u64),
+    Float(f64),
+}
+
+struct Lines {
+    text: String,
+    tags: Vec<(String, String)>,
+    timestamp: i64,
+}
+
+impl Lines {
+    // 读不到的字段不写；一个字段都没有的行在行协议里不合法，整行跳过
+    fn line(&mut self, measurement: &str, tags: &[(&str, String)], fields: &[(&str, Option<Field>)]) {
+        let fields: Vec<String> = fields
+            .iter()
+            .filter_map(|(key, value)| {
+                let value = match value.as_ref()? {
+                    Field::Integer(value) => format!("{}i", value),
+                    Field::Float(value) => value.to_string(),
+                };
+                Some(format!("{}={}", key, value))
+            })
+            .collect();
+        if fields.is_empty() {
+            return;
+        }
+
+        self.text.push_str(measurement);
+        let tags = tags
+            .iter()
+            .map(|(key, value)| (*key, value))
+            .chain(self.tags.iter().map(|(key, value)| (key.as_str(), value)));
+        // 空值的标签在行协议里不合法
+        for (key, value) in tags.filter(|(_, value)| !value.is_empty()) {
+            write!(self.text, ",{}={}", escape(key), escape(value)).unwrap();
+        }
+        writeln!(self.text, " {} {}", fields.join(","), self.timestamp).unwrap();
+    }
+}
+
+// 标签的键和值里逗号、等号、空格要转义
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+}
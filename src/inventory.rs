@@ -0,0 +1,215 @@
+use std::process;
+
+use serde::Serialize;
+use sysinfo::{CpuRefreshKind, Disks, MemoryRefreshKind, RefreshKind, System};
+
+use crate::anonymize;
+use crate::cli::Cli;
+use crate::gpu::{get_gpus_info, GpuInfo};
+use crate::i18n::{t, tf};
+use crate::output::Format;
+use crate::render::{optional, print_table};
+use crate::report::SectionError;
+use crate::system::bytes_to_gb;
+
+// 资产登记用的不可变信息，不包含温度、频率这些随时变化的数据
+#[derive(Serialize)]
+struct GpuInventory<'a> {
+    index: u32,
+    name: Option<&'a str>,
+    uuid: &'a str,
+    serial: Option<&'a str>,
+    vbios_version: Option<&'a str>,
+    memory_total: Option<f64>,
+}
+
+impl<'a> From<&'a GpuInfo> for GpuInventory<'a> {
+    fn from(gpu: &'a GpuInfo) -> Self {
+        GpuInventory {
+            index: gpu.index,
+            name: gpu.name.as_deref(),
+            uuid: &gpu.uuid,
+            serial: gpu.serial.as_deref(),
+            vbios_version: gpu.vbios_version.as_deref(),
+            memory_total: gpu.memory_total,
+        }
+    }
+}
+
+pub fn run(cli: &Cli) {
+    let mut gpus = match get_gpus_info(&cli.gpu_indices(), !cli.no_wake, cli.gpu_backend) {
+        Ok(gpus) => gpus,
+        Err(e) => {
+            eprintln!("{}: {}", t("error"), e);
+            process::exit(2);
+        }
+    };
+
+    if let Some(scrub) = cli.scrub() {
+        anonymize::anonymize_gpus(&mut gpus, &scrub);
+    }
+
+    if cli.json || cli.format == Format::Json {
+        let inventory: Vec<GpuInventory> = gpus.iter().map(GpuInventory::from).collect();
+        println!("{}", serde_json::to_string_pretty(&inventory).unwrap());
+        return;
+    }
+
+    for gpu in &gpus {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            gpu.index,
+            optional(&gpu.name),
+            gpu.uuid,
+            optional(&gpu.serial),
+            optional(&gpu.vbios_version)
+        );
+    }
+}
+
+// --inventory：整机很少变化的硬件和系统信息，供 CMDB 导入；不做 CPU 采样、不拉天气，几乎立即返回
+#[derive(Serialize)]
+struct HardwareInventory<'a> {
+    host_name: Option<String>,
+    system_name: Option<String>,
+    os_version: Option<String>,
+    kernel_version: Option<String>,
+    cpu_model: Option<String>,
+    physical_cores: Option<usize>,
+    logical_cores: usize,
+    total_memory: f64,
+    gpus: Vec<GpuInventory<'a>>,
+    disks: Vec<DiskInventory>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<SectionError>,
+}
+
+#[derive(Serialize)]
+struct DiskInventory {
+    name: String,
+    mount_point: String,
+    model: Option<String>,
+    kind: String,
+    total_space: f64,
+}
+
+pub fn hardware(cli: &Cli) {
+    let sys = System::new_with_specifics(
+        RefreshKind::new()
+            .with_cpu(CpuRefreshKind::new())
+            .with_memory(MemoryRefreshKind::new().with_ram()),
+    );
+    let disks = Disks::new_with_refreshed_list();
+
+    let mut errors = Vec::new();
+    let mut gpus = get_gpus_info(&cli.gpu_indices(), !cli.no_wake, cli.gpu_backend).unwrap_or_else(|e| {
+        errors.push(SectionError {
+            section: "gpu".to_string(),
+            error: e.to_string(),
+        });
+        Vec::new()
+    });
+    let scrub = cli.scrub();
+    let mut host_name = System::host_name();
+    if let Some(scrub) = &scrub {
+        anonymize::anonymize_gpus(&mut gpus, scrub);
+        host_name = host_name.map(|name| scrub.value(&name));
+    }
+
+    let disks = disks
+        .list()
+        .iter()
+        .map(|disk| {
+            let name = disk.name().to_string_lossy().into_owned();
+            let mount_point = disk.mount_point().to_string_lossy().into_owned();
+            DiskInventory {
+                model: disk_model(&name),
+                name: scrub.as_ref().map_or(name.clone(), |scrub| scrub.value(&name)),
+                mount_point: scrub.as_ref().map_or(mount_point.clone(), |scrub| scrub.value(&mount_point)),
+                kind: format!("{:?}", disk.kind()),
+                total_space: bytes_to_gb(disk.total_space()),
+            }
+        })
+        .collect();
+
+    let inventory = HardwareInventory {
+        host_name,
+        system_name: System::name(),
+        os_version: System::os_version(),
+        kernel_version: System::kernel_version(),
+        cpu_model: sys.cpus().first().map(|cpu| cpu.brand().trim().to_string()),
+        physical_cores: sys.physical_core_count(),
+        logical_cores: sys.cpus().len(),
+        total_memory: bytes_to_gb(sys.total_memory()),
+        gpus: gpus.iter().map(GpuInventory::from).collect(),
+        disks,
+        errors,
+    };
+
+    if cli.json || cli.format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&inventory).unwrap());
+        return;
+    }
+
+    for error in &inventory.errors {
+        println!("{}: {}", t("error"), error.error);
+    }
+    println!("{}: {}", t("system.host_name"), optional(&inventory.host_name));
+    println!("{}: {}", t("system.name"), optional(&inventory.system_name));
+    println!("{}: {}", t("system.os_version"), optional(&inventory.os_version));
+    println!("{}: {}", t("system.kernel_version"), optional(&inventory.kernel_version));
+    println!(
+        "{}: {} ({})",
+        t("inventory.cpu"),
+        optional(&inventory.cpu_model),
+        tf("inventory.cores", &[&optional(&inventory.physical_cores), &inventory.logical_cores])
+    );
+    println!("{}: {:.2} GB", t("system.total_memory"), inventory.total_memory);
+    for gpu in &inventory.gpus {
+        println!("{}", tf("gpu.section", &[&gpu.index]));
+        println!("{}: {}", t("gpu.name"), optional(&gpu.name));
+        println!("{}: {}", t("gpu.memory_total"), optional(&gpu.memory_total.map(|total| format!("{:.2} GB", total))));
+        println!("{}: {}", t("gpu.serial"), optional(&gpu.serial));
+        println!("{}: {}", t("gpu.uuid"), gpu.uuid);
+        println!("{}: {}", t("gpu.vbios_version"), optional(&gpu.vbios_version));
+    }
+    println!("{}", t("disks.section"));
+    let rows: Vec<Vec<String>> = inventory
+        .disks
+        .iter()
+        .map(|disk| {
+            vec![
+                disk.name.clone(),
+                disk.model.clone().unwrap_or_default(),
+                disk.kind.clone(),
+                disk.mount_point.clone(),
+                format!("{:.2} GB", disk.total_space),
+            ]
+        })
+        .collect();
+    print_table(
+        &[t("disks.name"), t("inventory.disk_model"), t("disks.kind"), t("disks.mount_point"), t("disks.total")],
+        &rows,
+    );
+}
+
+// /sys/class/block/<名称>/device/model；分区没有 device 目录，要看它所在的整块盘
+#[cfg(target_os = "linux")]
+fn disk_model(device: &str) -> Option<String> {
+    use std::fs;
+    use std::path::Path;
+
+    let path = fs::canonicalize(device).unwrap_or_else(|_| device.into());
+    let mut dir = fs::canonicalize(Path::new("/sys/class/block").join(path.file_name()?)).ok()?;
+    if dir.join("partition").exists() {
+        dir = dir.parent()?.to_path_buf();
+    }
+
+    let model = fs::read_to_string(dir.join("device/model")).ok()?;
+    Some(model.trim().to_string()).filter(|model| !model.is_empty())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn disk_model(_device: &str) -> Option<String> {
+    None
+}
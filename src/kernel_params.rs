@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+// 排查机器行为异常时常看的内核参数，读不到的项为 None
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KernelParams {
+    pub cmdline: Option<String>,
+    pub swappiness: Option<u32>,
+    pub overcommit_memory: Option<u32>,
+    pub transparent_hugepage: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn read() -> Option<KernelParams> {
+    let read = |path: &str| std::fs::read_to_string(path).ok().map(|text| text.trim().to_string());
+
+    Some(KernelParams {
+        cmdline: read("/proc/cmdline"),
+        swappiness: read("/proc/sys/vm/swappiness").and_then(|value| value.parse().ok()),
+        overcommit_memory: read("/proc/sys/vm/overcommit_memory").and_then(|value| value.parse().ok()),
+        // 文件内容形如 "always [madvise] never"，方括号里是当前选中的
+        transparent_hugepage: read("/sys/kernel/mm/transparent_hugepage/enabled").map(|value| {
+            value
+                .split_whitespace()
+                .find_map(|mode| mode.strip_prefix('[')?.strip_suffix(']'))
+                .map(str::to_string)
+                .unwrap_or(value)
+        }),
+    })
+}
+
+// 其它系统没有这些文件，整个部分不输出
+#[cfg(not(target_os = "linux"))]
+pub fn read() -> Option<KernelParams> {
+    None
+}
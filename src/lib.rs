@@ -0,0 +1,110 @@
+// 采集和输出都在库里，main.rs 只负责解析参数和分派
+pub mod aggregate;
+pub mod anonymize;
+pub mod boot_security;
+pub mod cgroup;
+pub mod cli;
+pub mod clock;
+pub mod config;
+pub mod cpu_cache;
+pub mod cpu_throttle;
+pub mod cpu_times;
+pub mod crash;
+pub mod custom_metrics;
+pub mod delta;
+pub mod disk_latency;
+pub mod diskstats;
+pub mod drm;
+pub mod encryption;
+pub mod fields;
+pub mod gpu;
+pub mod health;
+pub mod history;
+pub mod host;
+pub mod i18n;
+pub mod influx;
+pub mod inventory;
+pub mod kernel_params;
+pub mod log_file;
+pub mod memory_modules;
+pub mod memory_pressure;
+#[cfg(feature = "weather")]
+pub mod meizu;
+pub mod metrics;
+pub mod mock;
+pub mod net_top;
+pub mod numa;
+pub mod nvidia_smi;
+#[cfg(feature = "weather")]
+pub mod openweathermap;
+pub mod output;
+pub mod pager;
+pub mod privileges;
+pub mod processes;
+pub mod rapl;
+pub mod render;
+pub mod report;
+pub mod self_stats;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "smart")]
+pub mod smart;
+pub mod snapshot_diff;
+pub mod sqlite;
+pub mod system;
+pub mod virtualization;
+pub mod watch;
+pub mod weather;
+#[cfg(feature = "weather")]
+pub mod weather_cache;
+
+use cli::Cli;
+use i18n::tf;
+use report::{GpuCache, Report};
+
+pub fn collect(cli: &Cli) -> Report {
+    collect_cached(cli, None)
+}
+
+// --watch 用：cache 里的 GPU 静态信息跨轮复用
+pub fn collect_cached(cli: &Cli, cache: Option<&mut GpuCache>) -> Report {
+    let mut report = report::collect_report_cached(&cli.collect_options(), cache);
+
+    if let Some(scrub) = cli.scrub() {
+        anonymize::anonymize(&mut report, &scrub);
+    }
+
+    report
+}
+
+// --strict 时每个失败的部分一条消息，调用方打印后以 4 退出；显式给了 --gpu-optional 时不管 GPU 的错误
+pub fn strict_failures(cli: &Cli, report: &Report) -> Vec<String> {
+    if !cli.strict {
+        return Vec::new();
+    }
+
+    report
+        .errors
+        .iter()
+        .filter(|error| !(cli.gpu_optional && error.section == "gpu"))
+        .map(|error| tf("strict.failed", &[&error.section, &error.error]))
+        .collect()
+}
+
+// --crit-on-throttle 时每个 GPU 的每个严重降频原因一条消息，调用方打印后以 5 退出
+pub fn throttle_alerts(cli: &Cli, report: &Report) -> Vec<String> {
+    if !cli.crit_on_throttle {
+        return Vec::new();
+    }
+
+    report
+        .gpus
+        .iter()
+        .flat_map(|gpu| {
+            gpu.throttle_reasons
+                .iter()
+                .filter(|reason| gpu::CRITICAL_THROTTLE_REASONS.contains(&reason.as_str()))
+                .map(|reason| tf("throttle.critical", &[&gpu.index, reason]))
+        })
+        .collect()
+}
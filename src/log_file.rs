@@ -0,0 +1,109 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::clock;
+use crate::render::optional;
+use crate::report::Report;
+
+// watch 模式每轮追加一行摘要（--history 是一行 JSON）；超过 max_size 时把 log 依次改名为 log.1 … log.N，最旧的删掉
+pub struct RotatingLog {
+    path: PathBuf,
+    max_size: u64,
+    keep: u32,
+    file: File,
+    size: u64,
+}
+
+impl RotatingLog {
+    pub fn open(path: &Path, max_size: u64, keep: u32) -> Result<Self, String> {
+        let file = append(path)?;
+        let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+        Ok(RotatingLog {
+            path: path.to_path_buf(),
+            max_size,
+            keep,
+            file,
+            size,
+        })
+    }
+
+    pub fn record(&mut self, report: &Report) -> Result<(), String> {
+        self.append_line(&format!("{} {}", clock::now().format("%Y-%m-%dT%H:%M:%S%:z"), summary(report)))
+    }
+
+    pub fn append_line(&mut self, line: &str) -> Result<(), String> {
+        let line = format!("{}\n", line);
+
+        if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+        self.size += line.len() as u64;
+
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<(), String> {
+        if self.keep == 0 {
+            fs::remove_file(&self.path).map_err(|e| e.to_string())?;
+        } else {
+            let _ = fs::remove_file(self.rotated(self.keep));
+            for n in (1..self.keep).rev() {
+                let from = self.rotated(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated(n + 1)).map_err(|e| e.to_string())?;
+                }
+            }
+            fs::rename(&self.path, self.rotated(1)).map_err(|e| e.to_string())?;
+        }
+
+        self.file = append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn rotated(&self, n: u32) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+}
+
+fn append(path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// 例如：cpu 12.5% mem 7.81/31.90 GB | gpu0 61C 97% 212.4W 10.20/24.00 GB
+fn summary(report: &Report) -> String {
+    let system = &report.system;
+    let mut line = format!(
+        "cpu {:.1}% mem {:.2}/{:.2} GB",
+        system.average_cpu_usage, system.used_memory, system.total_memory
+    );
+
+    for gpu in &report.gpus {
+        let memory = match (gpu.memory_used, gpu.memory_total) {
+            (Some(used), Some(total)) => format!("{:.2}/{:.2} GB", used, total),
+            _ => "N/A".to_string(),
+        };
+        line.push_str(&format!(
+            " | gpu{} {}C {}% {}W {}",
+            gpu.index,
+            optional(&gpu.gpu_temperature),
+            optional(&gpu.utilization),
+            optional(&gpu.power_usage.map(|power| format!("{:.1}", power))),
+            memory
+        ));
+    }
+    if let Some(e) = report.error("gpu") {
+        line.push_str(&format!(" | gpu error: {}", e));
+    }
+
+    line
+}
@@ -1,419 +1,343 @@
-use std::ffi::OsStr;
+use std::env;
+use std::fs;
 use std::path::Path;
-use std::thread;
-use std::time::Duration;
-use nvml_wrapper::Nvml;
-use nvml_wrapper::enum_wrappers;
-use reqwest::Client;
-
-use sysinfo::{CpuRefreshKind, DiskKind, Disks, RefreshKind, System};
-use tokio::runtime::Runtime;
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Deserialize, Serialize)]
-struct Alarm {
-    alarmContent: String,
-    alarmDesc: String,
-    alarmId: String,
-    alarmLevelNo: String,
-    alarmLevelNoDesc: String,
-    alarmType: String,
-    alarmTypeDesc: String,
-    precaution: String,
-    publishTime: String,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct Index {
-    abbreviation: String,
-    alias: String,
-    content: String,
-    level: String,
-    name: String,
-}
+use std::process;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use system_details::cli::{Cli, Command};
+use system_details::health::Health;
+use system_details::i18n::{self, t, tf};
+use system_details::output::{pretty_json, Format, Output};
+use system_details::report::{self, Report};
+use system_details::system::format_bytes;
+use system_details::weather::{self, Provider};
+use system_details::{
+    aggregate, anonymize, clock, collect, config, crash, fields, gpu, history, inventory, mock, pager, render,
+    self_stats, snapshot_diff, strict_failures, throttle_alerts, watch,
+};
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Pm25 {
-    advice: String,
-    aqi: String,
-    citycount: i32,
-    cityrank: i32,
-    co: String,
-    color: String,
-    level: String,
-    no2: String,
-    o3: String,
-    pm10: String,
-    pm25: String,
-    quality: String,
-    so2: String,
-    timestamp: String,
-    upDateTime: String,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct Realtime {
-    img: String,
-    sD: String,
-    sendibleTemp: String,
-    temp: String,
-    time: String,
-    wD: String,
-    wS: String,
-    weather: String,
-    ziwaixian: String,
+fn main() {
+    // 语言也可能来自配置文件，读配置出错时只能按环境变量选语言
+    let cli = match config::resolve(env::args_os().collect()) {
+        Ok(cli) => cli,
+        Err(e) => {
+            i18n::init(None);
+            eprintln!("{}: {}", t("error"), e);
+            process::exit(2);
+        }
+    };
+    i18n::init(cli.lang);
+    clock::init(cli.timezone);
+    crash::install_hook();
+
+    let start = Instant::now();
+    let code = run(&cli);
+    print_self_stats(&cli, start);
+    if code != 0 {
+        process::exit(code);
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct WeatherDetailsInfo {
-    publishTime: String,
-    weather3HoursDetailsInfos: Vec<Weather3HoursDetailsInfo>,
-}
+// 返回退出码；参数、配置之类的错误在出错的地方直接退出
+fn run(cli: &Cli) -> i32 {
+    if cli.print_config {
+        config::print(cli);
+        return 0;
+    }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Weather3HoursDetailsInfo {
-    endTime: String,
-    highestTemperature: String,
-    img: String,
-    isRainFall: String,
-    lowerestTemperature: String,
-    precipitation: String,
-    startTime: String,
-    wd: String,
-    weather: String,
-    ws: String,
-}
+    if cli.verify_nvml {
+        verify_nvml();
+        return 0;
+    }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Weather {
-    aqi: String,
-    date: String,
-    img: String,
-    sun_down_time: String,
-    sun_rise_time: String,
-    temp_day_c: String,
-    temp_day_f: String,
-    temp_night_c: String,
-    temp_night_f: String,
-    wd: String,
-    weather: String,
-    week: String,
-    ws: String,
-}
+    if cli.list_gpus {
+        inventory::run(cli);
+        return 0;
+    }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct ApiResponse {
-    code: String,
-    message: String,
-    redirect: String,
-    value: Vec<Value>,
-}
+    if cli.inventory {
+        inventory::hardware(cli);
+        return 0;
+    }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Value {
-    alarms: Vec<Alarm>,
-    city: String,
-    cityid: i32,
-    indexes: Vec<Index>,
-    pm25: Pm25,
-    provinceName: String,
-    realtime: Realtime,
-    weatherDetailsInfo: WeatherDetailsInfo,
-    weathers: Vec<Weather>,
-}
+    if cli.bench {
+        bench(cli);
+        return 0;
+    }
 
-#[derive(Debug)]
-struct GpuInfo {
-    name: String,
-    num_cores: u32,
-    memory_bus_width: u32,
-    core_clock: u32,
-    memory_clock: u32,
-    gpu_temperature: u32,
-    power_usage: f64,
-    power_limit: u32,
-    memory_used: f64,
-    memory_total: f64,
-}
+    if let Some(Command::Get { path }) = &cli.command {
+        get(cli, path);
+        return 0;
+    }
 
-fn get_gpu_info() -> Result<GpuInfo, nvml_wrapper::error::NvmlError> {
-    let nvml = Nvml::init()?;
-    let device = nvml.device_by_index(0)?;
-    let power_limit = device.enforced_power_limit()?;
-    let memory_info = device.memory_info()?;
-    let power_usage = device.power_usage()?;
-    let gpu_temperature = device.temperature(enum_wrappers::device::TemperatureSensor::Gpu)?;
-    let core_clock = device.clock(enum_wrappers::device::Clock::Graphics, enum_wrappers::device::ClockId::Current)?;
-    let memory_clock = device.clock(enum_wrappers::device::Clock::Memory, enum_wrappers::device::ClockId::Current)?;
-    let name = device.name()?;
-    let num_cores = device.num_cores()?;
-    let memory_bus_width = device.memory_bus_width()?;
-
-    Ok(GpuInfo {
-        name,
-        num_cores,
-        memory_bus_width,
-        core_clock,
-        memory_clock,
-        gpu_temperature,
-        power_usage: power_usage as f64 / 1000.0,
-        power_limit: power_limit / 1000,
-        memory_used: memory_info.used as f64 / (1024.0 * 1024.0 * 1024.0),
-        memory_total: memory_info.total as f64 / (1024.0 * 1024.0 * 1024.0),
-    })
-}
+    if let Some(Command::Weather) = &cli.command {
+        return weather(cli);
+    }
 
-#[derive(Debug)]
-struct SystemInfo {
-    total_memory: f64,
-    used_memory: f64,
-    total_swap: f64,
-    used_swap: f64,
-    system_name: Option<String>,
-    kernel_version: Option<String>,
-    os_version: Option<String>,
-    host_name: Option<String>,
-    uptime: u64,
-    disks: Vec<DiskInfo>,
-    average_cpu_usage: f32,
-}
+    if let Some(port) = cli.serve {
+        serve(port, cli);
+        return 0;
+    }
 
-#[derive(Debug)]
-struct DiskInfo {
-    name: String,
-    kind: DiskKind,
-    file_system: String,
-    mount_point: String,
-    total_space: f64,
-    available_space: f64,
-}
+    if let Some(count) = cli.sample_count {
+        aggregate::run(cli, count);
+        return 0;
+    }
 
-fn get_system_info() -> SystemInfo {
-    let mut sys = System::new_all();
-    sys.refresh_all();
+    if cli.gpu_dmon {
+        watch::dmon(cli);
+        return 0;
+    }
 
-    let total_memory = bytes_to_gb(sys.total_memory());
-    let used_memory = bytes_to_gb(sys.used_memory());
-    let total_swap = bytes_to_gb(sys.total_swap());
-    let used_swap = bytes_to_gb(sys.used_swap());
+    if let (Some(path), Some(duration)) = (&cli.capture, cli.duration) {
+        if let Err(e) = watch::capture(cli, path, duration) {
+            eprintln!("{}: {}", t("error"), e);
+            process::exit(2);
+        }
+        return 0;
+    }
 
-    let system_name = System::name();
-    let kernel_version = System::kernel_version();
-    let os_version = System::os_version();
-    let host_name = System::host_name();
+    // --interval 0 等同于单次运行
+    if cli.watch && !cli.interval.is_zero() {
+        return watch::run(cli);
+    }
 
-    let uptime = System::uptime();
-    let disks = get_disk_info();
+    let report = match &cli.from_json {
+        Some(path) => match load_report(path, cli) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("{}: {}", t("error"), e);
+                process::exit(2);
+            }
+        },
+        None => {
+            let report = collect(cli);
+            exit_if_strict_failed(cli, &report);
+            report
+        }
+    };
+    if let Some(path) = &cli.diff {
+        return diff(cli, path, &report);
+    }
+    let mut output = match Output::new(cli) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    // JSON 之类的格式多半是给脚本的，写文件时也用不上 pager
+    let pager = (cli.pager && output.is_text() && cli.output.is_none()).then(pager::start).flatten();
+    let emitted = output.emit(&report);
+    if let Some(pager) = pager {
+        pager.finish();
+    }
+    if let Err(e) = emitted {
+        eprintln!("{}", e);
+        process::exit(2);
+    }
 
-    let mut s = System::new_with_specifics(RefreshKind::new().with_cpu(CpuRefreshKind::everything()));
+    let recorded = cli.open_history().and_then(|log| match log {
+        Some(mut log) => history::record(&mut log, &report),
+        None => Ok(()),
+    });
+    if let Err(e) = recorded {
+        eprintln!("history: {}", e);
+        process::exit(2);
+    }
 
-    thread::sleep(Duration::from_secs(1));
-    s.refresh_cpu();
+    let alerts = throttle_alerts(cli, &report);
+    if !alerts.is_empty() {
+        for alert in alerts {
+            eprintln!("{}", alert);
+        }
+        return 5;
+    }
 
-    let mut total_cpu_usage = 0.0;
-    for cpu in s.cpus() {
-        total_cpu_usage += cpu.cpu_usage();
+    // 默认没有 GPU 也正常退出，方便同一个脚本跑在有卡和没卡的机器上
+    if cli.gpu_required && report.gpus.is_empty() {
+        eprintln!("{}", t("gpu.required_missing"));
+        return 3;
     }
-    let average_cpu_usage = if !s.cpus().is_empty() {
-        total_cpu_usage / s.cpus().len() as f32
-    } else {
-        0.0
-    };
 
-    SystemInfo {
-        total_memory,
-        used_memory,
-        total_swap,
-        used_swap,
-        system_name,
-        kernel_version,
-        os_version,
-        host_name,
-        uptime,
-        disks,
-        average_cpu_usage,
+    match report.health.filter(|_| cli.health_exit) {
+        Some(Health::Critical) => 7,
+        Some(Health::Warning) => 6,
+        _ => 0,
     }
 }
 
-fn os_str_to_option_string(os_str: Option<&OsStr>) -> Option<String> {
-    os_str.map(|s| s.to_string_lossy().into_owned())
+// --strict：不输出不完整的报告
+fn exit_if_strict_failed(cli: &Cli, report: &Report) {
+    let failures = strict_failures(cli, report);
+    if failures.is_empty() {
+        return;
+    }
+    for failure in failures {
+        eprintln!("{}", failure);
+    }
+    process::exit(4);
 }
 
-fn path_to_string(path: &Path) -> String {
-    path.to_string_lossy().into_owned()
+// --self-stats：写到 stderr，不混进报告
+fn print_self_stats(cli: &Cli, start: Instant) {
+    if !cli.self_stats {
+        return;
+    }
+    let Some(stats) = self_stats::read() else {
+        return;
+    };
+
+    let seconds = |duration: Duration| format!("{:.2}", duration.as_secs_f64());
+    eprintln!(
+        "{}",
+        tf(
+            "self_stats.line",
+            &[
+                &seconds(stats.user_cpu + stats.system_cpu),
+                &seconds(stats.user_cpu),
+                &seconds(stats.system_cpu),
+                &format_bytes(stats.peak_rss_bytes as f64, cli.units),
+                &seconds(start.elapsed()),
+            ]
+        )
+    );
 }
 
-fn get_disk_info() -> Vec<DiskInfo> {
-    let mut disk_info_list: Vec<DiskInfo> = Vec::new();
-
-    let disks = Disks::new_with_refreshed_list();
-    for disk in disks.list() {
-        let disk_info = DiskInfo {
-            name: os_str_to_option_string(Option::from(disk.name())).unwrap(),
-            kind: Option::from(disk.kind()).unwrap(),
-            file_system: os_str_to_option_string(Option::from(disk.file_system())).unwrap(),
-            mount_point: path_to_string(disk.mount_point()).into(),
-            total_space: bytes_to_gb(disk.total_space()),
-            available_space: bytes_to_gb(disk.available_space()),
-        };
-        disk_info_list.push(disk_info);
-    }
+// 各阶段按名称排序，最后是总耗时
+fn bench(cli: &Cli) {
+    let report = collect(cli);
+    let Some(meta) = &report.meta else {
+        return;
+    };
 
-    disk_info_list
+    if cli.json || cli.format == Format::Json {
+        let mut timings = meta.timings_ms.clone();
+        timings.insert("total".to_string(), meta.duration_ms);
+        println!("{}", serde_json::to_string(&timings).unwrap_or_default());
+        return;
+    }
+    let mut phases: Vec<String> = meta
+        .timings_ms
+        .iter()
+        .map(|(phase, ms)| tf("bench.phase", &[phase, ms]))
+        .collect();
+    phases.push(tf("bench.phase", &[&t("bench.total"), &meta.duration_ms]));
+    println!("{}", phases.join(", "));
 }
 
-fn main() {
-    match get_gpu_info() {
-        Ok(gpu_info) => {
-            println!("GPU Name: {}", gpu_info.name);
-            println!("Number of Cores: {}", gpu_info.num_cores);
-            println!("Memory Bus Width: {}-bit bus width", gpu_info.memory_bus_width);
-            println!("GPU Core Clocks: {} MHz", gpu_info.core_clock);
-            println!("GPU Memory Clock: {} MHz", gpu_info.memory_clock);
-            println!("GPU Temperature: {} C", gpu_info.gpu_temperature);
-            println!("Power Usage: {} W", gpu_info.power_usage);
-            println!("Power Limit: {} W", gpu_info.power_limit);
-            println!("Memory Used: {:.2} GB", gpu_info.memory_used);
-            println!("Memory Total: {:.2} GB", gpu_info.memory_total);
+fn verify_nvml() {
+    match gpu::verify_nvml() {
+        Ok(status) => println!(
+            "{}",
+            tf("nvml.ok", &[&status.driver_version, &status.nvml_version, &status.device_count])
+        ),
+        Err(e) => {
+            eprintln!("{}: {}", t("error"), e);
+            process::exit(2);
         }
-        Err(e) => println!("Error: {}", e),
     }
+}
 
-    let system_info = get_system_info();
-
-    println!("=> system:");
-    println!("Total Memory: {:.2} GB", system_info.total_memory);
-    println!("Used Memory: {:.2} GB", system_info.used_memory);
-    println!("Total Swap: {:.2} GB", system_info.total_swap);
-    println!("Used Swap: {:.2} GB", system_info.used_swap);
-    println!("System Name: {:?}", system_info.system_name);
-    println!("Kernel Version: {:?}", system_info.kernel_version);
-    println!("OS Version: {:?}", system_info.os_version);
-    println!("Host Name: {:?}", system_info.host_name);
-    let (days, hours, minutes, remaining_seconds) = convert_seconds(system_info.uptime);
-    println!("Uptime {} seconds is equivalent to {} days, {} hours, {} minutes, and {} seconds", System::uptime(), days, hours, minutes, remaining_seconds);
-    println!("Average CPU Usage: {:.2}%", system_info.average_cpu_usage);
-
-    println!("=> disks:");
-    for disk in system_info.disks {
-        println!(
-            "{:?}\t{:?}\t{:?}\t{:?}\t{:.2} GB\t{:.2} GB",
-            disk.name,
-            disk.kind,
-            disk.file_system,
-            disk.mount_point,
-            disk.total_space,
-            disk.available_space
-        );
+// 只打印一个值，不带标签，方便在脚本里用 $(...) 取值
+fn get(cli: &Cli, path: &str) {
+    let mut options = cli.collect_options();
+    // 天气要联网，只有取天气字段时才请求
+    options.weather &= path.starts_with("weather");
+    let mut report = report::collect_report(&options);
+    exit_if_strict_failed(cli, &report);
+    if let Some(scrub) = cli.scrub() {
+        anonymize::anonymize(&mut report, &scrub);
     }
 
-
-    let mut rtt =  Runtime::new().unwrap();
-    rtt.block_on(async {
-        // 创建一个HTTP客户端
-        let client = Client::new();
-
-        // 发送GET请求并等待响应
-        let response = client
-            .get("https://api.oioweb.cn/api/weather/GetWeather")
-            .send()
-            .await
-            .unwrap();
-
-        // 检查响应状态码
-        if response.status().is_success() {
-            // 读取响应的内容
-            let body = response.text().await.unwrap();
-            println!("请求成功: {}", body);
-        } else {
-            println!("请求失败: {}", response.status());
+    let value = serde_json::to_value(&report).expect("report serializes to JSON");
+    match fields::resolve(&value, path) {
+        Some(Value::Null) => {
+            eprintln!("{}", tf("get.no_value", &[&path]));
+            process::exit(1);
         }
-    });
+        Some(Value::String(text)) => println!("{}", text),
+        Some(value) => println!("{}", value),
+        None => {
+            eprintln!("{}", fields::unknown_field(&value, path));
+            process::exit(1);
+        }
+    }
+}
 
-    // 创建一个运行时环境
-    let mut rt = Runtime::new().unwrap();
-
-    // 在运行时环境中执行异步任务
-    rt.block_on(async {
-        // 创建一个HTTP客户端
-        let client = Client::new();
-
-        // 发送GET请求并等待响应
-        let response = client
-            .get("https://aider.meizu.com/app/weather/listWeather?cityIds=101200105")
-            .send()
-            .await
-            .unwrap();
-
-        // 检查响应状态码
-        if response.status().is_success() {
-            // 读取响应的内容
-            let body = response.text().await.unwrap();
-
-            // 打印返回的数据
-            let response: ApiResponse = serde_json::from_str(body.as_str()).unwrap();
-
-            println!("Code: {}", response.code);
-            println!("Message: {}", response.message);
-            println!("Redirect: {}", response.redirect);
-
-            for value in response.value {
-                for alarm in value.alarms {
-                    println!("Alarm Content: {}", alarm.alarmContent);
-                    println!("Alarm Description: {}", alarm.alarmDesc);
-                    println!("Alarm ID: {}", alarm.alarmId);
-                    println!("Alarm Level: {}", alarm.alarmLevelNoDesc);
-                    println!("Alarm Type: {}", alarm.alarmTypeDesc);
-                    println!("Precaution: {}", alarm.precaution);
-                    println!("Publish Time: {}", alarm.publishTime);
-                    println!("------------------------");
-                }
-
-                println!("City: {}", value.city);
-                println!("City ID: {}", value.cityid);
-
-                for index in value.indexes {
-                    println!("Index Name: {}", index.name);
-                    println!("Index Level: {}", index.level);
-                    println!("Index Content: {}", index.content);
-                    println!("------------------------");
-                }
-
-                println!("PM2.5 Quality: {}", value.pm25.quality);
-                println!("PM2.5 AQI: {}", value.pm25.aqi);
-
-                println!("Province Name: {}", value.provinceName);
-
-                println!("Realtime Weather: {}", value.realtime.weather);
-                println!("Realtime Temperature: {}", value.realtime.temp);
-                println!("Realtime Wind: {} {}", value.realtime.wD, value.realtime.wS);
-
-                for weather in value.weathers {
-                    println!("Weather Date: {}", weather.date);
-                    println!("Weather: {}", weather.weather);
-                    println!("Day Temperature: {}", weather.temp_day_c);
-                    println!("Night Temperature: {}", weather.temp_night_c);
-                    println!("------------------------");
-                }
+#[cfg(feature = "serve")]
+fn serve(port: u16, cli: &Cli) {
+    system_details::serve::run(port, cli);
+}
+
+// 没有 serve 特性时 --serve 直接报错退出
+#[cfg(not(feature = "serve"))]
+fn serve(_port: u16, _cli: &Cli) {
+    eprintln!("{}: {}", t("error"), t("serve.disabled"));
+    process::exit(2);
+}
+
+// 天气请求失败时以 1 退出；oioweb 只有原始响应可打印，请求失败时以 2 退出
+fn weather(cli: &Cli) -> i32 {
+    let query = cli.collect_options().weather_query;
+    if query.provider == Provider::Oioweb && !cli.mock {
+        return match weather::print_raw_weather(query.base_url.as_deref()) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("{}: {}", t("request_failed"), e);
+                2
             }
-        } else {
-            println!("请求失败: {}", response.status());
+        };
+    }
+
+    let forecast = if cli.mock { Ok(mock::weather()) } else { weather::fetch(&query) };
+    let forecast = match forecast {
+        Ok(forecast) => forecast,
+        Err(e) => {
+            eprintln!("{}: {}", t("request_failed"), e);
+            return 1;
         }
-    });
+    };
+    if cli.json || cli.format == Format::Json {
+        let json = if cli.pretty { serde_json::to_string_pretty(&forecast) } else { serde_json::to_string(&forecast) };
+        println!("{}", json.unwrap_or_default());
+    } else {
+        render::print_forecast(&forecast, cli.weather_verbose);
+    }
+    0
 }
 
-fn bytes_to_gb(bytes: u64) -> f64 {
-    // 1 GB = 1024^3 bytes
-    let gb = bytes as f64 / 1024_f64.powi(3);
-    gb
+fn diff(cli: &Cli, path: &Path, report: &Report) -> i32 {
+    let old = match load_report(path, cli) {
+        Ok(old) => old,
+        Err(e) => {
+            eprintln!("{}: {}", t("error"), e);
+            return 2;
+        }
+    };
+
+    let changes = snapshot_diff::changes(&old, report);
+    if cli.json || cli.format == Format::Json {
+        let json =
+            if pretty_json(cli) { serde_json::to_string_pretty(&changes) } else { serde_json::to_string(&changes) };
+        println!("{}", json.expect("changes serialize to JSON"));
+    } else {
+        snapshot_diff::print(&changes);
+    }
+
+    0
 }
 
-fn convert_seconds(seconds: u64) -> (u64, u64, u64, u64) {
-    let days = seconds / (24 * 3600);
-    let hours = (seconds / 3600) % 24;
-    let minutes = (seconds / 60) % 60;
-    let remaining_seconds = seconds % 60;
+// 读取之前保存的 JSON 报告，代替现场采集；渲染、--fields 和 --gpu-required 的逻辑都照常
+fn load_report(path: &Path, cli: &Cli) -> Result<Report, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut report: Report = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
 
-    (days, hours, minutes, remaining_seconds)
-}
\ No newline at end of file
+    if let Some(scrub) = cli.scrub() {
+        anonymize::anonymize(&mut report, &scrub);
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,279 @@
+use chrono::{NaiveDateTime, NaiveTime};
+use reqwest::Client;
+use serde::Deserialize;
+use url::Url;
+
+use crate::clock;
+use crate::i18n::tf;
+use crate::weather::{
+    block_on, endpoint, get_json, AirQuality, CurrentWeather, DailyForecast, LifeIndex, Provider, WeatherAlert, WeatherProvider,
+    WeatherQuery, WeatherReport,
+};
+
+const DEFAULT_URL: &str = "https://aider.meizu.com/app/weather/listWeather";
+
+// 魅族天气，按城市编号查询，只覆盖国内城市
+pub struct Meizu {
+    city_id: String,
+    url: Url,
+}
+
+impl Meizu {
+    // --weather-url 给的是完整的 listWeather 地址
+    pub fn from_query(query: &WeatherQuery) -> Result<Self, String> {
+        Ok(Meizu {
+            city_id: query.city_id.clone(),
+            url: endpoint(query.base_url.as_deref().unwrap_or(DEFAULT_URL), "")?,
+        })
+    }
+}
+
+impl WeatherProvider for Meizu {
+    fn fetch(&self) -> Result<WeatherReport, String> {
+        let response: ApiResponse = block_on(get_json(
+            Client::new()
+                .get(self.url.clone())
+                .query(&[("cityIds", self.city_id.as_str())]),
+        ))??;
+
+        Ok(into_report(city_value(response, &self.city_id)?))
+    }
+}
+
+// 魅族天气接口的原始结构，只保留用到的字段；字段名大多是 camelCase。
+// 出错时 message 和 value 可能是 null
+#[derive(Debug, Deserialize)]
+struct ApiResponse {
+    code: String,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    value: Option<Vec<Value>>,
+}
+
+// HTTP 200 之外接口还有自己的 code，不是 "200" 时 value 里没有可用的数据
+fn city_value(response: ApiResponse, city_id: &str) -> Result<Value, String> {
+    if response.code != "200" {
+        return Err(api_error(&response.code, response.message.as_deref().unwrap_or_default()));
+    }
+
+    response
+        .value
+        .unwrap_or_default()
+        .into_iter()
+        .next()
+        .ok_or_else(|| tf("weather.no_data", &[&city_id]))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Value {
+    alarms: Vec<Alarm>,
+    city: String,
+    cityid: i32,
+    indexes: Vec<Index>,
+    pm25: Pm25,
+    province_name: String,
+    realtime: Realtime,
+    weathers: Vec<Weather>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Alarm {
+    alarm_content: String,
+    alarm_desc: String,
+    alarm_id: String,
+    alarm_level_no_desc: String,
+    alarm_type_desc: String,
+    precaution: String,
+    publish_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Index {
+    content: String,
+    level: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pm25 {
+    aqi: String,
+    quality: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Realtime {
+    s_d: String,
+    sendible_temp: String,
+    temp: String,
+    w_d: String,
+    w_s: String,
+    weather: String,
+    ziwaixian: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Weather {
+    date: String,
+    sun_down_time: String,
+    sun_rise_time: String,
+    temp_day_c: String,
+    temp_night_c: String,
+    wd: String,
+    weather: String,
+    ws: String,
+}
+
+// 接口的 code 沿用 HTTP 状态码的含义；不认识的 code 原样带上接口给的 message
+fn api_error(code: &str, message: &str) -> String {
+    let key = match code {
+        "400" | "404" => "weather.meizu_invalid_city",
+        "429" => "weather.meizu_rate_limited",
+        "500" | "502" | "503" => "weather.meizu_unavailable",
+        _ => "weather.meizu_failed",
+    };
+    let message = if message.is_empty() { "-" } else { message };
+
+    tf(key, &[&code, &message])
+}
+
+fn into_report(value: Value) -> WeatherReport {
+    let air_quality = (!value.pm25.aqi.is_empty()).then_some(AirQuality {
+        aqi: value.pm25.aqi,
+        quality: value.pm25.quality,
+    });
+
+    WeatherReport {
+        provider: Provider::Meizu,
+        city: value.city,
+        city_id: Some(value.cityid.to_string()),
+        region: Some(value.province_name).filter(|name| !name.is_empty()),
+        current: CurrentWeather {
+            weather: value.realtime.weather,
+            temperature: number(&value.realtime.temp),
+            temperature_raw: raw_number(&value.realtime.temp),
+            feels_like: number(&value.realtime.sendible_temp),
+            // 湿度有时带 %，有时不带
+            humidity: number(&value.realtime.s_d),
+            wind: format!("{} {}", value.realtime.w_d, value.realtime.w_s),
+            uv_index: Some(value.realtime.ziwaixian).filter(|uv| !uv.is_empty()),
+        },
+        air_quality,
+        indexes: value
+            .indexes
+            .into_iter()
+            .map(|index| LifeIndex {
+                name: index.name,
+                level: index.level,
+                content: index.content,
+            })
+            .collect(),
+        days: value
+            .weathers
+            .into_iter()
+            .map(|weather| DailyForecast {
+                sunrise: parse_time(&weather.sun_rise_time).map(|time| time.format("%H:%M").to_string()),
+                sunset: parse_time(&weather.sun_down_time).map(|time| time.format("%H:%M").to_string()),
+                date: weather.date,
+                weather: weather.weather,
+                temp_day: number(&weather.temp_day_c),
+                temp_night: number(&weather.temp_night_c),
+                temp_day_raw: raw_number(&weather.temp_day_c),
+                temp_night_raw: raw_number(&weather.temp_night_c),
+                wind: format!("{} {}", weather.wd, weather.ws),
+            })
+            .collect(),
+        alerts: value
+            .alarms
+            .into_iter()
+            .map(|alarm| WeatherAlert {
+                published: clock::china_time(&alarm.publish_time),
+                id: alarm.alarm_id,
+                title: alarm.alarm_desc,
+                description: alarm.alarm_content,
+                level: alarm.alarm_level_no_desc,
+                kind: alarm.alarm_type_desc,
+                precaution: alarm.precaution,
+            })
+            .collect(),
+    }
+}
+
+// 取开头的数字部分，容忍 "28°C"、"28℃"、"60%" 之类带单位的写法；空字符串和 "N/A" 之类返回 None
+fn number(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && matches!(c, '-' | '+'))))
+        .map_or(value.len(), |(i, _)| i);
+
+    value[..end].parse().ok()
+}
+
+// number 解析不出来、但又不是空字符串时的原文
+fn raw_number(value: &str) -> Option<String> {
+    let value = value.trim();
+    (number(value).is_none() && !value.is_empty()).then(|| value.to_string())
+}
+
+// 日出日落时间可能是 "06:12"、"06:12:30" 或带日期的格式；极地或缺数据时是空字符串
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    let value = value.trim();
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|time| time.time()))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").map(|time| time.time()))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temperature_with_and_without_unit() {
+        assert_eq!(number("28"), Some(28.0));
+        assert_eq!(raw_number("28"), None);
+        assert_eq!(number("28°C"), Some(28.0));
+        assert_eq!(raw_number("28°C"), None);
+    }
+
+    #[test]
+    fn empty_temperature() {
+        assert_eq!(number(""), None);
+        assert_eq!(raw_number(""), None);
+    }
+
+    fn parse(body: &str) -> Result<Value, String> {
+        city_value(serde_json::from_str(body).expect("canned body"), "101200105")
+    }
+
+    #[test]
+    fn api_error_code_with_null_value() {
+        let body = r#"{"code": "400", "message": "城市不存在", "redirect": "", "value": null}"#;
+        assert_eq!(parse(body).unwrap_err(), api_error("400", "城市不存在"));
+        assert!(parse(body).unwrap_err().contains("400"));
+    }
+
+    #[test]
+    fn api_error_without_message() {
+        let body = r#"{"code": "503", "message": null, "value": null}"#;
+        assert_eq!(parse(body).unwrap_err(), api_error("503", ""));
+        assert!(parse(body).unwrap_err().contains("503: -"));
+    }
+
+    #[test]
+    fn success_without_value() {
+        let err = parse(r#"{"code": "200", "message": "", "value": []}"#).unwrap_err();
+        assert_eq!(err, tf("weather.no_data", &[&"101200105"]));
+    }
+
+    #[test]
+    fn unparsable_temperature_keeps_raw() {
+        assert_eq!(number("N/A"), None);
+        assert_eq!(raw_number(" N/A "), Some("N/A".to_string()));
+    }
+}
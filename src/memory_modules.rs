@@ -0,0 +1,168 @@
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "linux")]
+use crate::i18n::t;
+#[cfg(target_os = "linux")]
+use crate::privileges;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MemoryModule {
+    pub slot: Option<String>,
+    pub size_gb: Option<f64>,
+    pub speed_mts: Option<u32>,
+    pub kind: Option<String>,
+    pub manufacturer: Option<String>,
+    pub part_number: Option<String>,
+}
+
+// dmidecode 需要 root，没有权限时返回错误，由调用方记到 errors 里
+#[cfg(target_os = "linux")]
+pub fn get_memory_modules() -> Result<Vec<MemoryModule>, String> {
+    let output = match Command::new("dmidecode").args(["-t", "memory"]).output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            privileges::denied("memory_modules");
+            return Err(format!("dmidecode: {}", t("privileges.required")));
+        }
+        Err(e) => return Err(format!("dmidecode: {}", e)),
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if privileges::is_permission_message(&stderr) {
+            privileges::denied("memory_modules");
+            return Err(format!("dmidecode: {}", t("privileges.required")));
+        }
+        return Err(format!("dmidecode: {}", stderr.trim()));
+    }
+
+    Ok(parse_dmidecode(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// 每个 "Memory Device" 段是一个插槽，没插内存条的（Size: No Module Installed）跳过
+#[cfg(target_os = "linux")]
+fn parse_dmidecode(text: &str) -> Vec<MemoryModule> {
+    let mut modules = Vec::new();
+
+    for block in text.split("\n\n").filter(|block| block.contains("Memory Device")) {
+        let mut module = MemoryModule::default();
+        let mut installed = false;
+
+        for line in block.lines() {
+            let Some((key, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "Size" => {
+                    module.size_gb = parse_size_gb(value);
+                    installed = module.size_gb.is_some();
+                }
+                "Locator" => module.slot = known(value),
+                "Type" => module.kind = known(value),
+                // 优先用实际运行频率，没有时用标称频率
+                "Configured Memory Speed" | "Configured Clock Speed" => {
+                    if let Some(speed) = parse_speed(value) {
+                        module.speed_mts = Some(speed);
+                    }
+                }
+                "Speed" if module.speed_mts.is_none() => module.speed_mts = parse_speed(value),
+                "Manufacturer" => module.manufacturer = known(value),
+                "Part Number" => module.part_number = known(value),
+                _ => {}
+            }
+        }
+
+        if installed {
+            modules.push(module);
+        }
+    }
+
+    modules
+}
+
+#[cfg(target_os = "linux")]
+fn parse_size_gb(value: &str) -> Option<f64> {
+    let (number, unit) = value.split_once(' ')?;
+    let number: f64 = number.parse().ok()?;
+
+    match unit {
+        "TB" => Some(number * 1024.0),
+        "GB" => Some(number),
+        "MB" => Some(number / 1024.0),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn parse_speed(value: &str) -> Option<u32> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+// dmidecode 用这些占位值表示厂商没有填
+#[cfg(target_os = "linux")]
+fn known(value: &str) -> Option<String> {
+    match value {
+        "" | "Unknown" | "Not Specified" | "Undefined" | "None" => None,
+        _ => Some(value.to_string()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_memory_modules() -> Result<Vec<MemoryModule>, String> {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct PhysicalMemory {
+        device_locator: Option<String>,
+        capacity: Option<u64>,
+        configured_clock_speed: Option<u32>,
+        speed: Option<u32>,
+        manufacturer: Option<String>,
+        part_number: Option<String>,
+    }
+
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_PhysicalMemory | Select-Object DeviceLocator,Capacity,ConfiguredClockSpeed,Speed,Manufacturer,PartNumber | ConvertTo-Json",
+        ])
+        .output()
+        .map_err(|e| format!("powershell: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Win32_PhysicalMemory: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    // 只有一条内存时 ConvertTo-Json 输出的是对象而不是数组
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value: serde_json::Value = serde_json::from_str(&stdout).map_err(|e| e.to_string())?;
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Null => Vec::new(),
+        item => vec![item],
+    };
+
+    items
+        .into_iter()
+        .map(|item| {
+            let memory: PhysicalMemory = serde_json::from_value(item).map_err(|e| e.to_string())?;
+            Ok(MemoryModule {
+                slot: memory.device_locator,
+                size_gb: memory.capacity.map(crate::system::bytes_to_gb),
+                speed_mts: memory.configured_clock_speed.or(memory.speed),
+                kind: None,
+                manufacturer: memory.manufacturer.map(|name| name.trim().to_string()),
+                part_number: memory.part_number.map(|part| part.trim().to_string()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn get_memory_modules() -> Result<Vec<MemoryModule>, String> {
+    Err("memory module details are not supported on this platform".to_string())
+}
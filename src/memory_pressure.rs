@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use crate::system::MemoryUsage;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PressureLevel {
+    Low,
+    Medium,
+    High,
+}
+
+// 得出这个等级的主要原因，显示时翻译成一句说明
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PressureReason {
+    Plenty,
+    LowAvailable,
+    SwapHeavy,
+    Stalls,
+}
+
+// 内存紧张程度的估计：综合可用内存比例、swap 使用率，Linux 上再看 PSI（进程因等待内存而停顿的时间比例）
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct MemoryPressure {
+    pub level: PressureLevel,
+    pub reason: PressureReason,
+    pub available_percent: f64,
+    // /proc/pressure/memory 里最近 10 秒的 some/full 百分比；内核没开 PSI 时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub psi_some_avg10: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub psi_full_avg10: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Psi {
+    pub some_avg10: f64,
+    pub full_avg10: f64,
+}
+
+// 没有读取内存数据（总量为 0）时返回 None；从上往下第一条满足的规则决定等级
+pub fn assess(memory: &MemoryUsage, swap: &MemoryUsage, psi: Option<Psi>) -> Option<MemoryPressure> {
+    if memory.total_bytes == 0 {
+        return None;
+    }
+    let available_percent = memory.available_bytes as f64 * 100.0 / memory.total_bytes as f64;
+    let swap_percent = swap.used_percent.unwrap_or(0.0);
+    let some = psi.map_or(0.0, |psi| psi.some_avg10);
+    let full = psi.map_or(0.0, |psi| psi.full_avg10);
+
+    let (level, reason) = if full >= 5.0 {
+        (PressureLevel::High, PressureReason::Stalls)
+    } else if available_percent < 5.0 {
+        (PressureLevel::High, PressureReason::LowAvailable)
+    } else if swap_percent > 80.0 && available_percent < 20.0 {
+        (PressureLevel::High, PressureReason::SwapHeavy)
+    } else if some >= 10.0 {
+        (PressureLevel::Medium, PressureReason::Stalls)
+    } else if available_percent < 15.0 {
+        (PressureLevel::Medium, PressureReason::LowAvailable)
+    } else if swap_percent > 50.0 {
+        (PressureLevel::Medium, PressureReason::SwapHeavy)
+    } else {
+        (PressureLevel::Low, PressureReason::Plenty)
+    };
+
+    Some(MemoryPressure {
+        level,
+        reason,
+        available_percent,
+        psi_some_avg10: psi.map(|psi| psi.some_avg10),
+        psi_full_avg10: psi.map(|psi| psi.full_avg10),
+    })
+}
+
+// 每行形如 "some avg10=0.00 avg60=0.00 avg300=0.00 total=0"
+#[cfg(target_os = "linux")]
+pub fn read_psi() -> Option<Psi> {
+    let text = std::fs::read_to_string("/proc/pressure/memory").ok()?;
+    let avg10 = |kind: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(kind))?
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))?
+            .parse()
+            .ok()
+    };
+
+    Some(Psi {
+        some_avg10: avg10("some ")?,
+        full_avg10: avg10("full ")?,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_psi() -> Option<Psi> {
+    None
+}
@@ -0,0 +1,239 @@
+use std::fmt::Write;
+
+use crate::report::Report;
+
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+// 指标本身用到的标签名，--label 不能重复使用
+const BUILTIN_LABELS: &[&str] = &["index", "uuid", "pci_bus_id", "name", "reason", "device", "mount_point"];
+
+// Prometheus 的标签名规则：[a-zA-Z_][a-zA-Z0-9_]*，双下划线开头的保留给 Prometheus 自己
+pub fn check_label_name(name: &str) -> Result<(), String> {
+    let valid = name
+        .chars()
+        .enumerate()
+        .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    if name.is_empty() || !valid || name.starts_with("__") {
+        return Err(format!(
+            "invalid label name '{}' (expected letters, digits and _, not starting with a digit or __)",
+            name
+        ));
+    }
+    if BUILTIN_LABELS.contains(&name) {
+        return Err(format!("label name '{}' is already used by the metrics", name));
+    }
+
+    Ok(())
+}
+
+// 按 Prometheus 文本格式输出指标，容量统一换回字节；--label 的标签加在每个样本上
+pub fn render(report: &Report) -> String {
+    let mut out = Metrics {
+        labels: report.meta.as_ref().map(|meta| meta.labels.clone().into_iter().collect()).unwrap_or_default(),
+        ..Default::default()
+    };
+
+    out.family("gpu_temperature_celsius", "gauge", "GPU core temperature");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.gpu_temperature.map(f64::from));
+    }
+    out.family("gpu_temperature_slowdown_celsius", "gauge", "Temperature at which the driver starts slowing the GPU");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.temp_slowdown_threshold.map(f64::from));
+    }
+    out.family("gpu_temperature_shutdown_celsius", "gauge", "Temperature at which the driver shuts the GPU down");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.temp_shutdown_threshold.map(f64::from));
+    }
+    out.family("gpu_performance_state", "gauge", "Performance state, 0 (P0, fastest) to 15 (P15, slowest)");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.performance_state.map(f64::from));
+    }
+    out.family("gpu_memory_temperature_celsius", "gauge", "GPU memory junction temperature");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.memory_temperature.map(f64::from));
+    }
+    out.family("gpu_utilization_percent", "gauge", "GPU busy time over the last sample period");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.utilization.map(f64::from));
+    }
+    out.family("gpu_power_usage_watts", "gauge", "GPU power draw");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.power_usage);
+    }
+    out.family("gpu_power_limit_watts", "gauge", "GPU enforced power limit");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.power_limit.map(f64::from));
+    }
+    // 可调范围，调功耗上限时参考
+    out.family("gpu_power_limit_min_watts", "gauge", "Lowest power limit the GPU accepts");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.power_limit_min.map(f64::from));
+    }
+    out.family("gpu_power_limit_max_watts", "gauge", "Highest power limit the GPU accepts");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.power_limit_max.map(f64::from));
+    }
+    out.family("gpu_power_limit_default_watts", "gauge", "GPU factory default power limit");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.power_limit_default.map(f64::from));
+    }
+    out.family("gpu_core_clock_mhz", "gauge", "GPU graphics clock");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.core_clock.map(f64::from));
+    }
+    out.family("gpu_memory_clock_mhz", "gauge", "GPU memory clock");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.memory_clock.map(f64::from));
+    }
+    out.family("gpu_memory_used_bytes", "gauge", "GPU memory in use");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.memory_used.map(|used| used * GB));
+    }
+    out.family("gpu_memory_reserved_bytes", "gauge", "GPU memory reserved by the driver and firmware");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.memory_reserved.map(|reserved| reserved * GB));
+    }
+    out.family("gpu_memory_total_bytes", "gauge", "GPU memory size");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.memory_total.map(|total| total * GB));
+    }
+    out.family("gpu_ecc_errors_corrected", "gauge", "Corrected ECC errors since the driver loaded");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.ecc_corrected.map(|count| count as f64));
+    }
+    out.family("gpu_ecc_errors_uncorrected", "gauge", "Uncorrected ECC errors since the driver loaded");
+    for gpu in &report.gpus {
+        out.optional_sample(&gpu_labels(gpu), gpu.ecc_uncorrected.map(|count| count as f64));
+    }
+    out.family("gpu_throttle_active", "gauge", "1 for each throttle reason currently active");
+    for gpu in &report.gpus {
+        for reason in &gpu.throttle_reasons {
+            let mut labels = gpu_labels(gpu);
+            labels.push(("reason", reason.clone()));
+            out.sample(&labels, 1.0);
+        }
+    }
+    out.family("gpu_collection_error", "gauge", "1 when NVML could not be queried");
+    out.sample(&[], if report.error("gpu").is_some() { 1.0 } else { 0.0 });
+
+    out.family("health", "gauge", "Overall health: 0 ok, 1 warning, 2 critical");
+    out.optional_sample(&[], report.health.map(|health| health as u8 as f64));
+    let system = &report.system;
+    out.family("memory_total_bytes", "gauge", "Total system memory");
+    out.sample(&[], system.total_memory * GB);
+    out.family("memory_used_bytes", "gauge", "Used system memory");
+    out.sample(&[], system.used_memory * GB);
+    out.family("swap_total_bytes", "gauge", "Total swap");
+    out.sample(&[], system.total_swap * GB);
+    out.family("swap_used_bytes", "gauge", "Used swap");
+    out.sample(&[], system.used_swap * GB);
+    out.family("memory_pressure_level", "gauge", "Memory pressure estimate: 0 low, 1 medium, 2 high");
+    out.optional_sample(&[], system.memory_pressure.map(|pressure| pressure.level as u8 as f64));
+    out.family("cpu_usage_percent", "gauge", "Average CPU usage across all cores");
+    out.sample(&[], system.average_cpu_usage as f64);
+    out.family("cpu_power_watts", "gauge", "CPU package power measured through RAPL");
+    out.optional_sample(&[], system.cpu_power_watts);
+    out.family("processes", "gauge", "Number of running processes");
+    out.optional_sample(&[], system.process_count.map(|count| count as f64));
+    out.family("threads", "gauge", "Number of threads across all processes");
+    out.optional_sample(&[], system.thread_count.map(|count| count as f64));
+    out.family("uptime_seconds", "counter", "System uptime");
+    out.sample(&[], system.uptime as f64);
+
+    out.family("disk_total_bytes", "gauge", "Disk capacity");
+    for disk in &system.disks {
+        out.sample(&disk_labels(&disk.name, &disk.mount_point), disk.total_space * GB);
+    }
+    out.family("disk_available_bytes", "gauge", "Disk space available");
+    for disk in &system.disks {
+        out.sample(&disk_labels(&disk.name, &disk.mount_point), disk.available_space * GB);
+    }
+    out.family("disk_inodes_total", "gauge", "Inodes on the filesystem");
+    for disk in &system.disks {
+        if let Some(total) = disk.inodes_total {
+            out.sample(&disk_labels(&disk.name, &disk.mount_point), total as f64);
+        }
+    }
+    out.family("disk_inodes_free", "gauge", "Free inodes on the filesystem");
+    for disk in &system.disks {
+        if let Some(free) = disk.inodes_free {
+            out.sample(&disk_labels(&disk.name, &disk.mount_point), free as f64);
+        }
+    }
+
+    out.family("custom_metric", "gauge", "Numeric output of the custom_metrics commands in the config file");
+    for metric in &report.custom_metrics {
+        out.optional_sample(&[("name", metric.name.clone())], metric.value);
+    }
+    out.family("custom_metric_error", "gauge", "1 when a custom_metrics command failed or timed out");
+    for metric in &report.custom_metrics {
+        out.sample(&[("name", metric.name.clone())], if metric.error.is_some() { 1.0 } else { 0.0 });
+    }
+
+    out.text
+}
+
+// 只带 --label 标签的 "{dc=\"dc1\"}"，没有标签时为空；给 render 之外单独拼接的指标用
+pub fn static_labels(report: &Report) -> String {
+    let Some(meta) = report.meta.as_ref().filter(|meta| !meta.labels.is_empty()) else {
+        return String::new();
+    };
+    let labels: Vec<String> =
+        meta.labels.iter().map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value))).collect();
+
+    format!("{{{}}}", labels.join(","))
+}
+
+fn gpu_labels(gpu: &crate::gpu::GpuInfo) -> Vec<(&'static str, String)> {
+    vec![
+        ("index", gpu.index.to_string()),
+        ("uuid", gpu.uuid.clone()),
+        ("pci_bus_id", gpu.pci_bus_id.clone().unwrap_or_default()),
+        ("name", gpu.name.clone().unwrap_or_default()),
+    ]
+}
+
+fn disk_labels(name: &str, mount_point: &str) -> Vec<(&'static str, String)> {
+    vec![("device", name.to_string()), ("mount_point", mount_point.to_string())]
+}
+
+#[derive(Default)]
+struct Metrics {
+    text: String,
+    name: String,
+    labels: Vec<(String, String)>,
+}
+
+impl Metrics {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        self.name = format!("system_details_{}", name);
+        writeln!(self.text, "# HELP {} {}", self.name, help).unwrap();
+        writeln!(self.text, "# TYPE {} {}", self.name, kind).unwrap();
+    }
+
+    // 查询失败的值不输出样本，而不是输出 0
+    fn optional_sample(&mut self, labels: &[(&str, String)], value: Option<f64>) {
+        if let Some(value) = value {
+            self.sample(labels, value);
+        }
+    }
+
+    fn sample(&mut self, labels: &[(&str, String)], value: f64) {
+        self.text.push_str(&self.name);
+        if !labels.is_empty() || !self.labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| (*key, value))
+                .chain(self.labels.iter().map(|(key, value)| (key.as_str(), value)))
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            write!(self.text, "{{{}}}", labels.join(",")).unwrap();
+        }
+        writeln!(self.text, " {}", value).unwrap();
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr};
+
+use chrono::{DateTime, Utc};
+use sysinfo::DiskKind;
+
+use crate::gpu::{FanPolicy, Fragmentation, GpuDriver, GpuInfo, PowerState, NVIDIA};
+use crate::memory_pressure;
+use crate::report::{CollectOptions, Report, ReportMeta};
+use crate::system::{DiskInfo, DiskSummary, MemoryUsage, SystemInfo};
+use crate::weather::{CurrentWeather, DailyForecast, Provider, WeatherReport};
+
+// --mock 用的固定数据，不碰硬件也不联网，每次输出都一样：
+//
+// GPU 0: NVIDIA GeForce RTX 4090，UUID GPU-00000000-0000-0000-0000-000000000000，
+//        61 C、风扇 45%（自动）、利用率 97%、功耗 212.4 W / 450 W、显存 10.00 / 24.00 GB、
+//        核心 2520 MHz、显存 10501 MHz
+// 系统:  mock-host（mock-host.example.com，192.0.2.10），Linux 6.1.0-mock x86_64，运行 1 天 2:03:04，
+//        CPU 12.5%，内存 8 / 32 GiB，swap 0.5 / 8 GiB
+// 磁盘:  /dev/nvme0n1p2 SSD ext4 挂载在 /，共 476.94 GB、剩 238.47 GB；
+//        /dev/sda1 HDD xfs 挂载在 /data，共 1863.02 GB、剩 931.51 GB
+// 天气:  meizu 武汉（101200105），晴 25 C，体感 27 C，湿度 60%，东北风 3级，
+//        2026-01-01 到 2026-01-03 三天预报，日出 07:12，日落 17:32
+// 采集时间: 2026-01-01 08:00:00 +08:00
+pub fn report(options: &CollectOptions) -> Report {
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    let gpu = GpuInfo {
+        index: 0,
+        uuid: "GPU-00000000-0000-0000-0000-000000000000".to_string(),
+        pci_bus_id: Some("00000000:01:00.0".to_string()),
+        vendor: Some(NVIDIA.to_string()),
+        serial: Some("0000000000000".to_string()),
+        vbios_version: Some("95.02.18.80.87".to_string()),
+        name: Some("NVIDIA GeForce RTX 4090".to_string()),
+        power_state: Some(PowerState::Active),
+        num_cores: Some(16384),
+        memory_bus_width: Some(384),
+        core_clock: Some(2520),
+        base_core_clock: Some(2235),
+        memory_clock: Some(10501),
+        application_clocks_set: Some(false),
+        performance_state: Some(2),
+        auto_boost: Some(true),
+        gpu_temperature: Some(61),
+        temp_slowdown_threshold: Some(90),
+        temp_shutdown_threshold: Some(95),
+        fan_speed: Some(45),
+        fan_target: Some(45),
+        fan_policy: Some(FanPolicy::Auto),
+        fan_speeds: vec![45, 47],
+        utilization: Some(97),
+        power_usage: Some(212.4),
+        power_limit: Some(450),
+        power_limit_min: Some(150),
+        power_limit_max: Some(600),
+        power_limit_default: Some(450),
+        memory_used: Some(10.0),
+        memory_total: Some(24.0),
+        memory_used_bytes: Some(10 * GIB),
+        memory_total_bytes: Some(24 * GIB),
+        largest_free_block_bytes: None,
+        fragmentation: Fragmentation::Unavailable,
+        cpu_affinity: (0..8).collect(),
+        ..Default::default()
+    };
+
+    let disk = |name: &str, kind, file_system: &str, mount_point: &str, total_gb: f64| DiskInfo {
+        name: name.to_string(),
+        kind,
+        file_system: file_system.to_string(),
+        mount_point: mount_point.to_string(),
+        total_space: total_gb,
+        available_space: total_gb / 2.0,
+        total_bytes: Some((total_gb * GIB as f64) as u64),
+        available_bytes: Some((total_gb * GIB as f64 / 2.0) as u64),
+        inodes_total: Some(1 << 24),
+        inodes_free: Some(1 << 23),
+        encrypted: Some(false),
+        busy_percent: Some(0.0),
+        total_read_bytes: None,
+        total_written_bytes: None,
+        smart_health: None,
+        latency: None,
+        duplicate_of: None,
+    };
+
+    let disks = vec![
+        disk("/dev/nvme0n1p2", DiskKind::SSD, "ext4", "/", 476.94),
+        disk("/dev/sda1", DiskKind::HDD, "xfs", "/data", 1863.02),
+    ];
+    let memory = MemoryUsage::new(32 * GIB, 8 * GIB, 24 * GIB);
+    let swap = MemoryUsage::new(8 * GIB, GIB / 2, 8 * GIB - GIB / 2);
+    let system = SystemInfo {
+        total_memory: 32.0,
+        used_memory: 8.0,
+        available_memory: 24.0,
+        used_memory_no_cache: Some(8.0),
+        total_swap: 8.0,
+        used_swap: 0.5,
+        cgroup: None,
+        memory_pressure: memory_pressure::assess(&memory, &swap, None),
+        memory,
+        swap,
+        system_name: Some("Linux".to_string()),
+        kernel_version: Some("6.1.0-mock".to_string()),
+        os_version: Some("12".to_string()),
+        arch: "x86_64".to_string(),
+        pointer_width: 64,
+        endian: "little".to_string(),
+        host_name: Some("mock-host".to_string()),
+        fqdn: Some("mock-host.example.com".to_string()),
+        primary_ip: Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 10))),
+        virtualization: Some("none".to_string()),
+        secure_boot: None,
+        tpm_present: None,
+        uptime: 93784,
+        process_count: Some(412),
+        thread_count: Some(1873),
+        disk_summary: Some(DiskSummary::new(&disks)),
+        disks,
+        disks_omitted: 0,
+        duplicate_mounts: 0,
+        empty_mounts: 0,
+        average_cpu_usage: 12.5,
+        cpu_power_watts: Some(65.0),
+        cpu_times: None,
+        cpu_throttle: None,
+        cpu_caches: Vec::new(),
+        numa_nodes: Vec::new(),
+        networks: Vec::new(),
+    };
+
+    let weather = options.weather.then(weather);
+
+    let sections = [("gpu", options.gpu), ("system", true), ("weather", options.weather)]
+        .into_iter()
+        .filter(|(_, attempted)| *attempted)
+        .map(|(name, _)| (name.to_string(), true))
+        .collect::<BTreeMap<_, _>>();
+
+    let collected_at = DateTime::parse_from_rfc3339("2026-01-01T08:00:00+08:00").expect("valid mock timestamp");
+    Report {
+        collected_at: Some(collected_at),
+        collected_at_utc: Some(collected_at.with_timezone(&Utc)),
+        gpus: if options.gpu { vec![gpu] } else { Vec::new() },
+        gpu_driver: options.gpu.then(|| GpuDriver {
+            driver_version: "550.54.14".to_string(),
+            cuda_version: Some("12.4".to_string()),
+        }),
+        system,
+        weather,
+        memory_modules: None,
+        kernel_params: None,
+        net_top: None,
+        custom_metrics: Vec::new(),
+        requires_privileges: Vec::new(),
+        errors: Vec::new(),
+        health: None,
+        meta: Some(ReportMeta {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            duration_ms: 0,
+            sections,
+            timings_ms: BTreeMap::new(),
+            labels: options.labels.clone(),
+        }),
+    }
+}
+
+// 武汉，晴 25 C，三天预报；weather 子命令的 --mock 也用它
+pub fn weather() -> WeatherReport {
+    let day = |date: &str, weather: &str, temp_day, temp_night| DailyForecast {
+        date: date.to_string(),
+        weather: weather.to_string(),
+        temp_day: Some(temp_day),
+        temp_night: Some(temp_night),
+        temp_day_raw: None,
+        temp_night_raw: None,
+        wind: "东北风 3级".to_string(),
+        sunrise: Some("07:12".to_string()),
+        sunset: Some("17:32".to_string()),
+    };
+    WeatherReport {
+        provider: Provider::Meizu,
+        city: "武汉".to_string(),
+        city_id: Some("101200105".to_string()),
+        region: Some("湖北".to_string()),
+        current: CurrentWeather {
+            weather: "晴".to_string(),
+            temperature: Some(25.0),
+            temperature_raw: None,
+            feels_like: Some(27.0),
+            humidity: Some(60.0),
+            wind: "东北风 3级".to_string(),
+            uv_index: None,
+        },
+        air_quality: None,
+        indexes: Vec::new(),
+        days: vec![
+            day("2026-01-01", "晴", 25.0, 15.0),
+            day("2026-01-02", "多云", 23.0, 14.0),
+            day("2026-01-03", "小雨", 18.0, 12.0),
+        ],
+        alerts: Vec::new(),
+    }
+}
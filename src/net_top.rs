@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProcessNetwork {
+    pub pid: u32,
+    pub name: Option<String>,
+    pub connections: usize,
+    // 字节/秒，采样窗口内的平均值
+    pub received_per_sec: f64,
+    pub transmitted_per_sec: f64,
+}
+
+pub struct NetTop {
+    pub processes: Vec<ProcessNetwork>,
+    // 有进程的 /proc/<pid>/fd 因为权限读不了，它们的连接没能算进去
+    pub partial: bool,
+}
+
+// /proc/net/tcp 只有收发队列长度、没有累计字节数，字节数取自 `ss -tie` 输出的 tcp_info；
+// 再扫 /proc/<pid>/fd 把 socket inode 对应到进程。只统计 TCP，按收发速率之和取前 count 个
+#[cfg(target_os = "linux")]
+pub fn sample(count: usize, window: std::time::Duration) -> Result<NetTop, String> {
+    use std::collections::HashMap;
+    use std::thread;
+
+    let before = socket_bytes()?;
+    thread::sleep(window);
+    let after = socket_bytes()?;
+    let (owners, partial) = socket_owners();
+
+    let seconds = window.as_secs_f64();
+    let mut processes: HashMap<u32, ProcessNetwork> = HashMap::new();
+    for (inode, (received, transmitted)) in after {
+        let Some(&pid) = owners.get(&inode) else {
+            continue;
+        };
+        // 窗口内新建的连接从 0 算起
+        let (received_before, transmitted_before) = before.get(&inode).copied().unwrap_or((0, 0));
+        let process = processes.entry(pid).or_insert_with(|| ProcessNetwork {
+            pid,
+            name: std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok().map(|name| name.trim().to_string()),
+            connections: 0,
+            received_per_sec: 0.0,
+            transmitted_per_sec: 0.0,
+        });
+        process.connections += 1;
+        process.received_per_sec += received.saturating_sub(received_before) as f64 / seconds;
+        process.transmitted_per_sec += transmitted.saturating_sub(transmitted_before) as f64 / seconds;
+    }
+
+    let mut processes: Vec<ProcessNetwork> = processes.into_values().collect();
+    let total = |process: &ProcessNetwork| process.received_per_sec + process.transmitted_per_sec;
+    processes.sort_by(|a, b| total(b).total_cmp(&total(a)).then(a.pid.cmp(&b.pid)));
+    processes.truncate(count);
+
+    Ok(NetTop { processes, partial })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn sample(_count: usize, _window: std::time::Duration) -> Result<NetTop, String> {
+    Err("per-process network usage is only supported on Linux".to_string())
+}
+
+// socket inode => (累计接收字节, 累计发送字节)
+#[cfg(target_os = "linux")]
+fn socket_bytes() -> Result<std::collections::HashMap<u64, (u64, u64)>, String> {
+    use std::io::ErrorKind;
+    use std::process::Command;
+
+    let output = Command::new("ss").args(["-tieH"]).output().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => "ss (iproute2) not found".to_string(),
+        _ => format!("ss: {}", e),
+    })?;
+    if !output.status.success() {
+        return Err(format!("ss: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    // 每个连接占一行，tcp_info 在下一行，以空白开头
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut sockets: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match sockets.last_mut() {
+            Some(socket) if line.starts_with(char::is_whitespace) => socket.push_str(line),
+            _ => sockets.push(line.to_string()),
+        }
+    }
+
+    let mut bytes = std::collections::HashMap::new();
+    for socket in &sockets {
+        let field = |name: &str| -> Option<u64> {
+            socket.split_whitespace().find_map(|token| token.strip_prefix(name)?.parse().ok())
+        };
+        if let Some(inode) = field("ino:").filter(|&inode| inode != 0) {
+            let transmitted = field("bytes_sent:").or_else(|| field("bytes_acked:")).unwrap_or(0);
+            bytes.insert(inode, (field("bytes_received:").unwrap_or(0), transmitted));
+        }
+    }
+
+    Ok(bytes)
+}
+
+// socket inode => PID；返回值的第二项表示是否有进程因为权限没能扫描
+#[cfg(target_os = "linux")]
+fn socket_owners() -> (std::collections::HashMap<u64, u32>, bool) {
+    use std::fs;
+    use std::io::ErrorKind;
+
+    let mut owners = std::collections::HashMap::new();
+    let mut partial = false;
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return (owners, true);
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let fds = match fs::read_dir(entry.path().join("fd")) {
+            Ok(fds) => fds,
+            Err(e) => {
+                partial |= e.kind() == ErrorKind::PermissionDenied;
+                continue;
+            }
+        };
+        for fd in fds.flatten() {
+            // 链接目标形如 socket:[12345]
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok());
+            if let Some(inode) = inode {
+                owners.entry(inode).or_insert(pid);
+            }
+        }
+    }
+
+    (owners, partial)
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+// 一个 NUMA 节点上的内存和 CPU；只有内存没有 CPU 的节点（比如 CXL 内存扩展）cpus 为空
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NumaNode {
+    pub id: u32,
+    pub memory_total_bytes: u64,
+    pub memory_free_bytes: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpus: Vec<usize>,
+}
+
+// 只有一个节点时不算 NUMA，返回空
+#[cfg(target_os = "linux")]
+pub fn read() -> Vec<NumaNode> {
+    use std::fs;
+
+    let Ok(entries) = fs::read_dir("/sys/devices/system/node") else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<NumaNode> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.strip_prefix("node")?.parse().ok()?;
+            let dir = entry.path();
+            let meminfo = fs::read_to_string(dir.join("meminfo")).ok()?;
+            let cpus = fs::read_to_string(dir.join("cpulist")).map(|list| parse_cpu_list(&list)).unwrap_or_default();
+            Some(NumaNode {
+                id,
+                memory_total_bytes: meminfo_bytes(&meminfo, "MemTotal:")?,
+                memory_free_bytes: meminfo_bytes(&meminfo, "MemFree:")?,
+                cpus,
+            })
+        })
+        .collect();
+
+    if nodes.len() < 2 {
+        return Vec::new();
+    }
+    nodes.sort_by_key(|node| node.id);
+    nodes
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read() -> Vec<NumaNode> {
+    Vec::new()
+}
+
+// 每行形如 "Node 0 MemTotal:       65837224 kB"
+#[cfg(target_os = "linux")]
+fn meminfo_bytes(meminfo: &str, key: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let mut fields = line.split_whitespace().skip(2);
+        if fields.next()? != key {
+            return None;
+        }
+        fields.next()?.parse::<u64>().ok().map(|kb| kb * 1024)
+    })
+}
+
+// cpulist 形如 "0-15,32-47"，没有 CPU 的节点是空行
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => Some(start.parse().ok()?..=end.parse().ok()?),
+            None => range.parse().ok().map(|cpu| cpu..=cpu),
+        })
+        .flatten()
+        .collect()
+}
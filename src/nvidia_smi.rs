@@ -0,0 +1,82 @@
+use std::io::ErrorKind;
+use std::process::Command;
+
+use crate::gpu::{Fragmentation, GpuInfo, NVIDIA};
+use crate::i18n::t;
+use crate::system::bytes_to_gb;
+
+// 顺序和下面 parse_line 里取值的顺序一致
+const QUERY: &str = "index,uuid,name,serial,vbios_version,temperature.gpu,utilization.gpu,power.draw,power.limit,\
+                     power.min_limit,power.max_limit,power.default_limit,memory.used,memory.total,clocks.gr,clocks.mem,\
+                     clocks.default_applications.graphics,pstate,pci.bus_id";
+
+// NVML 库加载不了（比如容器里只挂进来了 nvidia-smi）时的后备方案；
+// 只能拿到 nvidia-smi 查询接口提供的字段，进程、降频原因等留空
+pub fn get_gpus_info(indices: &[u32]) -> Result<Vec<GpuInfo>, String> {
+    let mut command = Command::new("nvidia-smi");
+    command.arg(format!("--query-gpu={}", QUERY)).arg("--format=csv,noheader,nounits");
+    if !indices.is_empty() {
+        let ids: Vec<String> = indices.iter().map(u32::to_string).collect();
+        command.arg(format!("--id={}", ids.join(",")));
+    }
+
+    let output = command.output().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => t("smi.not_found").to_string(),
+        _ => format!("nvidia-smi: {}", e),
+    })?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        // nvidia-smi 把错误信息打印在 stdout 上
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+        return Err(format!("nvidia-smi: {}", message));
+    }
+
+    stdout.lines().filter(|line| !line.trim().is_empty()).map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> Result<GpuInfo, String> {
+    let columns: Vec<&str> = line.split(',').map(str::trim).collect();
+    let column = |i: usize| -> Option<&str> {
+        // 不支持的项输出为 [N/A] 或 [Not Supported]
+        columns.get(i).copied().filter(|value| !value.is_empty() && !value.starts_with('['))
+    };
+    let number = |i: usize| column(i).and_then(|value| value.parse::<f64>().ok());
+    let whole = |i: usize| number(i).map(|value| value.round() as u32);
+    // 显存单位是 MiB
+    let mib_bytes = |i: usize| number(i).map(|value| (value * 1024.0 * 1024.0) as u64);
+    let mib = |i: usize| mib_bytes(i).map(bytes_to_gb);
+
+    let index = column(0)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| format!("nvidia-smi: unexpected output: {}", line))?;
+    let uuid = column(1).ok_or_else(|| format!("nvidia-smi: unexpected output: {}", line))?;
+
+    Ok(GpuInfo {
+        index,
+        uuid: uuid.to_string(),
+        vendor: Some(NVIDIA.to_string()),
+        name: column(2).map(str::to_string),
+        serial: column(3).map(str::to_string),
+        vbios_version: column(4).map(str::to_string),
+        gpu_temperature: whole(5),
+        utilization: whole(6),
+        power_usage: number(7),
+        power_limit: whole(8),
+        power_limit_min: whole(9),
+        power_limit_max: whole(10),
+        power_limit_default: whole(11),
+        memory_used: mib(12),
+        memory_total: mib(13),
+        memory_used_bytes: mib_bytes(12),
+        memory_total_bytes: mib_bytes(13),
+        largest_free_block_bytes: None,
+        fragmentation: Fragmentation::Unavailable,
+        core_clock: whole(14),
+        memory_clock: whole(15),
+        base_core_clock: whole(16),
+        pci_bus_id: column(18).map(str::to_string),
+        performance_state: column(17).and_then(|state| state.strip_prefix('P')?.parse().ok()),
+        ..Default::default()
+    })
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Timelike};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use url::Url;
+
+use crate::i18n::{self, t, Lang};
+use crate::weather::{
+    block_on, endpoint, get_json, CurrentWeather, DailyForecast, Provider, WeatherProvider, WeatherQuery, WeatherReport,
+};
+
+// 免费账号能用的两个接口：当前天气（weather）和 5 天内每 3 小时一条的预报（forecast），
+// --weather-url 给的是它们共同的前缀
+const DEFAULT_URL: &str = "https://api.openweathermap.org/data/2.5";
+
+#[derive(Debug, Deserialize)]
+struct Current {
+    id: i64,
+    name: String,
+    weather: Vec<Condition>,
+    main: Main,
+    wind: Wind,
+    sys: Sys,
+    // 当地时间相对 UTC 的秒数
+    timezone: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Condition {
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Main {
+    temp: Option<f64>,
+    feels_like: Option<f64>,
+    humidity: Option<f64>,
+    temp_min: Option<f64>,
+    temp_max: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Wind {
+    speed: Option<f64>,
+    deg: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sys {
+    country: Option<String>,
+    sunrise: Option<i64>,
+    sunset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Forecast {
+    list: Vec<Slot>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Slot {
+    dt: i64,
+    main: Main,
+    weather: Vec<Condition>,
+    wind: Wind,
+}
+
+pub enum Location {
+    City(String),
+    Coordinates(f64, f64),
+}
+
+pub struct OpenWeatherMap {
+    api_key: String,
+    location: Location,
+    current_url: Url,
+    forecast_url: Url,
+}
+
+impl OpenWeatherMap {
+    pub fn new(api_key: &str, location: Location, base_url: &str) -> Result<Self, String> {
+        Ok(OpenWeatherMap {
+            api_key: api_key.to_string(),
+            location,
+            current_url: endpoint(base_url, "weather")?,
+            forecast_url: endpoint(base_url, "forecast")?,
+        })
+    }
+
+    // 坐标比城市名准，两个都给时用坐标
+    pub fn from_query(query: &WeatherQuery) -> Result<Self, String> {
+        let api_key = query.api_key.as_deref().ok_or_else(|| t("weather.owm_no_key").to_string())?;
+        let location = match (query.coordinates, &query.city) {
+            (Some((lat, lon)), _) => Location::Coordinates(lat, lon),
+            (None, Some(city)) => Location::City(city.clone()),
+            (None, None) => return Err(t("weather.owm_no_location").to_string()),
+        };
+
+        OpenWeatherMap::new(api_key, location, query.base_url.as_deref().unwrap_or(DEFAULT_URL))
+    }
+
+    fn request(&self, client: &Client, url: &Url) -> RequestBuilder {
+        let request = client.get(url.clone()).query(&[
+            ("appid", self.api_key.as_str()),
+            ("units", "metric"),
+            ("lang", if i18n::lang() == Lang::Zh { "zh_cn" } else { "en" }),
+        ]);
+        match &self.location {
+            Location::Coordinates(lat, lon) => request.query(&[("lat", lat), ("lon", lon)]),
+            Location::City(city) => request.query(&[("q", city)]),
+        }
+    }
+}
+
+impl WeatherProvider for OpenWeatherMap {
+    fn fetch(&self) -> Result<WeatherReport, String> {
+        let client = Client::new();
+        let (current, forecast) = block_on(async {
+            tokio::try_join!(
+                get_json::<Current>(self.request(&client, &self.current_url)),
+                get_json::<Forecast>(self.request(&client, &self.forecast_url)),
+            )
+        })??;
+
+        Ok(into_report(current, forecast))
+    }
+}
+
+fn into_report(current: Current, forecast: Forecast) -> WeatherReport {
+    let offset = current.timezone;
+    let local = |timestamp: i64| DateTime::from_timestamp(timestamp + offset, 0).map(|time| time.naive_utc());
+
+    // 3 小时一条的预报按当地日期归成一天：最高温当白天温度，最低温当夜间温度，天气和风取最接近中午的一条
+    let mut days: BTreeMap<NaiveDate, Vec<(NaiveDateTime, &Slot)>> = BTreeMap::new();
+    for slot in &forecast.list {
+        if let Some(time) = local(slot.dt) {
+            days.entry(time.date()).or_default().push((time, slot));
+        }
+    }
+
+    let sunrise = current.sys.sunrise.and_then(local);
+    let sunset = current.sys.sunset.and_then(local);
+    let days = days
+        .into_iter()
+        .map(|(date, slots)| {
+            let noon = slots.iter().min_by_key(|(time, _)| time.hour().abs_diff(12)).map(|(_, slot)| *slot);
+            let sun = |time: Option<NaiveDateTime>| {
+                time.filter(|time| time.date() == date).map(|time| time.format("%H:%M").to_string())
+            };
+            DailyForecast {
+                date: date.format("%Y-%m-%d").to_string(),
+                weather: noon.map(|slot| description(&slot.weather)).unwrap_or_default(),
+                temp_day: max(slots.iter().filter_map(|(_, slot)| slot.main.temp_max.or(slot.main.temp))),
+                temp_night: min(slots.iter().filter_map(|(_, slot)| slot.main.temp_min.or(slot.main.temp))),
+                temp_day_raw: None,
+                temp_night_raw: None,
+                wind: noon.map(|slot| wind(&slot.wind)).unwrap_or_default(),
+                sunrise: sun(sunrise),
+                sunset: sun(sunset),
+            }
+        })
+        .collect();
+
+    WeatherReport {
+        provider: Provider::OpenWeatherMap,
+        city: current.name,
+        city_id: Some(current.id.to_string()),
+        region: current.sys.country,
+        current: CurrentWeather {
+            weather: description(&current.weather),
+            temperature: current.main.temp,
+            temperature_raw: None,
+            feels_like: current.main.feels_like,
+            humidity: current.main.humidity,
+            wind: wind(&current.wind),
+            uv_index: None,
+        },
+        air_quality: None,
+        indexes: Vec::new(),
+        days,
+        // 预警只有付费的 One Call 接口才有
+        alerts: Vec::new(),
+    }
+}
+
+fn description(conditions: &[Condition]) -> String {
+    conditions.iter().map(|condition| condition.description.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+// 例如 NE 3.6 m/s
+fn wind(wind: &Wind) -> String {
+    const DIRECTIONS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+
+    let speed = wind.speed.map(|speed| format!("{:.1} m/s", speed)).unwrap_or_default();
+    match wind.deg {
+        Some(deg) => format!("{} {}", DIRECTIONS[((deg.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8], speed),
+        None => speed,
+    }
+}
+
+fn max(values: impl Iterator<Item = f64>) -> Option<f64> {
+    values.reduce(f64::max)
+}
+
+fn min(values: impl Iterator<Item = f64>) -> Option<f64> {
+    values.reduce(f64::min)
+}
@@ -0,0 +1,324 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde_json::Value;
+
+use crate::cli::Cli;
+use crate::clock;
+use crate::fields;
+use crate::i18n::{t, tf};
+use crate::influx;
+use crate::render::{self, TextStyle};
+use crate::report::Report;
+use crate::system::Units;
+use crate::weather::{self, Provider};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human-readable report with aligned columns
+    #[value(alias = "table")]
+    Text,
+    /// Same report, but disk and process listings are tab-separated for scripts
+    Tsv,
+    Json,
+    Csv,
+    /// InfluxDB line protocol: system, gpu and disk measurements tagged with the hostname
+    Influx,
+}
+
+pub struct Output {
+    format: Format,
+    fields: Vec<String>,
+    csv_header: Option<Vec<String>>,
+    weather_verbose: bool,
+    explain: bool,
+    raw_weather: bool,
+    raw_weather_url: Option<String>,
+    units: Units,
+    precision: usize,
+    // --watch-diff-highlight 时保留上一轮的扁平化报告，用来找出变化的值
+    previous: Option<HashMap<String, Value>>,
+    pretty: bool,
+    out: Box<dyn Write>,
+    // --output 里带 {hostname}/{timestamp}/{date} 时，每份报告写入时展开成新文件
+    template: Option<String>,
+    // --section-output 分出去的部分，每份报告各写一行 JSON
+    sections: Vec<(String, Box<dyn Write>)>,
+}
+
+// 可以单独输出的报告顶层部分
+pub const SECTIONS: &[&str] = &["gpus", "system", "weather"];
+
+pub fn check_section(name: &str) -> Result<(), String> {
+    if SECTIONS.contains(&name) {
+        Ok(())
+    } else {
+        Err(format!("unknown section '{}' (expected one of {})", name, SECTIONS.join(", ")))
+    }
+}
+
+// 管道里默认单行 JSON，保存到文件的多半要人看，默认缩进；--pretty/--compact 优先
+pub fn pretty_json(cli: &Cli) -> bool {
+    if cli.pretty {
+        true
+    } else if cli.compact {
+        false
+    } else {
+        cli.output.is_some()
+    }
+}
+
+impl Output {
+    pub fn new(cli: &Cli) -> Result<Self, String> {
+        // --anonymize 的目的是生成可分享的 JSON，写文件时文本报告也没法保存，没指定格式时都默认输出 JSON
+        let section_outputs = cli.section_outputs();
+        let text_to_json = (cli.anonymize || cli.output.is_some() || !section_outputs.is_empty())
+            && matches!(cli.format, Format::Text | Format::Tsv);
+        let format = if cli.json || cli.delta || text_to_json {
+            Format::Json
+        } else {
+            cli.format
+        };
+
+        let template = cli
+            .output
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .filter(|path| PLACEHOLDERS.iter().any(|placeholder| path.contains(placeholder)));
+        if !section_outputs.is_empty() && format != Format::Json {
+            return Err(t("output.sections_json_only").to_string());
+        }
+        let sections = section_outputs
+            .into_iter()
+            .map(|(section, destination)| {
+                let out: Box<dyn Write> = match destination.as_str() {
+                    "stdout" | "-" => Box::new(io::stdout()),
+                    "stderr" => Box::new(io::stderr()),
+                    path => Box::new(create(Path::new(path))?),
+                };
+                Ok((section, out))
+            })
+            .collect::<Result<_, String>>()?;
+        let out: Box<dyn Write> = match &cli.output {
+            Some(_) if template.is_some() => Box::new(io::sink()),
+            Some(path) => Box::new(create(path)?),
+            None => Box::new(io::stdout()),
+        };
+
+        let pretty = pretty_json(cli);
+
+        Ok(Output {
+            format,
+            fields: cli.fields.clone(),
+            csv_header: None,
+            weather_verbose: cli.weather_verbose,
+            explain: cli.explain,
+            // 重放保存的报告和 --mock 时不做任何网络请求；选了 OpenWeatherMap 时也不请求只覆盖国内的 oioweb；
+            // --anonymize/--redact 和用不到天气的 --fields 跟采集一样不拉天气
+            raw_weather: cli.collect_options().weather
+                && cli.from_json.is_none()
+                && !cli.mock
+                && cli.weather_provider != Provider::OpenWeatherMap,
+            raw_weather_url: cli.weather_url.clone().filter(|_| cli.weather_provider == Provider::Oioweb),
+            units: cli.units,
+            precision: cli.precision.into(),
+            previous: (cli.watch_diff_highlight && cli.color.enabled()).then(HashMap::new),
+            pretty,
+            out,
+            template,
+            sections,
+        })
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self.format, Format::Text | Format::Tsv)
+    }
+
+    // 报告序列化后的 JSON，指定了 --fields 时只保留对应字段
+    pub fn value(&self, report: &Report) -> Result<Value, String> {
+        let value = serde_json::to_value(report).map_err(|e| e.to_string())?;
+
+        if self.fields.is_empty() {
+            Ok(value)
+        } else {
+            fields::project(&value, &self.fields)
+        }
+    }
+
+    pub fn emit(&mut self, report: &Report) -> Result<(), String> {
+        if let Some(template) = &self.template {
+            let path = expand_template(template, report);
+            self.out = Box::new(create(&path)?);
+            // 每个文件都是完整的一份，CSV 也要带表头
+            self.csv_header = None;
+        }
+
+        if self.is_text() && self.fields.is_empty() {
+            let changed = self.changed(report)?;
+            let style = TextStyle {
+                explain: self.explain,
+                tsv: self.format == Format::Tsv,
+                changed: changed.as_ref(),
+                units: self.units,
+                precision: self.precision,
+            };
+            render::print_report(report, &style);
+            render::print_memory_modules(report, self.precision);
+            render::print_kernel_params(report);
+            render::print_net_top(report, self.units);
+            render::print_custom_metrics(report);
+            // 请求失败只影响这一行，报告的其它部分照常输出
+            if self.raw_weather {
+                if let Err(e) = weather::print_raw_weather(self.raw_weather_url.as_deref()) {
+                    println!("{}: {}", t("request_failed"), e);
+                }
+            }
+            render::print_weather(report, self.weather_verbose);
+            render::print_status(report);
+            return Ok(());
+        }
+
+        let mut value = self.value(report)?;
+        self.route_sections(&mut value)?;
+
+        match self.format {
+            Format::Text => {
+                for (path, value) in fields::flatten(&value) {
+                    self.write_line(&format!("{}: {}", path, scalar_to_string(&value)))?;
+                }
+            }
+            Format::Tsv => {
+                for (path, value) in fields::flatten(&value) {
+                    self.write_line(&format!("{}\t{}", path, scalar_to_string(&value)))?;
+                }
+            }
+            Format::Json => {
+                let json = if self.pretty {
+                    serde_json::to_string_pretty(&value)
+                } else {
+                    serde_json::to_string(&value)
+                };
+                self.write_line(&json.map_err(|e| e.to_string())?)?;
+            }
+            Format::Csv => self.print_csv(&value)?,
+            // 行协议的字段是固定的一组数值指标，不受 --fields 影响
+            Format::Influx => self.out.write_all(influx::render(report).as_bytes()).map_err(|e| e.to_string())?,
+        }
+
+        self.out.flush().map_err(|e| e.to_string())
+    }
+
+    // 和上一轮相比变化了的路径；第一轮没有可比较的，什么都不高亮
+    fn changed(&mut self, report: &Report) -> Result<Option<HashSet<String>>, String> {
+        let Some(previous) = &mut self.previous else {
+            return Ok(None);
+        };
+
+        let current: HashMap<String, Value> =
+            fields::flatten(&serde_json::to_value(report).map_err(|e| e.to_string())?).into_iter().collect();
+        let changed = current
+            .iter()
+            .filter(|(path, value)| !previous.is_empty() && previous.get(*path) != Some(value))
+            .map(|(path, _)| path.clone())
+            .collect();
+        *previous = current;
+
+        Ok(Some(changed))
+    }
+
+    // 分出去的部分从主报告里去掉，带上采集时间单独写一行，方便和主报告对上
+    fn route_sections(&mut self, value: &mut Value) -> Result<(), String> {
+        let Some(object) = value.as_object_mut() else {
+            return Ok(());
+        };
+        for (section, out) in &mut self.sections {
+            let Some(part) = object.remove(section.as_str()) else {
+                continue;
+            };
+            let mut line = serde_json::Map::new();
+            if let Some(collected_at) = object.get("collected_at") {
+                line.insert("collected_at".to_string(), collected_at.clone());
+            }
+            line.insert(section.clone(), part);
+            let json = if self.pretty {
+                serde_json::to_string_pretty(&line)
+            } else {
+                serde_json::to_string(&line)
+            };
+            writeln!(out, "{}", json.map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+            out.flush().map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    // 输出一行到 stdout 或 --output 指定的文件
+    pub fn write_line(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.out, "{}", line).map_err(|e| e.to_string())
+    }
+
+    // watch 模式下表头只打印一次，列发生变化（比如新挂载了磁盘）时重新打印
+    fn print_csv(&mut self, value: &Value) -> Result<(), String> {
+        let (header, row): (Vec<String>, Vec<String>) = fields::flatten(value)
+            .into_iter()
+            .map(|(path, value)| (path, scalar_to_string(&value)))
+            .unzip();
+
+        if self.csv_header.as_ref() != Some(&header) {
+            self.write_line(&csv_line(&header))?;
+            self.csv_header = Some(header);
+        }
+        self.write_line(&csv_line(&row))
+    }
+}
+
+const PLACEHOLDERS: &[&str] = &["{hostname}", "{timestamp}", "{date}"];
+
+// 主机名取自报告本身（--anonymize 时是哈希值，--redact 时是 "<redacted>"），路径分隔符和 Windows 不允许的尖括号替换掉，
+// 免得写到别的目录
+fn expand_template(template: &str, report: &Report) -> PathBuf {
+    let now = clock::now();
+    let hostname = report.system.host_name.as_deref().unwrap_or("unknown").replace(['/', '\\', '<', '>'], "_");
+
+    PathBuf::from(
+        template
+            .replace("{hostname}", &hostname)
+            .replace("{timestamp}", &now.format("%Y%m%dT%H%M%S").to_string())
+            .replace("{date}", &now.format("%Y-%m-%d").to_string()),
+    )
+}
+
+// 目录不存在时给出明确的错误，而不是只有 "No such file or directory"
+fn create(path: &Path) -> Result<File, String> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if !dir.is_dir() {
+            return Err(tf("output.no_dir", &[&dir.display()]));
+        }
+    }
+
+    File::create(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn scalar_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_line(cells: &[String]) -> String {
+    cells
+        .iter()
+        .map(|cell| {
+            if cell.contains([',', '"', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
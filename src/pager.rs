@@ -0,0 +1,58 @@
+use std::io::{self, Write};
+use std::process::Child;
+
+// --pager：之后写到 stdout 的内容都交给 $PAGER 显示，不是终端时不启用
+pub struct Pager {
+    child: Child,
+}
+
+#[cfg(unix)]
+pub fn start() -> Option<Pager> {
+    use std::env;
+    use std::io::IsTerminal;
+    use std::os::unix::io::AsRawFd;
+    use std::process::{Command, Stdio};
+
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+
+    let command = env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| "less -R".to_string());
+    let mut pager = Command::new("sh");
+    pager.arg("-c").arg(&command).stdin(Stdio::piped());
+    // 和 git 一样：一屏放得下时 less 直接退出，不用再按 q
+    if env::var_os("LESS").is_none() {
+        pager.env("LESS", "FRX");
+    }
+    let mut child = pager.spawn().ok()?;
+
+    let stdin = child.stdin.take()?;
+    // SAFETY: 把管道的写端复制到 fd 1，之后的 println! 都写进 pager
+    if unsafe { libc::dup2(stdin.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+        let _ = child.kill();
+        return None;
+    }
+
+    Some(Pager { child })
+}
+
+#[cfg(not(unix))]
+pub fn start() -> Option<Pager> {
+    None
+}
+
+impl Pager {
+    // 关闭 stdout 让 pager 读到 EOF，等用户退出 pager 再返回
+    pub fn finish(mut self) {
+        let _ = io::stdout().flush();
+        #[cfg(unix)]
+        // SAFETY: 关闭后 Rust 的 stdout 把 EBADF 当成成功，之后的输出直接丢弃
+        unsafe {
+            libc::close(libc::STDOUT_FILENO);
+        }
+        let _ = self.child.wait();
+    }
+}
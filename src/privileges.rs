@@ -0,0 +1,26 @@
+use std::cell::RefCell;
+
+// 采集过程中因为权限不够而读不到的字段；每次 collect_report 开始时清空，结束时收进 Report
+thread_local! {
+    static DENIED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn denied(field: impl Into<String>) {
+    let field = field.into();
+    DENIED.with(|denied| {
+        let mut denied = denied.borrow_mut();
+        if !denied.contains(&field) {
+            denied.push(field);
+        }
+    });
+}
+
+pub fn take() -> Vec<String> {
+    DENIED.with(|denied| denied.take())
+}
+
+// dmidecode 之类的外部命令没有权限时只会在 stderr 里说明
+pub fn is_permission_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("permission denied") || message.contains("operation not permitted")
+}
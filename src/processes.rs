@@ -0,0 +1,144 @@
+use std::cmp::Ordering;
+use std::thread;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, Process, ProcessRefreshKind, System, MINIMUM_CPU_UPDATE_INTERVAL};
+
+use crate::gpu::GpuInfo;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GpuProcess {
+    pub pid: u32,
+    pub used_memory: Option<f64>,
+    // 百分比，一个核跑满是 100；只有 --sort cpu 时才采样
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_usage: Option<f32>,
+    pub name: Option<String>,
+    pub cmd: Option<String>,
+    pub parent_pid: Option<u32>,
+    pub parent_name: Option<String>,
+    pub parent_cmd: Option<String>,
+}
+
+impl GpuProcess {
+    pub fn new(pid: u32, used_memory: Option<f64>) -> Self {
+        GpuProcess {
+            pid,
+            used_memory,
+            cpu_usage: None,
+            name: None,
+            cmd: None,
+            parent_pid: None,
+            parent_name: None,
+            parent_cmd: None,
+        }
+    }
+}
+
+// --sort：GPU 进程列表的排序方式，mem 和 cpu 从大到小，name 按字母顺序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProcessSort {
+    #[default]
+    Mem,
+    Cpu,
+    Name,
+}
+
+// 读不到的值排在最后；相同时按 PID 排，每次输出的顺序一样
+pub fn sort(processes: &mut [GpuProcess], key: ProcessSort) {
+    processes.sort_by(|a, b| {
+        let order = match key {
+            ProcessSort::Mem => b.used_memory.partial_cmp(&a.used_memory).unwrap_or(Ordering::Equal),
+            ProcessSort::Cpu => b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap_or(Ordering::Equal),
+            ProcessSort::Name => match (&a.name, &b.name) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        };
+        order.then(a.pid.cmp(&b.pid))
+    });
+}
+
+// NVML 只给出 PID，进程名、命令行和父进程要从 sysinfo 的进程表里查；
+// 孤儿 worker 的父进程往往就是启动训练任务的 launcher。
+// cpu 为 true 时隔 MINIMUM_CPU_UPDATE_INTERVAL 刷新两次，算出各进程的 CPU 使用率
+pub fn resolve_owners(gpus: &mut [GpuInfo], cpu: bool) {
+    if gpus.iter().all(|gpu| gpu.processes.is_empty()) {
+        return;
+    }
+
+    let mut sys = System::new();
+    let kind = if cpu { ProcessRefreshKind::new().with_cpu() } else { ProcessRefreshKind::new() };
+    sys.refresh_processes_specifics(kind);
+    if cpu {
+        thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_processes_specifics(kind);
+    }
+
+    for process in gpus.iter_mut().flat_map(|gpu| gpu.processes.iter_mut()) {
+        let Some(info) = sys.process(Pid::from_u32(process.pid)) else {
+            continue;
+        };
+        process.name = Some(info.name().to_string());
+        process.cmd = command_line(info);
+        if cpu {
+            process.cpu_usage = Some(info.cpu_usage());
+        }
+
+        let Some(parent) = info.parent().and_then(|pid| sys.process(pid)) else {
+            continue;
+        };
+        process.parent_pid = Some(parent.pid().as_u32());
+        process.parent_name = Some(parent.name().to_string());
+        process.parent_cmd = command_line(parent);
+    }
+}
+
+// 内核线程等没有命令行的进程返回 None
+fn command_line(process: &Process) -> Option<String> {
+    let cmd = process.cmd();
+    (!cmd.is_empty()).then(|| cmd.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, used_memory: Option<f64>, cpu_usage: Option<f32>, name: Option<&str>) -> GpuProcess {
+        GpuProcess {
+            cpu_usage,
+            name: name.map(str::to_string),
+            ..GpuProcess::new(pid, used_memory)
+        }
+    }
+
+    fn sorted(key: ProcessSort) -> Vec<u32> {
+        let mut processes = vec![
+            process(40, Some(1.0), Some(5.0), Some("python")),
+            process(30, None, None, None),
+            process(20, Some(0.5), Some(80.0), Some("blender")),
+            process(10, Some(1.0), Some(5.0), Some("python")),
+            process(50, Some(2.0), Some(120.0), Some("ffmpeg")),
+        ];
+        sort(&mut processes, key);
+        processes.iter().map(|process| process.pid).collect()
+    }
+
+    #[test]
+    fn sort_by_memory() {
+        assert_eq!(sorted(ProcessSort::Mem), [50, 10, 40, 20, 30]);
+    }
+
+    #[test]
+    fn sort_by_cpu() {
+        assert_eq!(sorted(ProcessSort::Cpu), [50, 20, 10, 40, 30]);
+    }
+
+    #[test]
+    fn sort_by_name() {
+        assert_eq!(sorted(ProcessSort::Name), [20, 50, 10, 40, 30]);
+    }
+}
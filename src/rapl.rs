@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+// 各 CPU 插槽 package 域的累计能耗（微焦）和计数器的回绕范围，来自 /sys/class/powercap/intel-rapl:N；
+// 名字里的 intel 只是历史原因，新内核上 AMD 也在这里
+pub struct EnergySnapshot {
+    packages: Vec<(u64, u64)>,
+}
+
+// 5.10 以后 energy_uj 默认只有 root 能读；没有 RAPL（虚拟机、ARM）时返回 None
+#[cfg(target_os = "linux")]
+pub fn snapshot() -> Option<EnergySnapshot> {
+    use std::fs;
+    use std::io::ErrorKind;
+
+    use crate::privileges;
+
+    let read = |path: &std::path::Path| fs::read_to_string(path).map(|text| text.trim().parse::<u64>().ok());
+    let mut packages = Vec::new();
+    for entry in fs::read_dir("/sys/class/powercap").ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // intel-rapl:0:0 之类是 package 下面的 core/uncore/dram 子域，已经包含在 package 里
+        if name.strip_prefix("intel-rapl:").is_none_or(|rest| rest.contains(':')) {
+            continue;
+        }
+        let dir = entry.path();
+        let energy = match read(&dir.join("energy_uj")) {
+            Ok(energy) => energy,
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                privileges::denied("system.cpu_power_watts");
+                return None;
+            }
+            Err(_) => None,
+        };
+        let range = read(&dir.join("max_energy_range_uj")).ok().flatten();
+        if let (Some(energy), Some(range)) = (energy, range) {
+            packages.push((energy, range));
+        }
+    }
+
+    (!packages.is_empty()).then_some(EnergySnapshot { packages })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn snapshot() -> Option<EnergySnapshot> {
+    None
+}
+
+// 所有插槽在这段时间里的平均功率；计数器到 max_energy_range_uj 后从 0 重新开始
+pub fn watts(before: &EnergySnapshot, after: &EnergySnapshot, window: Duration) -> Option<f64> {
+    if before.packages.len() != after.packages.len() || window.is_zero() {
+        return None;
+    }
+
+    let microjoules: u64 = before
+        .packages
+        .iter()
+        .zip(&after.packages)
+        .map(|(&(start, range), &(end, _))| if end >= start { end - start } else { range - start + end })
+        .sum();
+    Some(microjoules as f64 / 1_000_000.0 / window.as_secs_f64())
+}
@@ -0,0 +1,904 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::clock;
+use crate::gpu::{FanPolicy, Fragmentation, GpuSummary, PowerState};
+use crate::health::Health;
+use crate::memory_pressure::{PressureLevel, PressureReason};
+use crate::i18n::{self, t, tf};
+use crate::report::Report;
+use crate::system::{bytes_to_gb, convert_seconds, format_bytes, format_count, format_rate, Units};
+use crate::weather::{Provider, WeatherReport};
+
+pub struct TextStyle<'a> {
+    pub explain: bool,
+    // 磁盘和 GPU 进程列表用制表符分隔，方便脚本处理；否则按列对齐
+    pub tsv: bool,
+    // watch 模式下和上一轮相比变化了的字段路径（fields::flatten 的格式），这些值加粗高亮
+    pub changed: Option<&'a HashSet<String>>,
+    pub units: Units,
+    // --precision：GB、W 和使用率之类的小数位数
+    pub precision: usize,
+}
+
+impl TextStyle<'_> {
+    fn mark(&self, path: &str, text: String) -> String {
+        match self.changed {
+            Some(changed) if changed.contains(path) => format!("\x1B[1;33m{}\x1B[0m", text),
+            _ => text,
+        }
+    }
+}
+
+pub fn print_report(report: &Report, style: &TextStyle) {
+    let tsv = style.tsv;
+    let precision = style.precision;
+    let note = |key: &str| {
+        if let Some(text) = i18n::explain(key).filter(|_| style.explain) {
+            println!("    ↳ {}", text);
+        }
+    };
+
+    if let Some(collected_at) = report.collected_at {
+        println!("{}", tf("report.generated", &[&collected_at.format("%Y-%m-%d %H:%M:%S %:z")]));
+    }
+    if !report.requires_privileges.is_empty() {
+        println!("{}", t("privileges.note"));
+        for field in &report.requires_privileges {
+            println!("  {}: {}", field, t("privileges.required"));
+        }
+    }
+
+    if let Some(e) = report.error("gpu") {
+        println!("{}: {}", t("error"), e);
+    }
+
+    if let Some(driver) = &report.gpu_driver {
+        match &driver.cuda_version {
+            Some(cuda) => println!("{}: {} (CUDA {})", t("gpu.driver_version"), driver.driver_version, cuda),
+            None => println!("{}: {}", t("gpu.driver_version"), driver.driver_version),
+        }
+    }
+    for (i, gpu_info) in report.gpus.iter().enumerate() {
+        println!("{}", tf("gpu.section", &[&gpu_info.index]));
+        match gpu_info.power_state {
+            Some(state) => {
+                let state = t(if state == PowerState::Suspended { "gpu.suspended" } else { "gpu.active" });
+                println!("{}: {} ({})", t("gpu.name"), optional(&gpu_info.name), state);
+            }
+            None => println!("{}: {}", t("gpu.name"), optional(&gpu_info.name)),
+        }
+        if let Some(vendor) = &gpu_info.vendor {
+            println!("{}: {}", t("gpu.vendor"), vendor);
+        }
+        println!("{}: {}", t("gpu.uuid"), gpu_info.uuid);
+        if let Some(bus_id) = &gpu_info.pci_bus_id {
+            println!("{}: {}", t("gpu.pci_bus_id"), bus_id);
+        }
+        println!("{}: {}", t("gpu.serial"), optional(&gpu_info.serial));
+        println!("{}: {}", t("gpu.vbios_version"), optional(&gpu_info.vbios_version));
+        println!("{}: {}", t("gpu.cores"), optional(&gpu_info.num_cores));
+        println!("{}", tf("gpu.bus_width", &[&optional(&gpu_info.memory_bus_width)]));
+        let gpu_path = |field: &str| format!("gpus.{}.{}", i, field);
+        let mut core_clock = with_unit(gpu_info.core_clock, "MHz");
+        if let (Some(current), Some(base)) = (gpu_info.core_clock, gpu_info.base_core_clock) {
+            let key = match current.cmp(&base) {
+                Ordering::Greater => "gpu.clock_boosting",
+                Ordering::Less => "gpu.clock_below_base",
+                Ordering::Equal => "gpu.clock_at_base",
+            };
+            core_clock.push_str(&format!(" ({})", tf(key, &[&base])));
+        }
+        let core_clock = style.mark(&gpu_path("core_clock"), core_clock);
+        let core_clock = with_sparkline(core_clock, &gpu_info.sparklines.core_clock);
+        println!("{}: {}", t("gpu.core_clock"), core_clock);
+        note("gpu.core_clock");
+        let memory_clock = style.mark(&gpu_path("memory_clock"), with_unit(gpu_info.memory_clock, "MHz"));
+        let memory_clock = with_sparkline(memory_clock, &gpu_info.sparklines.memory_clock);
+        println!("{}: {}", t("gpu.memory_clock"), memory_clock);
+        note("gpu.memory_clock");
+        let clocks_set = gpu_info
+            .application_clocks_set
+            .map(|set| t(if set { "gpu.clocks_custom" } else { "gpu.clocks_default" }));
+        println!("{}: {}", t("gpu.application_clocks"), optional(&clocks_set));
+        if let Some(state) = gpu_info.performance_state {
+            println!("{}: P{}", t("gpu.performance_state"), state);
+        }
+        let auto_boost = gpu_info
+            .auto_boost
+            .map(|enabled| t(if enabled { "gpu.auto_boost_on" } else { "gpu.auto_boost_off" }));
+        println!("{}: {}", t("gpu.auto_boost"), optional(&auto_boost));
+        let mut temperature = style.mark(&gpu_path("gpu_temperature"), with_unit(gpu_info.gpu_temperature, "C"));
+        let thresholds: Vec<String> = [
+            ("gpu.temp_slowdown_at", gpu_info.temp_slowdown_threshold),
+            ("gpu.temp_shutdown_at", gpu_info.temp_shutdown_threshold),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| tf(key, &[&value])))
+        .collect();
+        if !thresholds.is_empty() {
+            temperature = format!("{} ({})", temperature, thresholds.join(", "));
+        }
+        let temperature = with_sparkline(temperature, &gpu_info.sparklines.temperature);
+        match gpu_info.temperature_trend {
+            Some(rate) => println!("{}: {} {}", t("gpu.temperature"), temperature, trend(rate)),
+            None => println!("{}: {}", t("gpu.temperature"), temperature),
+        }
+        note("gpu.temperature");
+        let temps: Vec<String> = [
+            (t("gpu.sensor_edge"), gpu_info.gpu_temperature),
+            (t("gpu.sensor_memory"), gpu_info.memory_temperature),
+        ]
+        .into_iter()
+        .filter_map(|(sensor, value)| value.map(|value| format!("{} {} C", sensor, value)))
+        .collect();
+        if temps.len() > 1 {
+            println!("{}: {}", t("gpu.temps"), temps.join(", "));
+        }
+        if gpu_info.fan_speed.is_some() || gpu_info.fan_policy.is_some() {
+            let policy = gpu_info.fan_policy.map(|policy| {
+                t(if policy == FanPolicy::Manual { "gpu.fan_manual" } else { "gpu.fan_auto" })
+            });
+            println!(
+                "{}: {} ({}, {})",
+                t("gpu.fan"),
+                with_unit(gpu_info.fan_speed, "%"),
+                optional(&policy),
+                tf("gpu.fan_target", &[&with_unit(gpu_info.fan_target, "%")])
+            );
+        }
+        if gpu_info.fan_speeds.len() > 1 {
+            let speeds: Vec<String> = gpu_info.fan_speeds.iter().map(|speed| format!("{}%", speed)).collect();
+            println!("{}: {}", t("gpu.fans"), speeds.join(", "));
+        }
+        let utilization = style.mark(&gpu_path("utilization"), with_unit(gpu_info.utilization, "%"));
+        let utilization = with_sparkline(utilization, &gpu_info.sparklines.utilization);
+        println!("{}: {}", t("gpu.utilization"), utilization);
+        note("gpu.utilization");
+        if let (Some(average), Some(peak)) = (gpu_info.gpu_util_avg, gpu_info.gpu_util_peak) {
+            println!("{}", tf("gpu.utilization_window", &[&format!("{:.*}", precision, average), &peak]));
+        }
+        if let Some(sessions) = gpu_info.active_encoder_sessions {
+            println!("NVENC: {}", tf("gpu.encoder", &[&sessions, &optional(&gpu_info.encoder_fps)]));
+        }
+        let power_usage = style.mark(&gpu_path("power_usage"), with_unit(gpu_info.power_usage.map(|power| format!("{:.*}", precision, power)), "W"));
+        println!("{}: {}", t("gpu.power_usage"), power_usage);
+        note("gpu.power_usage");
+        let mut power_limit = with_unit(gpu_info.power_limit, "W");
+        let mut limits = Vec::new();
+        if let (Some(min), Some(max)) = (gpu_info.power_limit_min, gpu_info.power_limit_max) {
+            limits.push(tf("gpu.power_limit_range", &[&min, &max]));
+        }
+        if let Some(default) = gpu_info.power_limit_default {
+            limits.push(tf("gpu.power_limit_default", &[&default]));
+        }
+        if !limits.is_empty() {
+            power_limit.push_str(&format!(" ({})", limits.join(", ")));
+        }
+        println!("{}: {}", t("gpu.power_limit"), power_limit);
+        note("gpu.power_limit");
+        if gpu_info.throttle_reasons.is_empty() {
+            println!("{}", t("gpu.not_throttling"));
+        } else {
+            println!("{}: {}", t("gpu.throttling"), gpu_info.throttle_reasons.join(", "));
+        }
+        let mut memory_used = gb(precision, gpu_info.memory_used);
+        if let Some(percent) = gpu_info.memory_usage_percent() {
+            memory_used.push_str(&format!(" ({:.*}%)", precision, percent));
+        }
+        println!("{}: {}", t("gpu.memory_used"), style.mark(&gpu_path("memory_used"), memory_used));
+        note("gpu.memory_used");
+        println!("{}: {}", t("gpu.memory_total"), gb(precision, gpu_info.memory_total));
+        match (gpu_info.fragmentation, gpu_info.largest_free_block_bytes) {
+            (Fragmentation::Available, Some(bytes)) => {
+                println!("{}: {}", t("gpu.largest_free_block"), gb(precision, Some(bytes_to_gb(bytes))))
+            }
+            _ => println!("{}", t("gpu.fragmentation_unavailable")),
+        }
+        if let (Some(used), Some(reserved), Some(total)) =
+            (gpu_info.memory_used, gpu_info.memory_reserved, gpu_info.memory_total)
+        {
+            println!(
+                "{}",
+                tf(
+                    "gpu.memory_breakdown",
+                    &[&format!("{:.*}", precision, used), &format!("{:.*}", precision, reserved), &format!("{:.*}", precision, total)]
+                )
+            );
+        }
+        if let (Some(corrected), Some(uncorrected)) = (gpu_info.ecc_corrected, gpu_info.ecc_uncorrected) {
+            println!("{}: {}", t("gpu.ecc_errors"), tf("gpu.ecc_counts", &[&corrected, &uncorrected]));
+        }
+        if !gpu_info.cpu_affinity.is_empty() {
+            println!("{}: {}", t("gpu.cpu_affinity"), cpu_ranges(&gpu_info.cpu_affinity));
+        }
+        if !gpu_info.processes.is_empty() {
+            println!("{}", t("gpu.processes"));
+        }
+        // --sort cpu 时才有 CPU 使用率
+        let with_cpu = gpu_info.processes.iter().any(|process| process.cpu_usage.is_some());
+        let mut rows = Vec::new();
+        for process in &gpu_info.processes {
+            let cpu = optional(&process.cpu_usage.map(|cpu| format!("{:.*}%", precision, cpu)));
+            let command = process.cmd.as_deref().or(process.name.as_deref()).unwrap_or("?");
+            let parent = process.parent_pid.map(|parent_pid| {
+                let parent = process.parent_cmd.as_deref().or(process.parent_name.as_deref()).unwrap_or("?");
+                tf("gpu.process_parent", &[&parent_pid, &parent])
+            });
+            if tsv {
+                let parent = parent.map(|parent| format!("\t{}", parent)).unwrap_or_default();
+                let cpu = if with_cpu { format!("\t{}", cpu) } else { String::new() };
+                println!("  {}\t{}{}\t{}{}", process.pid, gb(precision, process.used_memory), cpu, command, parent);
+            } else {
+                let command = match parent {
+                    Some(parent) => format!("{} {}", command, parent),
+                    None => command.to_string(),
+                };
+                let mut row = vec![process.pid.to_string(), gb(precision, process.used_memory)];
+                if with_cpu {
+                    row.push(cpu);
+                }
+                row.push(command);
+                rows.push(row);
+            }
+        }
+        if !rows.is_empty() {
+            let mut header = vec![t("gpu.process_pid"), t("gpu.process_memory")];
+            if with_cpu {
+                header.push(t("gpu.process_cpu"));
+            }
+            header.push(t("gpu.process_command"));
+            print_table(&header, &rows);
+        }
+    }
+    if report.gpus.len() > 1 {
+        let summary = GpuSummary::new(&report.gpus);
+        println!("{}", tf("gpu.summary_section", &[&summary.count]));
+        println!("{}: {:.*} GB", t("gpu.memory_total"), precision, summary.memory_total);
+        println!("{}: {:.*} GB", t("gpu.memory_used"), precision, summary.memory_used);
+        println!("{}: {:.*} W", t("gpu.power_usage"), precision, summary.power_usage);
+        println!("{}: {}", t("gpu.max_temperature"), with_unit(summary.max_temperature, "C"));
+    }
+
+    let system_info = &report.system;
+
+    println!("{}", t("system.section"));
+    println!("{}: {:.*} GB", t("system.total_memory"), precision, system_info.total_memory);
+    note("system.total_memory");
+    let used_memory = style.mark("system.used_memory", format!("{:.*} GB", precision, system_info.used_memory));
+    println!("{}: {}", t("system.used_memory"), used_memory);
+    note("system.used_memory");
+    if let Some(used) = system_info.used_memory_no_cache {
+        println!("{}: {:.*} GB", t("system.used_memory_no_cache"), precision, used);
+    }
+    println!("{}: {:.*} GB", t("system.available_memory"), precision, system_info.available_memory);
+    note("system.available_memory");
+    // cgroup 的内存上限比宿主机内存还大时等于没有限制，不输出
+    if let Some(limits) = &system_info.cgroup {
+        let memory_limit = limits.memory_limit_bytes.filter(|&limit| limit < system_info.memory.total_bytes);
+        if let Some(limit) = memory_limit {
+            println!("{}: {:.*} GB", t("system.cgroup_memory_limit"), precision, bytes_to_gb(limit));
+        }
+        if let Some(cores) = limits.cpu_limit {
+            println!("{}: {}", t("system.cgroup_cpu_limit"), tf("system.cgroup_cores", &[&format!("{:.*}", precision, cores)]));
+        }
+    }
+    println!("{}: {:.*} GB", t("system.total_swap"), precision, system_info.total_swap);
+    note("system.total_swap");
+    let used_swap = style.mark("system.used_swap", format!("{:.*} GB", precision, system_info.used_swap));
+    println!("{}: {}", t("system.used_swap"), used_swap);
+    note("system.used_swap");
+    if let Some(pressure) = &system_info.memory_pressure {
+        let level = match pressure.level {
+            PressureLevel::Low => t("system.pressure_low"),
+            PressureLevel::Medium => t("system.pressure_medium"),
+            PressureLevel::High => t("system.pressure_high"),
+        };
+        let reason = match pressure.reason {
+            PressureReason::Plenty => t("system.pressure_plenty"),
+            PressureReason::LowAvailable => t("system.pressure_low_available"),
+            PressureReason::SwapHeavy => t("system.pressure_swap_heavy"),
+            PressureReason::Stalls => t("system.pressure_stalls"),
+        };
+        let level = style.mark("system.memory_pressure", level.to_string());
+        println!("{}: {} ({})", t("system.memory_pressure"), level, reason);
+        note("system.memory_pressure");
+    }
+    for node in &system_info.numa_nodes {
+        let memory = tf(
+            "system.numa_memory",
+            &[
+                &format!("{:.*}", precision, bytes_to_gb(node.memory_free_bytes)),
+                &format!("{:.*}", precision, bytes_to_gb(node.memory_total_bytes)),
+            ],
+        );
+        match node.cpus.is_empty() {
+            true => println!("{} {}: {}", t("system.numa_node"), node.id, memory),
+            false => println!("{} {}: {} (CPU {})", t("system.numa_node"), node.id, memory, cpu_ranges(&node.cpus)),
+        }
+    }
+    // sysinfo 在部分系统上拿不到这几项
+    let unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| t("system.unknown").to_string());
+    println!("{}: {}", t("system.name"), unknown(&system_info.system_name));
+    println!("{}: {}", t("system.kernel_version"), unknown(&system_info.kernel_version));
+    println!("{}: {}", t("system.os_version"), unknown(&system_info.os_version));
+    if !system_info.arch.is_empty() {
+        let bits = tf("system.arch_bits", &[&system_info.arch, &system_info.pointer_width, &system_info.endian]);
+        println!("{}: {}", t("system.arch"), bits);
+    }
+    println!("{}: {}", t("system.host_name"), unknown(&system_info.host_name));
+    println!("{}: {}", t("system.fqdn"), optional(&system_info.fqdn));
+    println!("{}: {}", t("system.primary_ip"), optional(&system_info.primary_ip));
+    if let Some(virtualization) = &system_info.virtualization {
+        println!("{}: {}", t("system.virtualization"), virtualization);
+    }
+    // 只有 --security 时才检测，两项都判断不了时不输出
+    if system_info.secure_boot.is_some() || system_info.tpm_present.is_some() {
+        let secure_boot = system_info
+            .secure_boot
+            .map(|enabled| t(if enabled { "system.secure_boot_on" } else { "system.secure_boot_off" }));
+        let tpm = system_info.tpm_present.map(|present| t(if present { "system.tpm_yes" } else { "system.tpm_no" }));
+        println!("{}: {}", t("system.secure_boot"), optional(&secure_boot));
+        println!("{}: {}", t("system.tpm"), optional(&tpm));
+    }
+    let (days, hours, minutes, remaining_seconds) = convert_seconds(system_info.uptime);
+    println!(
+        "{}",
+        tf("system.uptime", &[&system_info.uptime, &days, &hours, &minutes, &remaining_seconds])
+    );
+    note("system.uptime");
+    match (system_info.process_count, system_info.thread_count) {
+        (Some(processes), Some(threads)) => println!("{}", tf("system.processes_threads", &[&processes, &threads])),
+        (Some(processes), None) => println!("{}", tf("system.processes", &[&processes])),
+        _ => {}
+    }
+    let cpu_usage = style.mark("system.average_cpu_usage", format!("{:.*}%", precision, system_info.average_cpu_usage));
+    println!("{}: {}", t("system.cpu_usage"), cpu_usage);
+    note("system.cpu_usage");
+    // 只加上能测到的部分：CPU package 和各 GPU 自己报告的功耗，不含主板、内存、风扇等
+    if let Some(cpu_power) = system_info.cpu_power_watts {
+        println!("{}: {:.*} W", t("system.cpu_power"), precision, cpu_power);
+        let gpu_power: f64 = report.gpus.iter().filter_map(|gpu| gpu.power_usage).sum();
+        println!("{}: {:.*} W", t("system.total_power"), precision, cpu_power + gpu_power);
+    }
+    if let Some(throttle) = &system_info.cpu_throttle {
+        println!(
+            "{}",
+            tf("system.cpu_throttle", &[&throttle.core_throttle_count, &throttle.package_throttle_count])
+        );
+    }
+    if !system_info.cpu_caches.is_empty() {
+        let caches: Vec<String> = system_info
+            .cpu_caches
+            .iter()
+            .map(|cache| format!("{}: {}", cache.label(), cache_size(cache.size_bytes)))
+            .collect();
+        println!("{}: {}", t("system.cpu_caches"), caches.join(", "));
+    }
+    if let Some(times) = &system_info.cpu_times {
+        let overall = &times.overall;
+        println!(
+            "{}",
+            tf(
+                "system.cpu_breakdown",
+                &[
+                    &format!("{:.*}", precision, overall.user + overall.nice),
+                    &format!("{:.*}", precision, overall.system + overall.irq + overall.softirq),
+                    &format!("{:.*}", precision, overall.idle),
+                    &format!("{:.*}", precision, overall.iowait),
+                ]
+            )
+        );
+    }
+
+    if !system_info.networks.is_empty() {
+        println!("{}", t("networks.section"));
+    }
+    for network in &system_info.networks {
+        println!(
+            "{}",
+            tf(
+                "networks.totals",
+                &[
+                    &network.name,
+                    &format!("{:.*}", precision, bytes_to_gb(network.total_received)),
+                    &format!("{:.*}", precision, bytes_to_gb(network.total_transmitted)),
+                ]
+            )
+        );
+        if let (Some(received), Some(transmitted)) = (network.received_per_sec, network.transmitted_per_sec) {
+            println!(
+                "{}",
+                tf(
+                    "networks.rates",
+                    &[&format_rate(received, style.units), &format_rate(transmitted, style.units)]
+                )
+            );
+        }
+    }
+
+    println!("{}", t("disks.section"));
+    note("disks.section");
+    if system_info.disks.is_empty() {
+        println!("{}", t("disks.none"));
+    }
+    if let Some(summary) = system_info.disk_summary.filter(|summary| summary.disks > 0) {
+        println!(
+            "{}",
+            tf(
+                "disks.summary",
+                &[
+                    &summary.disks,
+                    &format!("{:.*}", precision, summary.total_space),
+                    &format!("{:.*}", precision, summary.used_space),
+                    &format!("{:.*}", precision, summary.available_space),
+                    &optional(&summary.used_percent.map(|percent| format!("{:.*}%", precision, percent))),
+                ]
+            )
+        );
+    }
+    let mut rows = Vec::new();
+    for (i, disk) in system_info.disks.iter().enumerate() {
+        let inodes = |key: &str| match (disk.inodes_total, disk.inodes_free) {
+            (Some(total), Some(free)) => tf(key, &[&format_count(free), &format_count(total)]),
+            _ => String::new(),
+        };
+        let lock = if disk.encrypted == Some(true) { "🔒" } else { "" };
+        let mount_point = match &disk.duplicate_of {
+            Some(first) => tf("disks.duplicate_of", &[&disk.mount_point, first]),
+            None => disk.mount_point.clone(),
+        };
+        let latency = match &disk.latency {
+            Some(latency) => {
+                let ms = |value: Option<f64>| value.map(|ms| format!("{:.*}", precision, ms));
+                tf("disks.latency", &[&optional(&ms(latency.read_ms)), &optional(&ms(latency.write_ms))])
+            }
+            None => String::new(),
+        };
+
+        let busy =
+            disk.busy_percent.map(|busy| tf("disks.busy", &[&format!("{:.*}", precision, busy)])).unwrap_or_default();
+        let io_total = match (disk.total_read_bytes, disk.total_written_bytes) {
+            (Some(read), Some(written)) => {
+                format!("{} / {}", format_bytes(read as f64, style.units), format_bytes(written as f64, style.units))
+            }
+            _ => String::new(),
+        };
+
+        if tsv {
+            let io_total = if io_total.is_empty() { io_total } else { tf("disks.io_total", &[&io_total]) };
+            let smart = disk.smart_health.as_ref().map(|health| tf("disks.smart", &[health])).unwrap_or_default();
+            let extra: String = [busy.as_str(), io_total.as_str(), smart.as_str(), lock, latency.as_str()]
+                .iter()
+                .filter(|cell| !cell.is_empty())
+                .map(|cell| format!("\t{}", cell))
+                .collect();
+            println!(
+                "{:?}\t{:?}\t{:?}\t{:?}\t{:.*} GB\t{}\t{}{}",
+                disk.name,
+                disk.kind,
+                disk.file_system,
+                mount_point,
+                precision,
+                disk.total_space,
+                style.mark(&format!("system.disks.{}.available_space", i), format!("{:.*} GB", precision, disk.available_space)),
+                inodes("disks.inodes"),
+                extra
+            );
+            continue;
+        }
+
+        rows.push(vec![
+            disk.name.clone(),
+            format!("{:?}", disk.kind),
+            disk.file_system.clone(),
+            mount_point,
+            format!("{:.*} GB", precision, disk.total_space),
+            style.mark(&format!("system.disks.{}.available_space", i), format!("{:.*} GB", precision, disk.available_space)),
+            inodes("disks.inodes_free"),
+            disk.busy_percent.map(|busy| format!("{:.*}%", precision, busy)).unwrap_or_default(),
+            io_total,
+            disk.smart_health.clone().unwrap_or_default(),
+            lock.to_string(),
+            latency,
+        ]);
+    }
+    if !rows.is_empty() {
+        print_table(
+            &[
+                t("disks.name"),
+                t("disks.kind"),
+                t("disks.file_system"),
+                t("disks.mount_point"),
+                t("disks.total"),
+                t("disks.available"),
+                t("disks.inodes_column"),
+                t("disks.busy_column"),
+                t("disks.io_total_column"),
+                t("disks.smart_column"),
+                t("disks.encrypted"),
+                t("disks.latency_column"),
+            ],
+            &rows,
+        );
+    }
+    if system_info.disks_omitted > 0 {
+        println!("{}", tf("disks.omitted", &[&system_info.disks_omitted]));
+    }
+    if system_info.duplicate_mounts > 0 {
+        println!("{}", tf("disks.duplicate_mounts", &[&system_info.duplicate_mounts]));
+    }
+    if system_info.empty_mounts > 0 {
+        println!("{}", tf("disks.empty_mounts", &[&system_info.empty_mounts]));
+    }
+    // 小文件很多的盘 inode 先用完，df -h 看着还有空间却写不进文件
+    for disk in system_info.disks.iter().filter(|disk| disk.inodes_exhausted()) {
+        let inodes_free = disk.inodes_free_percent().unwrap_or_default();
+        let space_free = disk.free_percent().map(|percent| format!("{:.*}%", precision, percent));
+        let inodes_free = format!("{:.*}", precision, inodes_free);
+        println!("{}", tf("disks.inodes_exhausted", &[&disk.mount_point, &inodes_free, &optional(&space_free)]));
+    }
+}
+
+// 缓存大小都是 2 的幂，整 MB 时用 MB，否则用 KB
+fn cache_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 && bytes.is_multiple_of(1024 * 1024) {
+        format!("{} MB", bytes / (1024 * 1024))
+    } else {
+        format!("{} KB", bytes / 1024)
+    }
+}
+
+// 连续的编号合并成区间，例如 0-15,32-47
+fn cpu_ranges(cpus: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == cpu => *end = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|&(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn with_sparkline(text: String, sparkline: &str) -> String {
+    if sparkline.is_empty() {
+        text
+    } else {
+        format!("{} {}", text, sparkline)
+    }
+}
+
+// 例如 ↑ 2.0 C/s；变化不到 0.05 C/s 当作持平
+fn trend(rate: f64) -> String {
+    let arrow = if rate >= 0.05 {
+        "↑"
+    } else if rate <= -0.05 {
+        "↓"
+    } else {
+        "→"
+    };
+    format!("{} {:.1} C/s", arrow, rate.abs())
+}
+
+// 查询不到的值显示为 N/A
+pub fn optional<T: Display>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "N/A".to_string(),
+    }
+}
+
+fn with_unit<T: Display>(value: Option<T>, unit: &str) -> String {
+    match value {
+        Some(value) => format!("{} {}", value, unit),
+        None => "N/A".to_string(),
+    }
+}
+
+fn gb(precision: usize, value: Option<f64>) -> String {
+    with_unit(value.map(|value| format!("{:.*}", precision, value)), "GB")
+}
+
+pub fn print_custom_metrics(report: &Report) {
+    if report.custom_metrics.is_empty() {
+        return;
+    }
+
+    println!("{}", t("custom_metrics.section"));
+    for metric in &report.custom_metrics {
+        let value = match (&metric.value, &metric.json, &metric.text, &metric.error) {
+            (_, _, _, Some(e)) => format!("{}: {}", t("error"), e),
+            (Some(value), _, _, _) => value.to_string(),
+            (_, Some(json), _, _) => json.to_string(),
+            (_, _, Some(text), _) => text.clone(),
+            _ => String::new(),
+        };
+        println!("{}: {}", metric.name, value);
+    }
+}
+
+pub fn print_memory_modules(report: &Report, precision: usize) {
+    if let Some(e) = report.error("memory_modules") {
+        println!("{}: {}", t("error"), e);
+    }
+    let Some(modules) = &report.memory_modules else {
+        return;
+    };
+
+    println!("{}", t("memory_modules.section"));
+    for module in modules {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            optional(&module.slot),
+            gb(precision, module.size_gb),
+            with_unit(module.speed_mts, "MT/s"),
+            optional(&module.kind),
+            optional(&module.manufacturer),
+            optional(&module.part_number)
+        );
+    }
+}
+
+// 例如：STATUS: ok sections=3 errors=0 duration_ms=1034 health=ok；重放没有 meta 的旧报告时不输出
+pub fn print_status(report: &Report) {
+    let Some(meta) = &report.meta else {
+        return;
+    };
+
+    let status = if report.errors.is_empty() { "ok" } else { "partial" };
+    println!(
+        "STATUS: {} sections={} errors={} duration_ms={}{}",
+        status,
+        meta.sections.len(),
+        report.errors.len(),
+        meta.duration_ms,
+        report.health.map_or(String::new(), |health| format!(" health={}", health_name(health)))
+    );
+}
+
+// 和 JSON 里的取值一样，不翻译
+fn health_name(health: Health) -> &'static str {
+    match health {
+        Health::Ok => "ok",
+        Health::Warning => "warning",
+        Health::Critical => "critical",
+    }
+}
+
+pub fn print_net_top(report: &Report, units: Units) {
+    if let Some(e) = report.error("net_top") {
+        println!("{}: {}", t("net_top.unavailable"), e);
+    }
+    let Some(processes) = &report.net_top else {
+        return;
+    };
+
+    println!("{}", t("net_top.section"));
+    let rows: Vec<Vec<String>> = processes
+        .iter()
+        .map(|process| {
+            vec![
+                process.pid.to_string(),
+                process.name.clone().unwrap_or_default(),
+                process.connections.to_string(),
+                format_rate(process.received_per_sec, units),
+                format_rate(process.transmitted_per_sec, units),
+            ]
+        })
+        .collect();
+    print_table(
+        &[
+            t("gpu.process_pid"),
+            t("gpu.process_command"),
+            t("net_top.connections"),
+            t("net_top.received"),
+            t("net_top.transmitted"),
+        ],
+        &rows,
+    );
+}
+
+pub fn print_kernel_params(report: &Report) {
+    let Some(params) = &report.kernel_params else {
+        return;
+    };
+
+    println!("{}", t("kernel_params.section"));
+    println!("{}: {}", t("kernel_params.cmdline"), optional(&params.cmdline));
+    println!("vm.swappiness: {}", optional(&params.swappiness));
+    println!("vm.overcommit_memory: {}", optional(&params.overcommit_memory));
+    println!("{}: {}", t("kernel_params.transparent_hugepage"), optional(&params.transparent_hugepage));
+}
+
+pub fn print_weather(report: &Report, verbose: bool) {
+    if let Some(e) = report.error("weather") {
+        println!("{}: {}", t("request_failed"), e);
+    }
+
+    if let Some(weather) = &report.weather {
+        print_forecast(weather, verbose);
+    }
+}
+
+pub fn print_forecast(weather: &WeatherReport, verbose: bool) {
+    for alert in &weather.alerts {
+        println!("{}: {}", t("weather.alarm_content"), alert.description);
+        println!("{}: {}", t("weather.alarm_desc"), alert.title);
+        println!("{}: {}", t("weather.alarm_id"), alert.id);
+        println!("{}: {}", t("weather.alarm_level"), alert.level);
+        println!("{}: {}", t("weather.alarm_type"), alert.kind);
+        println!("{}: {}", t("weather.precaution"), alert.precaution);
+        println!("{}: {}", t("weather.publish_time"), optional(&alert.published.map(clock::display)));
+        println!("------------------------");
+    }
+
+    println!("{}: {}", t("weather.provider"), provider_name(weather.provider));
+    println!("{}: {}", t("weather.city"), weather.city);
+    if let Some(city_id) = &weather.city_id {
+        println!("{}: {}", t("weather.city_id"), city_id);
+    }
+
+    for index in &weather.indexes {
+        println!("{}: {}", t("weather.index_name"), index.name);
+        println!("{}: {}", t("weather.index_level"), index.level);
+        println!("{}: {}", t("weather.index_content"), index.content);
+        println!("------------------------");
+    }
+
+    if let Some(air_quality) = &weather.air_quality {
+        println!("{}: {}", t("weather.air_quality"), air_quality.quality);
+        println!("{}: {}", t("weather.aqi"), air_quality.aqi);
+    }
+
+    if let Some(region) = &weather.region {
+        println!("{}: {}", t("weather.region"), region);
+    }
+
+    let current = &weather.current;
+    println!("{}: {}", t("weather.realtime_weather"), current.weather);
+    println!("{}: {}", t("weather.realtime_temp"), temperature(current.temperature, &current.temperature_raw));
+    println!("{}: {}", t("weather.realtime_wind"), current.wind);
+    println!(
+        "{}",
+        tf(
+            "weather.realtime_humidity_feels",
+            &[&optional(&current.humidity.map(compact)), &optional(&current.feels_like.map(compact))]
+        )
+    );
+    if let Some(uv_index) = &current.uv_index {
+        println!("{}: {}", t("weather.realtime_uv"), uv_index);
+    }
+
+    // 预报的 date 是 "YYYY-MM-DD"，找不到今天时用第一天
+    let today = clock::now().format("%Y-%m-%d").to_string();
+    let sun = weather.days.iter().find(|day| day.date == today).or(weather.days.first());
+    if let Some((rise, down)) = sun.and_then(|day| day.sun_times()) {
+        println!("{}", tf("weather.sun", &[&rise.format("%H:%M"), &down.format("%H:%M")]));
+    }
+    if let Some(daylight) = sun.and_then(|day| day.daylight()) {
+        let minutes = daylight.num_minutes();
+        println!("{}", tf("weather.daylight", &[&(minutes / 60), &(minutes % 60)]));
+    }
+
+    if verbose {
+        for day in &weather.days {
+            println!("{}: {}", t("weather.date"), day.date);
+            println!("{}: {}", t("weather.weather"), day.weather);
+            println!("{}: {}", t("weather.day_temp"), temperature(day.temp_day, &day.temp_day_raw));
+            println!("{}: {}", t("weather.night_temp"), temperature(day.temp_night, &day.temp_night_raw));
+            println!("------------------------");
+        }
+        return;
+    }
+
+    // 多日预报默认用一张对齐的表，一天一行
+    let rows: Vec<Vec<String>> = weather
+        .days
+        .iter()
+        .map(|day| {
+            vec![
+                day.date.clone(),
+                day.weather.clone(),
+                format!(
+                    "{}°/{}°",
+                    temperature(day.temp_day, &day.temp_day_raw),
+                    temperature(day.temp_night, &day.temp_night_raw)
+                ),
+                day.wind.clone(),
+            ]
+        })
+        .collect();
+    print_table(
+        &[t("weather.date"), t("weather.weather"), t("weather.day_night_temp"), t("weather.wind")],
+        &rows,
+    );
+}
+
+fn provider_name(provider: Provider) -> &'static str {
+    match provider {
+        Provider::Meizu => "meizu",
+        Provider::Oioweb => "oioweb",
+        Provider::OpenWeatherMap => "OpenWeatherMap",
+    }
+}
+
+// 魅族给的温度、湿度是整数，OpenWeatherMap 带小数，统一最多保留一位
+// 解析不出数字的温度显示接口给的原文，都没有时是 N/A
+fn temperature(value: Option<f64>, raw: &Option<String>) -> String {
+    optional(&value.map(compact).or_else(|| raw.clone()))
+}
+
+fn compact(value: f64) -> String {
+    let rounded = (value * 10.0).round() / 10.0;
+    format!("{}", rounded)
+}
+
+// 按显示宽度对齐（中文字符占两列），列之间用 " | " 分隔
+// 高亮用的 ANSI 转义序列不占显示宽度
+fn display_width(cell: &str) -> usize {
+    let mut width = 0;
+    let mut rest = cell;
+    while let Some(start) = rest.find('\x1B') {
+        width += rest[..start].width();
+        rest = rest[start..].find('m').map_or("", |end| &rest[start + end + 1..]);
+    }
+
+    width + rest.width()
+}
+
+// 所有单元格都为空的列（比如没有加密盘时的加密列）不显示
+pub fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let shown: Vec<usize> = (0..header.len())
+        .filter(|&i| rows.iter().any(|row| row.get(i).is_some_and(|cell| !cell.is_empty())))
+        .collect();
+    if shown.len() < header.len() {
+        let header: Vec<&str> = shown.iter().map(|&i| header[i]).collect();
+        let rows: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| shown.iter().filter_map(|&i| row.get(i).cloned()).collect())
+            .collect();
+        return print_table(&header, &rows);
+    }
+
+    let mut widths: Vec<usize> = header.iter().map(|cell| display_width(cell)).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(display_width(cell));
+        }
+    }
+
+    let format_row = |cells: &mut dyn Iterator<Item = &str>| {
+        let padded: Vec<String> = cells
+            .zip(&widths)
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - display_width(cell))))
+            .collect();
+        padded.join(" | ").trim_end().to_string()
+    };
+
+    println!("{}", format_row(&mut header.iter().copied()));
+    println!("{}", widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("-+-"));
+    for row in rows {
+        println!("{}", format_row(&mut row.iter().map(String::as_str)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temperature_falls_back_to_raw() {
+        assert_eq!(temperature(Some(28.04), &None), "28");
+        assert_eq!(temperature(None, &Some("--".to_string())), "--");
+        assert_eq!(temperature(None, &None), "N/A");
+    }
+}
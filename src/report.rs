@@ -0,0 +1,436 @@
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::CustomMetric;
+use crate::clock;
+use crate::crash;
+use crate::custom_metrics::{self, CustomMetricValue};
+use crate::boot_security;
+use crate::disk_latency;
+use crate::drm;
+use crate::gpu::{self, get_gpus_info, sample_utilization, GpuBackend, GpuDriver, GpuInfo, GpuPoller};
+use crate::health::{self, Health};
+use crate::kernel_params::{self, KernelParams};
+use crate::net_top::{self, ProcessNetwork};
+use crate::memory_modules::{get_memory_modules, MemoryModule};
+use crate::mock;
+use crate::privileges;
+use crate::processes::{self, resolve_owners, ProcessSort};
+use crate::system::{get_system_info, Refresh, SystemInfo};
+use crate::weather::{self, Provider, WeatherQuery, WeatherReport};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Report {
+    // 开始采集的时间，带时区偏移（--timezone 或本地时区），另附一份 UTC 方便跨机器比较；旧版本保存的报告里没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collected_at: Option<DateTime<FixedOffset>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collected_at_utc: Option<DateTime<Utc>>,
+    pub gpus: Vec<GpuInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_driver: Option<GpuDriver>,
+    pub system: SystemInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weather: Option<WeatherReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_modules: Option<Vec<MemoryModule>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kernel_params: Option<KernelParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_top: Option<Vec<ProcessNetwork>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_metrics: Vec<CustomMetricValue>,
+    // 因为权限不够而读不到的字段路径，如 gpus.0.serial、memory_modules
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_privileges: Vec<String>,
+    pub errors: Vec<SectionError>,
+    // 各项检查汇总成的 ok / warning / critical，见 health::assess；旧版本保存的报告里没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Health>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ReportMeta>,
+}
+
+// 给自动化健康检查用：版本、采集耗时和每个采集过的部分是否成功
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReportMeta {
+    pub version: String,
+    pub duration_ms: u64,
+    pub sections: BTreeMap<String, bool>,
+    // 各采集阶段的耗时；GPU 利用率和 --net-top 的采样和 CPU 采样同时进行，算在 system 里
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub timings_ms: BTreeMap<String, u64>,
+    // --label 和配置文件里的 labels
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+// 某一部分采集失败时记在这里，报告的其它部分照常输出
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SectionError {
+    pub section: String,
+    pub error: String,
+}
+
+impl Report {
+    pub fn error(&self, section: &str) -> Option<&str> {
+        self.errors
+            .iter()
+            .find(|error| error.section == section)
+            .map(|error| error.error.as_str())
+    }
+}
+
+// 和 CPU 使用率的采样间隔一致
+const UTIL_SAMPLE_WINDOW: Duration = Duration::from_secs(1);
+
+// 采集哪些部分；默认只有 GPU 和系统信息
+#[derive(Debug, Clone, Default)]
+pub struct CollectOptions {
+    pub gpu: bool,
+    pub weather: bool,
+    pub weather_query: WeatherQuery,
+    pub gpu_indices: Vec<u32>,
+    pub memory_modules: bool,
+    pub kernel_params: bool,
+    pub security: bool,
+    pub refresh: Refresh,
+    pub gpu_util_samples: u32,
+    pub wake_gpus: bool,
+    pub gpu_backend: GpuBackend,
+    pub custom_metrics: Vec<CustomMetric>,
+    pub disk_latency: bool,
+    pub disk_latency_write: bool,
+    pub max_disks: Option<usize>,
+    pub all_mounts: bool,
+    pub sort_disks: bool,
+    pub process_sort: ProcessSort,
+    pub max_processes: Option<usize>,
+    pub net_top: Option<usize>,
+    pub mock: bool,
+    pub labels: BTreeMap<String, String>,
+}
+
+pub fn collect_report(options: &CollectOptions) -> Report {
+    collect_report_cached(options, None)
+}
+
+// --watch 每轮复用的 GPU 信息：NVIDIA 卡第一轮完整查询，之后只刷新 GpuDynamic 和进程列表，
+// 名称、UUID、功耗上限这些不会变的项不再每轮查；其它厂商的卡读 sysfs，开销很小，照常每轮读
+pub struct GpuCache {
+    poller: GpuPoller,
+}
+
+impl GpuCache {
+    pub fn new(options: &CollectOptions) -> Self {
+        GpuCache {
+            poller: GpuPoller::new(&options.gpu_indices, options.wake_gpus, options.gpu_backend).with_processes(),
+        }
+    }
+}
+
+pub fn collect_report_cached(options: &CollectOptions, cache: Option<&mut GpuCache>) -> Report {
+    let mut report = if options.mock { mock::report(options) } else { collect(options, cache) };
+    report.health = Some(health::assess(&report));
+    report
+}
+
+fn collect(options: &CollectOptions, cache: Option<&mut GpuCache>) -> Report {
+    let start = Instant::now();
+    let collected_at = clock::now();
+    let mut errors = Vec::new();
+    let mut timings = Timings::new();
+    privileges::take();
+    crash::reset();
+
+    let mut gpus = if options.gpu {
+        section("gpu", &mut errors, || collect_gpus(options, cache)).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    crash::record("gpus", &gpus);
+    let gpu_driver = (options.gpu_backend != GpuBackend::Smi && gpus.iter().any(GpuInfo::is_nvidia))
+        .then(gpu::driver_info)
+        .flatten();
+    timings.lap("gpu");
+    // GPU 进程的名称和父进程要扫描进程表，只在 --refresh 包含 processes 时做；按名称或 CPU 排序也要用到进程表
+    if options.refresh.processes || options.process_sort != ProcessSort::Mem {
+        resolve_owners(&mut gpus, options.process_sort == ProcessSort::Cpu);
+    }
+    for gpu in &mut gpus {
+        processes::sort(&mut gpu.processes, options.process_sort);
+        if let Some(max) = options.max_processes {
+            gpu.processes.truncate(max);
+        }
+    }
+
+    // 和 CPU 的 1 秒采样同时进行
+    let net_top = options
+        .net_top
+        .map(|count| thread::spawn(move || net_top::sample(count, UTIL_SAMPLE_WINDOW)));
+
+    // 利用率采样和 CPU 的 1 秒采样同时进行，不额外增加耗时
+    let mut system = if options.gpu_util_samples > 0 && !gpus.is_empty() {
+        let indices: Vec<u32> = gpus
+            .iter()
+            .filter(|gpu| gpu.is_nvidia() && (options.wake_gpus || !gpu.suspended()))
+            .map(|gpu| gpu.index)
+            .collect();
+        let (system, utilization) = thread::scope(|scope| {
+            let sampler =
+                scope.spawn(|| sample_utilization(&indices, options.gpu_util_samples, UTIL_SAMPLE_WINDOW));
+            let system = get_system_info(&options.refresh);
+            (system, sampler.join().unwrap_or_default())
+        });
+        for gpu in &mut gpus {
+            if let Some(&(average, peak)) = utilization.get(&gpu.index).filter(|_| gpu.is_nvidia()) {
+                gpu.gpu_util_avg = Some(average);
+                gpu.gpu_util_peak = Some(peak);
+            }
+        }
+        system
+    } else {
+        get_system_info(&options.refresh)
+    };
+
+    let net_top = net_top.and_then(|handle| {
+        section("net_top", &mut errors, || {
+            let top = handle.join().map_err(|_| "panicked".to_string())??;
+            if top.partial {
+                privileges::denied("net_top");
+            }
+            Ok(top.processes)
+        })
+    });
+
+    crash::record("system", &system);
+    crash::record("errors", &errors);
+    timings.lap("system");
+
+    if options.sort_disks {
+        system.sort_disks();
+    }
+    if options.security {
+        system.secure_boot = boot_security::secure_boot();
+        system.tpm_present = boot_security::tpm_present();
+    }
+
+    if !options.all_mounts {
+        system.hide_duplicate_mounts();
+        system.hide_empty_mounts();
+    }
+    // 在测延迟之前截断，几百个 overlay 挂载点不用逐个探测
+    if let Some(max) = options.max_disks {
+        system.limit_disks(max);
+    }
+
+    #[cfg(feature = "smart")]
+    crate::smart::fill(&mut system.disks);
+
+    if options.disk_latency {
+        disk_latency::probe(&mut system.disks, options.disk_latency_write);
+    }
+    timings.lap("disks");
+
+    // oioweb 只在文本输出里打印原始响应
+    let fetch_weather = options.weather && options.weather_query.provider != Provider::Oioweb;
+    let weather = if fetch_weather {
+        section("weather", &mut errors, || weather::fetch(&options.weather_query))
+    } else {
+        None
+    };
+
+    crash::record("weather", &weather);
+    if fetch_weather {
+        timings.lap("weather");
+    }
+
+    let memory_modules = if options.memory_modules {
+        section("memory_modules", &mut errors, get_memory_modules)
+    } else {
+        None
+    };
+    if options.memory_modules {
+        timings.lap("memory_modules");
+    }
+
+    let kernel_params = options.kernel_params.then(kernel_params::read).flatten();
+
+    let custom_metrics = custom_metrics::collect(&options.custom_metrics);
+    if !options.custom_metrics.is_empty() {
+        timings.lap("custom_metrics");
+    }
+
+    let attempted = [
+        ("gpu", options.gpu),
+        ("system", true),
+        ("weather", fetch_weather),
+        ("memory_modules", options.memory_modules),
+        ("net_top", options.net_top.is_some()),
+    ];
+    let sections = attempted
+        .into_iter()
+        .filter(|(_, attempted)| *attempted)
+        .map(|(name, _)| (name.to_string(), !errors.iter().any(|error: &SectionError| error.section == name)))
+        .collect();
+    let meta = ReportMeta {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        sections,
+        timings_ms: timings.laps,
+        labels: options.labels.clone(),
+    };
+
+    Report {
+        collected_at: Some(collected_at),
+        collected_at_utc: Some(collected_at.with_timezone(&Utc)),
+        gpus,
+        gpu_driver,
+        system,
+        weather,
+        memory_modules,
+        kernel_params,
+        net_top,
+        custom_metrics,
+        requires_privileges: privileges::take(),
+        errors,
+        health: None,
+        meta: Some(meta),
+    }
+}
+
+// 记录每个阶段从上一阶段结束到现在的耗时；跳过的阶段不记
+struct Timings {
+    last: Instant,
+    laps: BTreeMap<String, u64>,
+}
+
+impl Timings {
+    fn new() -> Self {
+        Timings {
+            last: Instant::now(),
+            laps: BTreeMap::new(),
+        }
+    }
+
+    fn lap(&mut self, phase: &str) {
+        let now = Instant::now();
+        self.laps.insert(phase.to_string(), now.duration_since(self.last).as_millis() as u64);
+        self.last = now;
+    }
+}
+
+// 没指定 --gpu 和 --gpu-backend 时，NVIDIA 之外的卡也一起报告，按厂商再按序号排列；
+// 机器上只有别家的卡时，NVML 加载不了不算错误
+fn collect_gpus(options: &CollectOptions, cache: Option<&mut GpuCache>) -> Result<Vec<GpuInfo>, String> {
+    let nvidia = match cache {
+        Some(cache) => cache.poller.poll().map(<[GpuInfo]>::to_vec),
+        None => get_gpus_info(&options.gpu_indices, options.wake_gpus, options.gpu_backend),
+    };
+    if !options.gpu_indices.is_empty() || options.gpu_backend != GpuBackend::Auto {
+        return nvidia.map_err(|e| e.to_string());
+    }
+
+    let first_index = nvidia.as_ref().map_or(0, |gpus| gpus.iter().map(|gpu| gpu.index + 1).max().unwrap_or(0));
+    let others = drm::get_gpus_info(first_index);
+    let mut gpus = match nvidia {
+        Ok(gpus) => gpus,
+        Err(e) if e.no_driver() && !others.is_empty() => Vec::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    gpus.extend(others);
+    gpus.sort_by(|a, b| a.vendor.cmp(&b.vendor).then(a.index.cmp(&b.index)));
+
+    Ok(gpus)
+}
+
+// 错误和 panic 都转成 SectionError，保证 JSON/CSV 输出始终是完整的
+fn section<T>(
+    name: &'static str,
+    errors: &mut Vec<SectionError>,
+    collect: impl FnOnce() -> Result<T, String>,
+) -> Option<T> {
+    let result = crash::catching(|| panic::catch_unwind(AssertUnwindSafe(collect))).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_string());
+        Err(message)
+    });
+
+    match result {
+        Ok(value) => Some(value),
+        Err(error) => {
+            errors.push(SectionError {
+                section: name.to_string(),
+                error,
+            });
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::weather::{AirQuality, LifeIndex, WeatherAlert};
+
+    fn keys(value: &Value) -> Vec<&str> {
+        let mut keys: Vec<&str> = value.as_object().expect("object").keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    // 天气接口的 camelCase 字段只用于解析，报告里统一是 snake_case
+    #[test]
+    fn report_json_keys() {
+        let options = CollectOptions { gpu: true, weather: true, ..Default::default() };
+        let mut report = mock::report(&options);
+        let weather = report.weather.as_mut().expect("mock weather");
+        weather.air_quality = Some(AirQuality { aqi: "42".to_string(), quality: "优".to_string() });
+        weather.indexes.push(LifeIndex {
+            name: "穿衣指数".to_string(),
+            level: "舒适".to_string(),
+            content: "建议穿薄外套".to_string(),
+        });
+        weather.alerts.push(WeatherAlert {
+            id: "1".to_string(),
+            title: "大风蓝色预警".to_string(),
+            description: String::new(),
+            level: "蓝色".to_string(),
+            kind: "大风".to_string(),
+            precaution: String::new(),
+            published: None,
+        });
+
+        let json = serde_json::to_value(&report).expect("serialize report");
+        assert_eq!(
+            keys(&json),
+            ["collected_at", "collected_at_utc", "errors", "gpu_driver", "gpus", "meta", "system", "weather"]
+        );
+        let weather = &json["weather"];
+        assert_eq!(
+            keys(weather),
+            ["air_quality", "alerts", "city", "city_id", "current", "days", "indexes", "provider", "region"]
+        );
+        assert_eq!(keys(&weather["current"]), ["feels_like", "humidity", "temperature", "uv_index", "weather", "wind"]);
+        assert_eq!(keys(&weather["air_quality"]), ["aqi", "quality"]);
+        assert_eq!(keys(&weather["indexes"][0]), ["content", "level", "name"]);
+        assert_eq!(
+            keys(&weather["days"][0]),
+            ["date", "sunrise", "sunset", "temp_day", "temp_night", "weather", "wind"]
+        );
+        assert_eq!(
+            keys(&weather["alerts"][0]),
+            ["description", "id", "kind", "level", "precaution", "published", "title"]
+        );
+    }
+}
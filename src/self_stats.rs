@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+// 本进程自己消耗的 CPU 时间和内存峰值，--self-stats 在退出前打印
+pub struct SelfStats {
+    pub user_cpu: Duration,
+    pub system_cpu: Duration,
+    pub peak_rss_bytes: u64,
+}
+
+#[cfg(unix)]
+pub fn read() -> Option<SelfStats> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: usage 是有效的出参
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return None;
+    }
+
+    let time = |tv: libc::timeval| Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1000);
+    // ru_maxrss 在 Linux 上是 KB，macOS 上是字节
+    let peak_rss_bytes = if cfg!(target_os = "macos") {
+        usage.ru_maxrss as u64
+    } else {
+        usage.ru_maxrss as u64 * 1024
+    };
+
+    Some(SelfStats {
+        user_cpu: time(usage.ru_utime),
+        system_cpu: time(usage.ru_stime),
+        peak_rss_bytes,
+    })
+}
+
+#[cfg(windows)]
+pub fn read() -> Option<SelfStats> {
+    use std::mem;
+
+    use windows_sys::Win32::Foundation::FILETIME;
+    use windows_sys::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, GetProcessTimes};
+
+    let mut times: [FILETIME; 4] = unsafe { mem::zeroed() };
+    let mut counters: PROCESS_MEMORY_COUNTERS = unsafe { mem::zeroed() };
+    // SAFETY: GetCurrentProcess 返回的伪句柄不需要关闭，出参都是有效的缓冲区
+    unsafe {
+        let process = GetCurrentProcess();
+        let [creation, exit, kernel, user] = &mut times;
+        if GetProcessTimes(process, creation, exit, kernel, user) == 0 {
+            return None;
+        }
+        counters.cb = mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        if GetProcessMemoryInfo(process, &mut counters, counters.cb) == 0 {
+            return None;
+        }
+    }
+
+    // FILETIME 的单位是 100 纳秒
+    let time = |time: FILETIME| {
+        Duration::from_nanos((((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64) * 100)
+    };
+
+    Some(SelfStats {
+        user_cpu: time(times[3]),
+        system_cpu: time(times[2]),
+        peak_rss_bytes: counters.PeakWorkingSetSize as u64,
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn read() -> Option<SelfStats> {
+    None
+}
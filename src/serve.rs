@@ -0,0 +1,142 @@
+use std::net::SocketAddr;
+use std::process;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::State;
+use axum::http::{header, HeaderName, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use tokio::sync::Mutex;
+
+use crate::anonymize::{self, Scrub};
+use crate::cli::Cli;
+use crate::i18n::t;
+use crate::metrics;
+use crate::report::{self, CollectOptions, Report};
+
+const SNAPSHOT_AGE: HeaderName = HeaderName::from_static("x-snapshot-age");
+
+type Snapshot = Option<(Instant, Arc<Report>)>;
+
+struct ServeState {
+    scrub: Option<Scrub>,
+    options: CollectOptions,
+    gpu_required: bool,
+    // --pretty 时 /report.json 也缩进，默认和 stdout 一样单行
+    pretty: bool,
+    cache_ttl: Duration,
+    // 最近一次采集的结果；锁在采集期间一直持有，同时到达的请求会等这一次采集完成后共用结果
+    cache: Arc<Mutex<Snapshot>>,
+}
+
+pub fn run(port: u16, cli: &Cli) {
+    let state = Arc::new(ServeState {
+        scrub: cli.scrub(),
+        // 导出器不拉天气
+        options: CollectOptions {
+            weather: false,
+            ..cli.collect_options()
+        },
+        gpu_required: cli.gpu_required,
+        pretty: cli.pretty,
+        cache_ttl: cli.cache_ttl,
+        cache: Arc::new(Mutex::new(None)),
+    });
+    let app = Router::new()
+        .route("/report.json", get(report_json))
+        .route("/metrics", get(metrics))
+        .route("/healthz", get(healthz))
+        .with_state(state);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let served = runtime.block_on(async {
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        eprintln!("listening on http://{}", addr);
+        axum::serve(listener, app).await
+    });
+
+    if let Err(e) = served {
+        eprintln!("{}", e);
+        process::exit(2);
+    }
+}
+
+// 返回报告以及它已经采集了多久
+async fn snapshot(state: &ServeState) -> Result<(Arc<Report>, Duration), String> {
+    let mut cache = state.cache.lock().await;
+
+    if let Some((collected_at, report)) = cache.as_ref() {
+        let age = collected_at.elapsed();
+        if age < state.cache_ttl {
+            return Ok((report.clone(), age));
+        }
+    }
+
+    let report = Arc::new(collect(state.scrub.clone(), state.options.clone()).await?);
+    *cache = Some((Instant::now(), report.clone()));
+    Ok((report, Duration::ZERO))
+}
+
+// 采集是阻塞的（CPU 采样要睡 1 秒），放到 blocking 线程池里跑
+async fn collect(scrub: Option<Scrub>, options: CollectOptions) -> Result<Report, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut report = report::collect_report(&options);
+        if let Some(scrub) = &scrub {
+            anonymize::anonymize(&mut report, scrub);
+        }
+        report
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn age_header(age: Duration) -> [(HeaderName, String); 1] {
+    [(SNAPSHOT_AGE, format!("{:.3}", age.as_secs_f64()))]
+}
+
+async fn report_json(State(state): State<Arc<ServeState>>) -> Response {
+    match snapshot(&state).await {
+        Ok((report, age)) if state.pretty => match serde_json::to_string_pretty(report.as_ref()) {
+            Ok(body) => ([(header::CONTENT_TYPE, "application/json")], age_header(age), body).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        },
+        Ok((report, age)) => (age_header(age), Json(report.as_ref())).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+async fn metrics(State(state): State<Arc<ServeState>>) -> Response {
+    match snapshot(&state).await {
+        Ok((report, age)) => {
+            let mut body = metrics::render(&report);
+            body.push_str("# HELP system_details_snapshot_age_seconds Age of the served snapshot\n");
+            body.push_str("# TYPE system_details_snapshot_age_seconds gauge\n");
+            body.push_str(&format!(
+                "system_details_snapshot_age_seconds{} {:.3}\n",
+                metrics::static_labels(&report),
+                age.as_secs_f64()
+            ));
+
+            (
+                [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+                age_header(age),
+                body,
+            )
+                .into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+async fn healthz(State(state): State<Arc<ServeState>>) -> Response {
+    match snapshot(&state).await {
+        Ok((report, age)) if state.gpu_required && report.gpus.is_empty() => {
+            (StatusCode::SERVICE_UNAVAILABLE, age_header(age), t("gpu.required_missing")).into_response()
+        }
+        Ok((_, age)) => (StatusCode::OK, age_header(age), "ok").into_response(),
+        Err(e) => (StatusCode::SERVICE_UNAVAILABLE, e).into_response(),
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::process::Command;
+
+use crate::privileges;
+use crate::system::DiskInfo;
+
+// 用 smartctl -H 读 SMART 自检结果（"PASSED"/"FAILED!" 之类），只在编译时开了 smart 特性时调用。
+// 没装 smartctl、虚拟磁盘或网络文件系统都只是 None，不算采集失败；同一块物理磁盘上的多个分区只查一次
+pub fn fill(disks: &mut [DiskInfo]) {
+    let mut results: HashMap<String, Option<String>> = HashMap::new();
+
+    for disk in disks {
+        let Some(device) = physical_device(&disk.name) else {
+            continue;
+        };
+        disk.smart_health = results.entry(device.clone()).or_insert_with(|| health(&device)).clone();
+    }
+}
+
+fn health(device: &str) -> Option<String> {
+    let output = Command::new("smartctl").args(["-H", device]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    if privileges::is_permission_message(&text) {
+        privileges::denied("system.disks.smart_health");
+        return None;
+    }
+
+    // ATA/NVMe: "SMART overall-health self-assessment test result: PASSED"
+    // SCSI/SAS: "SMART Health Status: OK"
+    text.lines().find_map(|line| {
+        let (label, value) = line.split_once(':')?;
+        let label = label.trim();
+        (label.ends_with("self-assessment test result") || label == "SMART Health Status")
+            .then(|| value.trim().to_string())
+    })
+}
+
+// 分区换成所在的整块磁盘：/dev/nvme0n1p2 -> /dev/nvme0n1；不是 /dev 下的块设备时返回 None
+#[cfg(target_os = "linux")]
+fn physical_device(name: &str) -> Option<String> {
+    use std::fs;
+    use std::path::Path;
+
+    let device = fs::canonicalize(name).ok()?;
+    let block_name = device.file_name()?.to_str()?.to_string();
+    let block = fs::canonicalize(Path::new("/sys/class/block").join(&block_name)).ok()?;
+    if block.join("partition").exists() {
+        let parent = block.parent()?.file_name()?.to_str()?;
+        return Some(format!("/dev/{}", parent));
+    }
+
+    Some(format!("/dev/{}", block_name))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn physical_device(name: &str) -> Option<String> {
+    name.starts_with("/dev/").then(|| name.to_string())
+}
@@ -0,0 +1,106 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::fields;
+use crate::i18n::t;
+use crate::report::Report;
+
+// 采集时间和 meta 里的耗时之类每次都不一样，不参与比较
+const IGNORED: &[&str] = &["collected_at", "collected_at_utc", "meta"];
+
+// --diff 的一项变化；新增的字段 old 为 None，消失的字段 new 为 None。
+// 整块磁盘、整张 GPU 之类的数组元素只出现在一边时记成一项，值是整个对象
+#[derive(Debug, Serialize)]
+pub struct Change {
+    pub path: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+// 两份报告逐字段比较，只返回变了的字段；数组元素按挂载点、UUID 之类的标识配对，
+// 插拔一块磁盘不会让后面的磁盘全都算成变化
+pub fn changes(old: &Report, new: &Report) -> Vec<Change> {
+    let to_value = |report: &Report| {
+        let mut value = serde_json::to_value(report).expect("report serializes to JSON");
+        if let Some(map) = value.as_object_mut() {
+            map.retain(|key, _| !IGNORED.contains(&key.as_str()));
+        }
+        value
+    };
+
+    let mut changes = Vec::new();
+    compare(Some(&to_value(old)), Some(&to_value(new)), "", &mut changes);
+    changes
+}
+
+fn compare(old: Option<&Value>, new: Option<&Value>, path: &str, changes: &mut Vec<Change>) {
+    let join = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+
+    match (old, new) {
+        (Some(Value::Object(old)), Some(Value::Object(new))) => compare_maps(old, new, &join, changes),
+        (Some(Value::Array(old)), Some(Value::Array(new))) => {
+            let keyed = |items: &[Value]| -> Map<String, Value> {
+                items.iter().enumerate().map(|(i, item)| (fields::item_key(item, i), item.clone())).collect()
+            };
+            compare_maps(&keyed(old), &keyed(new), &join, changes);
+        }
+        (old, new) if old != new => changes.push(Change {
+            path: path.to_string(),
+            old: old.cloned(),
+            new: new.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+// 先按旧报告的顺序，再补上新报告里才有的键
+fn compare_maps(
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    join: &dyn Fn(&str) -> String,
+    changes: &mut Vec<Change>,
+) {
+    for (key, old_value) in old {
+        compare(Some(old_value), new.get(key), &join(key), changes);
+    }
+    for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+        compare(None, Some(new_value), &join(key), changes);
+    }
+}
+
+// 每行 "路径: 旧值 -> 新值"，两边都是数字时再附上差值；整个对象只出现在一边时只说新增或消失
+pub fn print(changes: &[Change]) {
+    if changes.is_empty() {
+        println!("{}", t("diff.no_changes"));
+        return;
+    }
+
+    let show = |value: &Option<Value>| match value {
+        None => t("diff.missing").to_string(),
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+    };
+    let is_item = |value: &Option<Value>| matches!(value, Some(Value::Object(_) | Value::Array(_)));
+
+    for change in changes {
+        if change.old.is_none() && is_item(&change.new) {
+            println!("{}: {}", change.path, t("diff.added"));
+            continue;
+        }
+        if change.new.is_none() && is_item(&change.old) {
+            println!("{}: {}", change.path, t("diff.removed"));
+            continue;
+        }
+
+        let delta = match (change.old.as_ref().and_then(Value::as_f64), change.new.as_ref().and_then(Value::as_f64)) {
+            (Some(old), Some(new)) => format!(" ({:+})", round(new - old)),
+            _ => String::new(),
+        };
+        println!("{}: {} -> {}{}", change.path, show(&change.old), show(&change.new), delta);
+    }
+}
+
+// 浮点相减会出现 0.30000000000000004 这样的尾巴
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
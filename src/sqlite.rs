@@ -0,0 +1,64 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection};
+
+use crate::report::Report;
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS readings (
+    timestamp INTEGER NOT NULL,
+    gpu_index INTEGER,
+    temp INTEGER,
+    power REAL,
+    util INTEGER,
+    mem_used REAL,
+    cpu REAL NOT NULL,
+    mem REAL NOT NULL
+)";
+
+const INSERT: &str = "INSERT INTO readings (timestamp, gpu_index, temp, power, util, mem_used, cpu, mem)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)";
+
+// 每次采集按 GPU 写一行；没有 GPU 时写一行 gpu_index 为 NULL 的记录，CPU 和内存照样能查
+pub struct SqliteSink {
+    conn: Connection,
+}
+
+impl SqliteSink {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        conn.execute(SCHEMA, []).map_err(|e| e.to_string())?;
+
+        Ok(SqliteSink { conn })
+    }
+
+    pub fn record(&mut self, report: &Report) -> Result<(), rusqlite::Error> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        let cpu = report.system.average_cpu_usage as f64;
+        let mem = report.system.used_memory;
+
+        let tx = self.conn.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(INSERT)?;
+            if report.gpus.is_empty() {
+                insert.execute(params![timestamp, None::<u32>, None::<u32>, None::<f64>, None::<u32>, None::<f64>, cpu, mem])?;
+            }
+            for gpu in &report.gpus {
+                insert.execute(params![
+                    timestamp,
+                    gpu.index,
+                    gpu.gpu_temperature,
+                    gpu.power_usage,
+                    gpu.utilization,
+                    gpu.memory_used,
+                    cpu,
+                    mem
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+}
@@ -0,0 +1,750 @@
+use std::ffi::OsStr;
+use std::net::IpAddr;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sysinfo::{CpuRefreshKind, Disk, DiskKind, Disks, MemoryRefreshKind, Networks, RefreshKind, System};
+
+use crate::cgroup::{self, CgroupLimits};
+use crate::cpu_cache::{self, CacheInfo};
+use crate::cpu_throttle::{self, CpuThrottle};
+use crate::cpu_times::{self, CpuBreakdown};
+use crate::disk_latency::DiskLatency;
+use crate::diskstats;
+use crate::encryption;
+use crate::host;
+use crate::memory_pressure::{self, MemoryPressure};
+use crate::numa::{self, NumaNode};
+use crate::rapl;
+use crate::virtualization;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SystemInfo {
+    pub total_memory: f64,
+    pub used_memory: f64,
+    #[serde(default)]
+    pub available_memory: f64,
+    // 和 free 的 used 一列一致：总量减去可用量，不含可以回收的缓存和缓冲区；只在 Linux 上计算
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub used_memory_no_cache: Option<f64>,
+    pub total_swap: f64,
+    pub used_swap: f64,
+    // 在容器里时 cgroup 设置的内存和 CPU 上限
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cgroup: Option<CgroupLimits>,
+    // 同样的数据按原始字节数给出，避免 GB 取整丢精度
+    #[serde(default)]
+    pub memory: MemoryUsage,
+    #[serde(default)]
+    pub swap: MemoryUsage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_pressure: Option<MemoryPressure>,
+    pub system_name: Option<String>,
+    pub kernel_version: Option<String>,
+    pub os_version: Option<String>,
+    // 编译目标的架构（x86_64、aarch64）、指针位数和字节序（"little"/"big"）；旧版本保存的报告里为空
+    #[serde(default)]
+    pub arch: String,
+    #[serde(default)]
+    pub pointer_width: usize,
+    #[serde(default)]
+    pub endian: String,
+    pub host_name: Option<String>,
+    pub fqdn: Option<String>,
+    pub primary_ip: Option<IpAddr>,
+    // docker、kvm 之类，裸机为 "none"，和 systemd-detect-virt 的输出一致
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub virtualization: Option<String>,
+    // 只在 --security 时检测
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secure_boot: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tpm_present: Option<bool>,
+    pub uptime: u64,
+    // Linux 上直接数 /proc，其它系统只有 --refresh 包含 processes 时才有；线程数只有 Linux 有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_count: Option<usize>,
+    pub disks: Vec<DiskInfo>,
+    // --max-disks 截掉的磁盘数
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub disks_omitted: usize,
+    // 没加 --all-mounts 时隐藏的重复挂载点数
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub duplicate_mounts: usize,
+    // 没加 --all-mounts 时隐藏的容量为 0 的挂载点数（空光驱、部分虚拟挂载）
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub empty_mounts: usize,
+    // 在 --max-disks 截断之前统计，包含被省略的磁盘
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_summary: Option<DiskSummary>,
+    pub average_cpu_usage: f32,
+    // RAPL 测得的 CPU package 功耗，和 CPU 使用率在同一个采样窗口里测；没有 RAPL 或没有权限时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_power_watts: Option<f64>,
+    pub cpu_times: Option<CpuBreakdown>,
+    pub cpu_throttle: Option<CpuThrottle>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpu_caches: Vec<CacheInfo>,
+    // 多路服务器上每个 NUMA 节点的内存；只有一个节点或不是 Linux 时为空
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub numa_nodes: Vec<NumaNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub networks: Vec<NetworkInfo>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MemoryUsage {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    // total 为 0（没有 swap）时为 null
+    pub used_percent: Option<f64>,
+}
+
+impl MemoryUsage {
+    pub fn new(total_bytes: u64, used_bytes: u64, available_bytes: u64) -> Self {
+        MemoryUsage {
+            total_bytes,
+            used_bytes,
+            available_bytes,
+            used_percent: (total_bytes > 0).then(|| used_bytes as f64 * 100.0 / total_bytes as f64),
+        }
+    }
+}
+
+// 开机以来各网卡的累计收发字节数
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NetworkInfo {
+    pub name: String,
+    pub total_received: u64,
+    pub total_transmitted: u64,
+    // 字节/秒，只有在 CPU 采样窗口里测量时才有
+    pub received_per_sec: Option<f64>,
+    pub transmitted_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DiskInfo {
+    pub name: String,
+    #[serde(serialize_with = "serialize_disk_kind", deserialize_with = "deserialize_disk_kind")]
+    pub kind: DiskKind,
+    pub file_system: String,
+    pub mount_point: String,
+    pub total_space: f64,
+    pub available_space: f64,
+    // 和上面两项相同，但不经过 GB 换算，给需要精确数值的脚本用；旧版本保存的报告里没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available_bytes: Option<u64>,
+    pub inodes_total: Option<u64>,
+    pub inodes_free: Option<u64>,
+    pub encrypted: Option<bool>,
+    pub busy_percent: Option<f64>,
+    // 开机以来累计读写的字节数，SSD 可以用来估算写入寿命的消耗
+    #[serde(default)]
+    pub total_read_bytes: Option<u64>,
+    #[serde(default)]
+    pub total_written_bytes: Option<u64>,
+    // SMART 自检结果，例如 "PASSED"；只有编译时开了 smart 特性才读取
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smart_health: Option<String>,
+    // 只在 --disk-latency 时测量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<DiskLatency>,
+    // 同一设备的第二个及以后的挂载点（bind mount、btrfs 子卷）记下第一个挂载点，总量里不再计入
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+}
+
+// 剩余 inode 低于这个百分比时，即使空间还很多也写不进新文件
+const INODES_EXHAUSTED_PERCENT: f64 = 5.0;
+
+impl DiskInfo {
+    // 容量为 0 时为 None，不会算出 NaN
+    pub fn free_percent(&self) -> Option<f64> {
+        (self.total_space > 0.0).then(|| self.available_space * 100.0 / self.total_space)
+    }
+
+    pub fn inodes_free_percent(&self) -> Option<f64> {
+        let (total, free) = (self.inodes_total?, self.inodes_free?);
+        (total > 0).then(|| free as f64 * 100.0 / total as f64)
+    }
+
+    pub fn inodes_exhausted(&self) -> bool {
+        self.inodes_free_percent().is_some_and(|percent| percent < INODES_EXHAUSTED_PERCENT)
+    }
+}
+
+// 整台机器的存储总量，单位和 DiskInfo 一样是 GB
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct DiskSummary {
+    pub disks: usize,
+    pub total_space: f64,
+    pub used_space: f64,
+    pub available_space: f64,
+    // 总容量为 0 时为 null
+    pub used_percent: Option<f64>,
+}
+
+// 不占真实磁盘空间的文件系统，不计入总量
+const PSEUDO_FILE_SYSTEMS: &[&str] = &[
+    "tmpfs", "devtmpfs", "ramfs", "overlay", "squashfs", "proc", "sysfs", "devfs", "cgroup", "cgroup2", "autofs",
+    "fuse.portal", "efivarfs",
+];
+
+// 按挂载顺序，同一设备第一次出现的挂载点为主，后面的标记为重复。
+// 伪文件系统的设备名都是 tmpfs、overlay 之类，不能按名称判断；Windows 上的名称是卷标，也不用来判断
+pub fn mark_duplicate_mounts(disks: &mut [DiskInfo]) {
+    if !cfg!(unix) {
+        return;
+    }
+
+    let mut first: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    for disk in disks {
+        if PSEUDO_FILE_SYSTEMS.contains(&disk.file_system.as_str()) {
+            continue;
+        }
+        match first.get(&disk.name) {
+            Some(mount_point) => disk.duplicate_of = Some(mount_point.clone()),
+            None => {
+                first.insert(disk.name.clone(), disk.mount_point.clone());
+            }
+        }
+    }
+}
+
+impl DiskSummary {
+    // 同一设备的多个挂载点（bind mount、btrfs 子卷）只算一次
+    pub fn new(disks: &[DiskInfo]) -> Self {
+        let real: Vec<&DiskInfo> = disks
+            .iter()
+            .filter(|disk| !PSEUDO_FILE_SYSTEMS.contains(&disk.file_system.as_str()))
+            .filter(|disk| disk.duplicate_of.is_none())
+            .collect();
+        let total_space: f64 = real.iter().map(|disk| disk.total_space).sum();
+        let available_space: f64 = real.iter().map(|disk| disk.available_space).sum();
+        let used_space = (total_space - available_space).max(0.0);
+
+        DiskSummary {
+            disks: real.len(),
+            total_space,
+            used_space,
+            available_space,
+            used_percent: (total_space > 0.0).then(|| used_space * 100.0 / total_space),
+        }
+    }
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+impl SystemInfo {
+    // 按容量从大到小排
+    pub fn sort_disks(&mut self) {
+        self.disks.sort_by(|a, b| b.total_space.total_cmp(&a.total_space));
+    }
+
+    // 只保留容量最大的 max 个磁盘，其余的计入 disks_omitted
+    pub fn limit_disks(&mut self, max: usize) {
+        if self.disks.len() <= max {
+            return;
+        }
+        self.sort_disks();
+        self.disks_omitted += self.disks.len() - max;
+        self.disks.truncate(max);
+    }
+
+    // 去掉重复的挂载点，数量计入 duplicate_mounts；disk_summary 已经在这之前算好
+    pub fn hide_duplicate_mounts(&mut self) {
+        let before = self.disks.len();
+        self.disks.retain(|disk| disk.duplicate_of.is_none());
+        self.duplicate_mounts += before - self.disks.len();
+    }
+
+    pub fn hide_empty_mounts(&mut self) {
+        let before = self.disks.len();
+        self.disks.retain(|disk| disk.total_space > 0.0);
+        self.empty_mounts += before - self.disks.len();
+    }
+}
+
+// DiskKind 没有实现 Serialize，按 Debug 的文本输出 (SSD/HDD/Unknown(-1))
+fn serialize_disk_kind<S: Serializer>(kind: &DiskKind, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{:?}", kind))
+}
+
+// --from-json 读回报告时按同样的文本还原
+fn deserialize_disk_kind<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DiskKind, D::Error> {
+    let text = String::deserialize(deserializer)?;
+
+    Ok(match text.as_str() {
+        "SSD" => DiskKind::SSD,
+        "HDD" => DiskKind::HDD,
+        other => DiskKind::Unknown(
+            other
+                .strip_prefix("Unknown(")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(-1),
+        ),
+    })
+}
+
+// 要刷新哪些 sysinfo 数据；没刷新的部分输出为 0 或空列表
+#[derive(Debug, Clone, Copy)]
+pub struct Refresh {
+    pub memory: bool,
+    pub cpu: bool,
+    pub disks: bool,
+    pub processes: bool,
+    pub networks: bool,
+}
+
+// 默认不扫描进程表和网卡：进程扫描是最慢的一项
+impl Default for Refresh {
+    fn default() -> Self {
+        Refresh {
+            memory: true,
+            cpu: true,
+            disks: true,
+            processes: false,
+            networks: false,
+        }
+    }
+}
+
+pub fn get_system_info(refresh: &Refresh) -> SystemInfo {
+    // 只刷新需要的内存和 CPU 数据，CPU 使用率需要在同一个实例上间隔采样两次
+    let mut kind = RefreshKind::new();
+    if refresh.memory {
+        kind = kind.with_memory(MemoryRefreshKind::everything());
+    }
+    if refresh.cpu {
+        kind = kind.with_cpu(CpuRefreshKind::everything());
+    }
+    let mut sys = System::new_with_specifics(kind);
+
+    // 不需要 CPU 使用率时省掉 1 秒的采样间隔
+    // 磁盘忙碌比例和网卡速率也在同一个采样窗口里测
+    let mut io_window = None;
+    let mut networks = refresh.networks.then(Networks::new_with_refreshed_list);
+    let mut network_window = None;
+    let mut cpu_power_watts = None;
+    let (stat_before, stat_after) = if refresh.cpu {
+        let before = cpu_times::snapshot();
+        let io_before = if refresh.disks { diskstats::snapshot() } else { None };
+        let energy_before = rapl::snapshot();
+        let started = Instant::now();
+        thread::sleep(Duration::from_secs(1));
+        sys.refresh_cpu();
+        if let (Some(before), Some(after)) = (&energy_before, rapl::snapshot()) {
+            cpu_power_watts = rapl::watts(before, &after, started.elapsed());
+        }
+        if let Some(io_before) = io_before {
+            io_window = diskstats::snapshot().map(|io_after| (io_before, io_after, started.elapsed()));
+        }
+        if let Some(networks) = &mut networks {
+            networks.refresh();
+            network_window = Some(started.elapsed());
+        }
+        (before, cpu_times::snapshot())
+    } else {
+        (None, None)
+    };
+
+    // 个别平台第一次刷新拿不到磁盘列表，空的时候再刷新一次
+    let mut disks = if refresh.disks { Disks::new_with_refreshed_list() } else { Disks::new() };
+    if refresh.disks && disks.list().is_empty() {
+        disks.refresh_list();
+    }
+
+    let mut info = SystemInfo::from((&sys, &disks));
+    #[cfg(windows)]
+    fill_from_wmi(&mut info);
+    info.cpu_power_watts = cpu_power_watts;
+    (info.process_count, info.thread_count) = process_counts(refresh.processes);
+    info.fqdn = info.host_name.as_deref().and_then(host::fqdn);
+    info.primary_ip = host::primary_ip();
+    if let Some((before, after, window)) = &io_window {
+        for disk in &mut info.disks {
+            disk.busy_percent = diskstats::busy_percent(before, after, *window, &disk.name);
+        }
+    }
+    if let Some(io) = refresh.disks.then(diskstats::snapshot).flatten() {
+        for disk in &mut info.disks {
+            (disk.total_read_bytes, disk.total_written_bytes) = diskstats::total_bytes(&io, &disk.name).unzip();
+        }
+    }
+    if let (Some(before), Some(after)) = (stat_before, stat_after) {
+        info.cpu_times = Some(cpu_times::breakdown(&before, &after));
+    }
+    if let Some(networks) = &networks {
+        // received()/transmitted() 是上次 refresh 以来的字节数，除以窗口长度得到速率
+        let rate = |bytes: u64| network_window.map(|window| bytes as f64 / window.as_secs_f64());
+        info.networks = networks
+            .iter()
+            .map(|(name, data)| NetworkInfo {
+                name: name.clone(),
+                total_received: data.total_received(),
+                total_transmitted: data.total_transmitted(),
+                received_per_sec: rate(data.received()),
+                transmitted_per_sec: rate(data.transmitted()),
+            })
+            .collect();
+    }
+    info
+}
+
+impl From<(&System, &Disks)> for SystemInfo {
+    fn from((sys, disks): (&System, &Disks)) -> Self {
+        let average_cpu_usage = if !sys.cpus().is_empty() {
+            sys.cpus().iter().map(|cpu| cpu.cpu_usage()).sum::<f32>() / sys.cpus().len() as f32
+        } else {
+            0.0
+        };
+
+        let mut disks: Vec<DiskInfo> = disks.list().iter().map(DiskInfo::from).collect();
+        mark_duplicate_mounts(&mut disks);
+        let memory = MemoryUsage::new(sys.total_memory(), sys.used_memory(), sys.available_memory());
+        let swap = MemoryUsage::new(sys.total_swap(), sys.used_swap(), sys.free_swap());
+
+        SystemInfo {
+            total_memory: bytes_to_gb(sys.total_memory()),
+            used_memory: bytes_to_gb(sys.used_memory()),
+            available_memory: bytes_to_gb(sys.available_memory()),
+            used_memory_no_cache: cfg!(target_os = "linux")
+                .then(|| bytes_to_gb(sys.total_memory().saturating_sub(sys.available_memory()))),
+            total_swap: bytes_to_gb(sys.total_swap()),
+            used_swap: bytes_to_gb(sys.used_swap()),
+            cgroup: cgroup::read(),
+            memory_pressure: memory_pressure::assess(&memory, &swap, memory_pressure::read_psi()),
+            memory,
+            swap,
+            system_name: System::name(),
+            kernel_version: System::kernel_version(),
+            os_version: System::os_version(),
+            arch: std::env::consts::ARCH.to_string(),
+            pointer_width: usize::BITS as usize,
+            endian: if cfg!(target_endian = "big") { "big" } else { "little" }.to_string(),
+            host_name: System::host_name(),
+            fqdn: None,
+            primary_ip: None,
+            virtualization: virtualization::detect(),
+            secure_boot: None,
+            tpm_present: None,
+            uptime: System::uptime(),
+            process_count: None,
+            thread_count: None,
+            disk_summary: Some(DiskSummary::new(&disks)),
+            disks,
+            disks_omitted: 0,
+            duplicate_mounts: 0,
+            empty_mounts: 0,
+            average_cpu_usage,
+            cpu_power_watts: None,
+            cpu_times: None,
+            cpu_throttle: cpu_throttle::read(),
+            cpu_caches: cpu_cache::read(),
+            numa_nodes: numa::read(),
+            networks: Vec::new(),
+        }
+    }
+}
+
+impl From<&Disk> for DiskInfo {
+    fn from(disk: &Disk) -> Self {
+        let (inodes_total, inodes_free) = inode_counts(disk.mount_point());
+
+        DiskInfo {
+            name: os_str_to_string(disk.name()),
+            kind: disk.kind(),
+            file_system: os_str_to_string(disk.file_system()),
+            mount_point: path_to_string(disk.mount_point()),
+            total_space: bytes_to_gb(disk.total_space()),
+            available_space: bytes_to_gb(disk.available_space()),
+            total_bytes: Some(disk.total_space()),
+            available_bytes: Some(disk.available_space()),
+            inodes_total,
+            inodes_free,
+            encrypted: encryption::is_encrypted(&os_str_to_string(disk.name()), disk.mount_point()),
+            busy_percent: None,
+            total_read_bytes: None,
+            total_written_bytes: None,
+            smart_health: None,
+            latency: None,
+            duplicate_of: None,
+        }
+    }
+}
+
+// 不用扫描整个进程表：/proc 下数字命名的目录就是进程，/proc/loadavg 第 4 列 "运行中/总数" 的总数是线程数
+#[cfg(target_os = "linux")]
+fn process_counts(_scan: bool) -> (Option<usize>, Option<usize>) {
+    let processes = std::fs::read_dir("/proc").ok().map(|entries| {
+        entries
+            .flatten()
+            .filter(|entry| entry.file_name().to_str().is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit())))
+            .count()
+    });
+    let threads = std::fs::read_to_string("/proc/loadavg")
+        .ok()
+        .and_then(|text| text.split_whitespace().nth(3)?.split_once('/')?.1.parse().ok());
+
+    (processes, threads)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_counts(scan: bool) -> (Option<usize>, Option<usize>) {
+    if !scan {
+        return (None, None);
+    }
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(sysinfo::ProcessRefreshKind::new());
+    (Some(sys.processes().len()), None)
+}
+
+// 通过 statvfs 读取 inode 总数和剩余数；部分网络文件系统报告 0 个 inode，此时当作未知
+#[cfg(unix)]
+fn inode_counts(mount_point: &Path) -> (Option<u64>, Option<u64>) {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(path) = CString::new(mount_point.as_os_str().as_bytes()) else {
+        return (None, None);
+    };
+
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 || stat.f_files == 0 {
+        return (None, None);
+    }
+
+    (Some(stat.f_files as u64), Some(stat.f_ffree as u64))
+}
+
+#[cfg(not(unix))]
+fn inode_counts(_mount_point: &Path) -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+// 部分 Windows 版本上 sysinfo 拿不到系统名称或版本号，用 WMI 的 Win32_OperatingSystem 补上
+#[cfg(windows)]
+fn fill_from_wmi(info: &mut SystemInfo) {
+    use std::process::Command;
+
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct OperatingSystem {
+        caption: Option<String>,
+        version: Option<String>,
+        build_number: Option<String>,
+        #[serde(rename = "CSName")]
+        cs_name: Option<String>,
+    }
+
+    if info.system_name.is_some()
+        && info.os_version.is_some()
+        && info.kernel_version.is_some()
+        && info.host_name.is_some()
+    {
+        return;
+    }
+
+    let Ok(output) = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_OperatingSystem | Select-Object Caption,Version,BuildNumber,CSName | ConvertTo-Json",
+        ])
+        .output()
+    else {
+        return;
+    };
+    let Ok(os) = serde_json::from_slice::<OperatingSystem>(&output.stdout) else {
+        return;
+    };
+
+    // Caption 形如 "Microsoft Windows 11 Pro"，Version 形如 "10.0.22631"
+    info.system_name = info.system_name.take().or(os.caption.map(|caption| caption.trim().to_string()));
+    info.os_version = info.os_version.take().or(os.version);
+    info.kernel_version = info.kernel_version.take().or(os.build_number);
+    info.host_name = info.host_name.take().or(os.cs_name);
+}
+
+fn os_str_to_string(os_str: &OsStr) -> String {
+    os_str.to_string_lossy().into_owned()
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+pub fn bytes_to_gb(bytes: u64) -> f64 {
+    // 1 GB = 1024^3 bytes
+    bytes as f64 / 1024_f64.powi(3)
+}
+
+// 1.2M / 350K 这种简写，用于 inode 等计数
+pub fn format_count(count: u64) -> String {
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => format!("{:.1}K", count as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1}M", count as f64 / 1e6),
+        _ => format!("{:.1}G", count as f64 / 1e9),
+    }
+}
+
+// 速率的单位：SI 按 1000 进位（kB/s、MB/s），IEC 按 1024 进位（KiB/s、MiB/s）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Units {
+    #[default]
+    Si,
+    Iec,
+}
+
+// 例如 "512 B"、"1.20 MB"、"3.40 TB"
+pub fn format_bytes(bytes: f64, units: Units) -> String {
+    let (base, names) = match units {
+        Units::Si => (1000.0, ["kB", "MB", "GB", "TB", "PB"]),
+        Units::Iec => (1024.0, ["KiB", "MiB", "GiB", "TiB", "PiB"]),
+    };
+
+    if bytes < base {
+        return format!("{:.0} B", bytes.max(0.0));
+    }
+
+    let mut value = bytes / base;
+    let mut name = names[0];
+    for next in &names[1..] {
+        if value < base {
+            break;
+        }
+        value /= base;
+        name = next;
+    }
+
+    format!("{:.2} {}", value, name)
+}
+
+// 例如 "512 B/s"、"1.20 MB/s"、"3.40 GB/s"，所有速率显示都用这个
+pub fn format_rate(bytes_per_sec: f64, units: Units) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec, units))
+}
+
+pub fn convert_seconds(seconds: u64) -> (u64, u64, u64, u64) {
+    let days = seconds / (24 * 3600);
+    let hours = (seconds / 3600) % 24;
+    let minutes = (seconds / 60) % 60;
+    let remaining_seconds = seconds % 60;
+
+    (days, hours, minutes, remaining_seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_rate_si() {
+        assert_eq!(format_rate(0.0, Units::Si), "0 B/s");
+        assert_eq!(format_rate(999.0, Units::Si), "999 B/s");
+        assert_eq!(format_rate(1000.0, Units::Si), "1.00 kB/s");
+        assert_eq!(format_rate(1024.0, Units::Si), "1.02 kB/s");
+        assert_eq!(format_rate(1_500_000.0, Units::Si), "1.50 MB/s");
+    }
+
+    #[test]
+    fn format_rate_iec() {
+        assert_eq!(format_rate(0.0, Units::Iec), "0 B/s");
+        assert_eq!(format_rate(999.0, Units::Iec), "999 B/s");
+        assert_eq!(format_rate(1023.0, Units::Iec), "1023 B/s");
+        assert_eq!(format_rate(1024.0, Units::Iec), "1.00 KiB/s");
+        assert_eq!(format_rate(3.0 * 1024.0 * 1024.0, Units::Iec), "3.00 MiB/s");
+    }
+
+    // 空光驱、部分虚拟挂载：容量、inode 都是 0
+    fn empty_disk() -> DiskInfo {
+        DiskInfo {
+            name: "sr0".to_string(),
+            kind: DiskKind::Unknown(-1),
+            file_system: "iso9660".to_string(),
+            mount_point: "/media/cdrom".to_string(),
+            total_space: 0.0,
+            available_space: 0.0,
+            total_bytes: Some(0),
+            available_bytes: Some(0),
+            inodes_total: Some(0),
+            inodes_free: Some(0),
+            encrypted: None,
+            busy_percent: None,
+            total_read_bytes: None,
+            total_written_bytes: None,
+            smart_health: None,
+            latency: None,
+            duplicate_of: None,
+        }
+    }
+
+    // 不做 1 秒的 CPU 采样，只走内存和磁盘的真实采集
+    fn collect() -> SystemInfo {
+        get_system_info(&Refresh { cpu: false, ..Refresh::default() })
+    }
+
+    #[test]
+    fn conversion_matches_sysinfo() {
+        let info = collect();
+        let sys = System::new_with_specifics(RefreshKind::new().with_memory(MemoryRefreshKind::everything()));
+        assert_eq!(info.total_memory, bytes_to_gb(sys.total_memory()));
+        assert_eq!(info.total_swap, bytes_to_gb(sys.total_swap()));
+        assert_eq!(info.memory.total_bytes, sys.total_memory());
+        assert_eq!(info.swap.total_bytes, sys.total_swap());
+
+        let disks = Disks::new_with_refreshed_list();
+        let mut expected: Vec<String> = disks.list().iter().map(|disk| path_to_string(disk.mount_point())).collect();
+        let mut mount_points: Vec<String> = info.disks.iter().map(|disk| disk.mount_point.clone()).collect();
+        expected.sort();
+        mount_points.sort();
+        assert_eq!(mount_points, expected);
+        for (disk, expected) in info.disks.iter().zip(disks.list()) {
+            assert_eq!(disk.total_bytes, Some(expected.total_space()));
+            assert_eq!(disk.total_space, bytes_to_gb(expected.total_space()));
+        }
+    }
+
+    #[test]
+    fn conversion_hides_zero_capacity_disks() {
+        let mut info = collect();
+        let empty = info.disks.iter().filter(|disk| disk.total_space == 0.0).count();
+        for disk in &info.disks {
+            let percent = disk.free_percent();
+            assert_eq!(percent.is_none(), disk.total_space == 0.0, "{}", disk.mount_point);
+            assert!(percent.is_none_or(f64::is_finite));
+        }
+
+        info.hide_empty_mounts();
+        assert_eq!(info.empty_mounts, empty);
+        assert!(info.disks.iter().all(|disk| disk.free_percent().is_some()));
+    }
+
+    #[test]
+    fn zero_capacity_disk_has_no_percentages() {
+        let disk = empty_disk();
+        assert_eq!(disk.free_percent(), None);
+        assert_eq!(disk.inodes_free_percent(), None);
+        assert!(!disk.inodes_exhausted());
+
+        let summary = DiskSummary::new(&[disk]);
+        assert_eq!(summary.disks, 1);
+        assert_eq!(summary.used_percent, None);
+        assert!(summary.total_space.is_finite() && summary.used_space.is_finite());
+        assert!(summary.available_space.is_finite());
+    }
+}
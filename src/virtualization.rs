@@ -0,0 +1,71 @@
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+// 和 systemd-detect-virt 的输出一致：先看是不是在容器里，再看是不是虚拟机，裸机是 "none"；判断不了的平台为 None
+#[cfg(target_os = "linux")]
+pub fn detect() -> Option<String> {
+    Some(container().or_else(vm).unwrap_or("none").to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn detect() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn container() -> Option<&'static str> {
+    if Path::new("/.dockerenv").exists() {
+        return Some("docker");
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("podman");
+    }
+
+    // 1 号进程的 cgroup 路径里带着容器运行时的名字；cgroup v2 的私有命名空间里只有 "0::/"，就只能靠上面的文件判断
+    let cgroup = fs::read_to_string("/proc/1/cgroup").unwrap_or_default();
+    if cgroup.contains("kubepods") {
+        return Some("kubernetes");
+    }
+    if cgroup.contains("docker") {
+        return Some("docker");
+    }
+    if cgroup.contains("/lxc") {
+        return Some("lxc");
+    }
+
+    let release = fs::read_to_string("/proc/sys/kernel/osrelease").unwrap_or_default().to_lowercase();
+    release.contains("microsoft").then_some("wsl")
+}
+
+// DMI 里的厂商和型号名；云主机的型号名一般也是底层虚拟化的名字
+#[cfg(target_os = "linux")]
+fn vm() -> Option<&'static str> {
+    let read = |name: &str| fs::read_to_string(format!("/sys/class/dmi/id/{}", name)).unwrap_or_default();
+    let dmi = format!("{} {}", read("sys_vendor"), read("product_name"));
+
+    const VENDORS: &[(&str, &str)] = &[
+        ("KVM", "kvm"),
+        ("QEMU", "qemu"),
+        ("VMware", "vmware"),
+        ("VirtualBox", "oracle"),
+        ("innotek", "oracle"),
+        ("Xen", "xen"),
+        ("Amazon EC2", "amazon"),
+        ("Google Compute Engine", "google"),
+        ("Virtual Machine", "microsoft"),
+        ("Parallels", "parallels"),
+        ("BHYVE", "bhyve"),
+    ];
+    if let Some((_, name)) = VENDORS.iter().find(|(marker, _)| dmi.contains(marker)) {
+        return Some(name);
+    }
+
+    // 没有 DMI 信息（例如部分 ARM 虚拟机）时，CPU 标志里的 hypervisor 至少说明在虚拟机里
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    cpuinfo
+        .lines()
+        .any(|line| line.starts_with("flags") && line.split_whitespace().any(|flag| flag == "hypervisor"))
+        .then_some("vm")
+}
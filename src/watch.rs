@@ -0,0 +1,720 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cli::Cli;
+use crate::clock;
+use crate::cpu_throttle::CpuThrottle;
+use crate::delta::DeltaEncoder;
+use crate::gpu::{GpuInfo, GpuPoller, GpuSparklines};
+use crate::history;
+use crate::i18n::{t, tf};
+use crate::log_file::RotatingLog;
+use crate::output::Output;
+use crate::report::{GpuCache, Report};
+use crate::sqlite::SqliteSink;
+
+static STOP: AtomicBool = AtomicBool::new(false);
+// SIGHUP / SIGUSR1 只设置这个标志，由 sleep_until 提前结束等待；采集过程中收到的信号
+// 在这一轮输出完后立即再采一次，连续多个信号合并成一次
+static REFRESH: AtomicBool = AtomicBool::new(false);
+
+// 返回退出码：--crit-on-throttle 时运行期间出现过严重降频就是 5
+pub fn run(cli: &Cli) -> i32 {
+    // Ctrl-C 只设置标志，等当前这一轮采集输出完再打印会话汇总退出
+    ctrlc::set_handler(|| STOP.store(true, Ordering::SeqCst)).expect("failed to install Ctrl-C handler");
+    install_refresh_handler();
+
+    let interval = cli.interval;
+    let mut vram_tracker = cli
+        .watch_vram_growth
+        .then(|| VramGrowthTracker::new(cli.vram_growth_window));
+    let mut throttle_tracker = ThrottleTracker::default();
+    let mut temperature_trend = TemperatureTrend::default();
+    let mut delta = cli.delta.then(|| DeltaEncoder::new(cli.full_every));
+    let mut output = match Output::new(cli) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    let mut sqlite = match cli.sqlite.as_deref().map(SqliteSink::open).transpose() {
+        Ok(sink) => sink,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    let log_file = cli
+        .log_file
+        .as_deref()
+        .map(|path| RotatingLog::open(path, cli.log_max_size * 1024 * 1024, cli.log_keep))
+        .transpose();
+    let mut log_file = match log_file {
+        Ok(log) => log,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    let mut history = match cli.open_history() {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    let mut cpu_throttle: Option<CpuThrottle> = None;
+    let mut session = SessionStats::default();
+    let text = output.is_text();
+    let mut sparklines = (cli.sparkline > 0 && text).then(|| Sparklines::new(cli.sparkline));
+    // 输出到日志或 CI 时不清屏、不隐藏光标，每一轮接着往后写，轮与轮之间空一行
+    let redraw = text && io::stdout().is_terminal();
+
+    if redraw {
+        // 重绘期间隐藏光标，退出时恢复
+        print!("\x1B[?25l");
+    }
+
+    let mut ticker = Ticker::new(interval);
+    let started = Instant::now();
+    let mut refreshes = 0;
+    // 同一个 GPU 的同一个原因只报一次，按出现的顺序
+    let mut throttle_alerts: Vec<String> = Vec::new();
+    let mut gpus = GpuCache::new(&cli.collect_options());
+    while !STOP.load(Ordering::SeqCst) {
+        let mut report = crate::collect_cached(cli, Some(&mut gpus));
+        let failures = crate::strict_failures(cli, &report);
+        if !failures.is_empty() {
+            show_cursor(redraw);
+            for failure in failures {
+                eprintln!("{}", failure);
+            }
+            process::exit(4);
+        }
+        temperature_trend.update(&mut report.gpus, Instant::now());
+        if let Some(sparklines) = &mut sparklines {
+            sparklines.update(&mut report.gpus);
+        }
+
+        if redraw {
+            // 清屏并把光标移到左上角，整屏重绘
+            print!("\x1B[2J\x1B[H");
+        } else if text && refreshes > 0 {
+            println!();
+        }
+        let emitted = match &mut delta {
+            Some(encoder) => output
+                .value(&report)
+                .and_then(|value| output.write_line(&encoder.encode(value).to_string())),
+            None => output.emit(&report),
+        };
+        if let Err(e) = emitted {
+            show_cursor(redraw);
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+
+        if let Some(tracker) = &mut vram_tracker {
+            for line in tracker.update(&report.gpus, Instant::now()) {
+                notice(text, &line);
+            }
+        }
+        throttle_tracker.update(&report.gpus);
+        for alert in crate::throttle_alerts(cli, &report) {
+            if !throttle_alerts.contains(&alert) {
+                throttle_alerts.push(alert);
+            }
+        }
+        if let Some(current) = report.system.cpu_throttle {
+            if let Some(line) = cpu_throttle.and_then(|previous| cpu_throttle_increase(previous, current)) {
+                notice(text, &line);
+            }
+            cpu_throttle = Some(current);
+        }
+        session.update(&report);
+
+        // 数据库暂时写不进去（比如被别的进程锁住）不影响 watch 继续运行
+        if let Some(sink) = &mut sqlite {
+            if let Err(e) = sink.record(&report) {
+                eprintln!("sqlite: {}", e);
+            }
+        }
+        if let Some(log) = &mut log_file {
+            if let Err(e) = log.record(&report) {
+                eprintln!("log-file: {}", e);
+            }
+        }
+        if let Some(log) = &mut history {
+            if let Err(e) = history::record(log, &report) {
+                eprintln!("history: {}", e);
+            }
+        }
+
+        refreshes += 1;
+        if cli.count > 0 && refreshes >= cli.count {
+            break;
+        }
+        ticker.wait();
+    }
+
+    show_cursor(redraw);
+
+    notice(text, t("watch.summary"));
+    // 只精确到秒，毫秒部分对会话时长没有意义
+    let runtime = Duration::from_secs(started.elapsed().as_secs());
+    notice(text, &tf("watch.runtime", &[&humantime::format_duration(runtime), &refreshes]));
+    for line in session.summary(cli.precision.into()) {
+        notice(text, &line);
+    }
+    for line in throttle_tracker.summary() {
+        notice(text, &line);
+    }
+
+    if throttle_alerts.is_empty() {
+        return 0;
+    }
+    for alert in throttle_alerts {
+        eprintln!("{}", alert);
+    }
+    5
+}
+
+// 和 nvidia-smi dmon 一样每轮追加一行，不清屏，适合重定向到文件；只查询 GPU，不做 CPU 采样。
+// 表头和 dmon 一样不翻译，列宽固定方便 awk 之类的工具处理
+pub fn dmon(cli: &Cli) {
+    ctrlc::set_handler(|| STOP.store(true, Ordering::SeqCst)).expect("failed to install Ctrl-C handler");
+
+    println!(
+        "# {:<19} {:>3} {:>5} {:>7} {:>5} {:>9} {:>6} {:>6}",
+        "time", "gpu", "temp", "pwr", "util", "mem", "sclk", "mclk"
+    );
+    println!(
+        "# {:<19} {:>3} {:>5} {:>7} {:>5} {:>9} {:>6} {:>6}",
+        "", "", "C", "W", "%", "GB", "MHz", "MHz"
+    );
+
+    let mut poller = GpuPoller::new(&cli.gpu_indices(), !cli.no_wake, cli.gpu_backend);
+    let mut ticker = Ticker::new(cli.interval);
+    while !STOP.load(Ordering::SeqCst) {
+        let time = clock::now().format("%Y-%m-%d %H:%M:%S");
+        match poller.poll() {
+            Ok(gpus) => {
+                for gpu in gpus {
+                    println!(
+                        "  {:<19} {:>3} {:>5} {:>7} {:>5} {:>9} {:>6} {:>6}",
+                        time,
+                        gpu.index,
+                        dash(gpu.gpu_temperature),
+                        dash(gpu.power_usage.map(|power| format!("{:.1}", power))),
+                        dash(gpu.utilization),
+                        dash(gpu.memory_used.map(|used| format!("{:.2}", used))),
+                        dash(gpu.core_clock),
+                        dash(gpu.memory_clock),
+                    );
+                }
+            }
+            // 驱动偶尔会短暂出错，记一行继续，不中断整段记录
+            Err(e) => eprintln!("{} {}: {}", time, t("error"), e),
+        }
+
+        if cli.interval.is_zero() {
+            break;
+        }
+        ticker.wait();
+    }
+}
+
+// 高频采样 GPU 温度、频率和降频原因写成 CSV，用来画频率-温度曲线；
+// Ctrl-C 提前结束时已写的行照常保留
+pub fn capture(cli: &Cli, path: &Path, duration: Duration) -> Result<(), String> {
+    ctrlc::set_handler(|| STOP.store(true, Ordering::SeqCst)).expect("failed to install Ctrl-C handler");
+
+    let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut out = BufWriter::new(file);
+    let write_error = |e: io::Error| format!("{}: {}", path.display(), e);
+    writeln!(
+        out,
+        concat!(
+            "time,elapsed_s,gpu,uuid,temperature_c,core_clock_mhz,memory_clock_mhz,power_w,",
+            "utilization_percent,throttle_reasons"
+        )
+    )
+    .map_err(write_error)?;
+
+    let mut poller = GpuPoller::new(&cli.gpu_indices(), !cli.no_wake, cli.gpu_backend);
+    let start = Instant::now();
+    let mut ticker = Ticker::new(cli.capture_interval);
+    let mut rows = 0;
+    while !STOP.load(Ordering::SeqCst) && start.elapsed() < duration {
+        let time = clock::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z");
+        let elapsed = start.elapsed().as_secs_f64();
+        // 和 dmon 一样，偶尔出错记一行继续
+        match poller.poll() {
+            Ok(gpus) => {
+                for gpu in gpus {
+                    let cell = |value: Option<String>| value.unwrap_or_default();
+                    writeln!(
+                        out,
+                        "{},{:.3},{},{},{},{},{},{},{},{}",
+                        time,
+                        elapsed,
+                        gpu.index,
+                        gpu.uuid,
+                        cell(gpu.gpu_temperature.map(|value| value.to_string())),
+                        cell(gpu.core_clock.map(|value| value.to_string())),
+                        cell(gpu.memory_clock.map(|value| value.to_string())),
+                        cell(gpu.power_usage.map(|power| format!("{:.1}", power))),
+                        cell(gpu.utilization.map(|value| value.to_string())),
+                        // 原因名称里没有逗号，多个原因用分号隔开
+                        gpu.throttle_reasons.join(";"),
+                    )
+                    .map_err(write_error)?;
+                    rows += 1;
+                }
+            }
+            Err(e) => eprintln!("{} {}: {}", time, t("error"), e),
+        }
+        ticker.wait();
+    }
+
+    out.flush().map_err(write_error)?;
+    eprintln!("{}", tf("capture.done", &[&rows, &path.display()]));
+    Ok(())
+}
+
+// 读不到的值和 dmon 一样显示为 -
+fn dash<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+// 正常结束和中途出错退出前都要恢复重绘时隐藏的光标
+fn show_cursor(redraw: bool) {
+    if redraw {
+        print!("\x1B[?25h");
+        let _ = io::stdout().flush();
+    }
+}
+
+// 非文本格式下提示信息走 stderr，stdout 只保留机器可读的数据
+fn notice(text: bool, line: &str) {
+    if text {
+        println!("{}", line);
+    } else {
+        eprintln!("{}", line);
+    }
+}
+
+// 按固定的节拍采集：第 n 轮从 start + n * interval 开始，采集本身的耗时不会累积成漂移；
+// 某一轮超时的话跳过已经错过的节拍，不连着补采
+struct Ticker {
+    interval: Duration,
+    next: Instant,
+}
+
+impl Ticker {
+    fn new(interval: Duration) -> Self {
+        Ticker {
+            interval,
+            next: Instant::now() + interval,
+        }
+    }
+
+    // 被 SIGHUP / SIGUSR1 提前唤醒时不推进节拍，额外的一轮不打乱定时采集
+    fn wait(&mut self) {
+        let now = Instant::now();
+        if self.next < now {
+            let missed = (now - self.next).as_nanos() / self.interval.as_nanos() + 1;
+            self.next += self.interval * missed as u32;
+        }
+        if sleep_until(self.next) {
+            self.next += self.interval;
+        }
+    }
+}
+
+// 睡到 deadline 返回 true；Ctrl-C 或收到刷新信号时提前返回 false
+fn sleep_until(deadline: Instant) -> bool {
+    while !STOP.load(Ordering::SeqCst) {
+        if REFRESH.swap(false, Ordering::SeqCst) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        thread::sleep((deadline - now).min(Duration::from_millis(100)));
+    }
+
+    false
+}
+
+#[cfg(unix)]
+fn install_refresh_handler() {
+    extern "C" fn handler(_: libc::c_int) {
+        REFRESH.store(true, Ordering::SeqCst);
+    }
+
+    // SAFETY: handler 只写一个原子变量，是异步信号安全的
+    unsafe {
+        libc::signal(libc::SIGHUP, handler as *const () as libc::sighandler_t);
+        libc::signal(libc::SIGUSR1, handler as *const () as libc::sighandler_t);
+    }
+}
+
+#[cfg(not(unix))]
+fn install_refresh_handler() {}
+
+fn cpu_throttle_increase(previous: CpuThrottle, current: CpuThrottle) -> Option<String> {
+    let core = current.core_throttle_count.saturating_sub(previous.core_throttle_count);
+    let package = current.package_throttle_count.saturating_sub(previous.package_throttle_count);
+
+    (core > 0 || package > 0).then(|| tf("watch.cpu_throttle", &[&core, &package]))
+}
+
+#[derive(Default)]
+struct Stats {
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: u32,
+}
+
+impl Stats {
+    fn add(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        }
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    // 一次都没采到（比如这张卡读不到功耗）时不输出
+    fn line(&self, label: &str, unit: &str, precision: usize) -> Option<String> {
+        (self.count > 0).then(|| {
+            tf(
+                "watch.stat",
+                &[
+                    &label,
+                    &format!("{:.*}", precision, self.min),
+                    &format!("{:.*}", precision, self.max),
+                    &format!("{:.*}", precision, self.sum / self.count as f64),
+                    &unit,
+                ],
+            )
+        })
+    }
+
+    // 频率看的是波动幅度：负载下最小值比最大值低很多通常意味着降频或超频不稳定
+    fn spread_line(&self, label: &str, precision: usize) -> Option<String> {
+        (self.count > 0).then(|| {
+            let spread = self.max - self.min;
+            let percent = if self.max > 0.0 { spread * 100.0 / self.max } else { 0.0 };
+            tf(
+                "watch.clock_spread",
+                &[&label, &self.min, &self.max, &spread, &format!("{:.*}", precision, percent)],
+            )
+        })
+    }
+}
+
+#[derive(Default)]
+struct GpuStats {
+    index: u32,
+    temperature: Stats,
+    power: Stats,
+    utilization: Stats,
+    memory_used: Stats,
+    core_clock: Stats,
+    memory_clock: Stats,
+}
+
+// 整个会话期间关键指标的 min/max/avg，Ctrl-C 退出时打印
+#[derive(Default)]
+struct SessionStats {
+    cpu: Stats,
+    memory_used: Stats,
+    gpus: BTreeMap<String, GpuStats>,
+}
+
+impl SessionStats {
+    fn update(&mut self, report: &Report) {
+        self.cpu.add(report.system.average_cpu_usage as f64);
+        self.memory_used.add(report.system.used_memory);
+
+        for gpu in &report.gpus {
+            let stats = self.gpus.entry(gpu.uuid.clone()).or_default();
+            stats.index = gpu.index;
+            if let Some(temperature) = gpu.gpu_temperature {
+                stats.temperature.add(temperature as f64);
+            }
+            if let Some(power) = gpu.power_usage {
+                stats.power.add(power);
+            }
+            if let Some(utilization) = gpu.utilization {
+                stats.utilization.add(utilization as f64);
+            }
+            if let Some(memory_used) = gpu.memory_used {
+                stats.memory_used.add(memory_used);
+            }
+            if let Some(clock) = gpu.core_clock {
+                stats.core_clock.add(clock as f64);
+            }
+            if let Some(clock) = gpu.memory_clock {
+                stats.memory_clock.add(clock as f64);
+            }
+        }
+    }
+
+    // --precision 同样适用于结束时的汇总
+    fn summary(&self, precision: usize) -> Vec<String> {
+        let mut lines: Vec<String> = [
+            self.cpu.line(t("system.cpu_usage"), "%", precision),
+            self.memory_used.line(t("system.used_memory"), "GB", precision),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        for stats in self.gpus.values() {
+            let prefix = tf("watch.gpu_prefix", &[&stats.index]);
+            lines.extend(
+                [
+                    stats.temperature.line(&format!("{} {}", prefix, t("gpu.temperature")), "C", precision),
+                    stats.power.line(&format!("{} {}", prefix, t("gpu.power_usage")), "W", precision),
+                    stats.utilization.line(&format!("{} {}", prefix, t("gpu.utilization")), "%", precision),
+                    stats.memory_used.line(&format!("{} {}", prefix, t("gpu.memory_used")), "GB", precision),
+                    stats.core_clock.spread_line(&format!("{} {}", prefix, t("gpu.core_clock")), precision),
+                    stats.memory_clock.spread_line(&format!("{} {}", prefix, t("gpu.memory_clock")), precision),
+                ]
+                .into_iter()
+                .flatten(),
+            );
+        }
+
+        lines
+    }
+}
+
+// 按 UUID 记住上一次采样的温度；第一次采样没有可比较的值，不显示趋势
+#[derive(Default)]
+struct TemperatureTrend {
+    previous: HashMap<String, (Instant, u32)>,
+}
+
+impl TemperatureTrend {
+    fn update(&mut self, gpus: &mut [GpuInfo], now: Instant) {
+        for gpu in gpus {
+            let Some(temperature) = gpu.gpu_temperature else {
+                continue;
+            };
+            if let Some((at, previous)) = self.previous.insert(gpu.uuid.clone(), (now, temperature)) {
+                let seconds = now.duration_since(at).as_secs_f64();
+                if seconds > 0.0 {
+                    gpu.temperature_trend = Some((temperature as f64 - previous as f64) / seconds);
+                }
+            }
+        }
+    }
+}
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// 按 UUID 保存温度、利用率、核心和显存频率最近 width 次的样本；读不到的值不记
+struct Sparklines {
+    width: usize,
+    gpus: HashMap<String, [VecDeque<f64>; 4]>,
+}
+
+impl Sparklines {
+    fn new(width: usize) -> Self {
+        Sparklines {
+            width,
+            gpus: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, gpus: &mut [GpuInfo]) {
+        for gpu in gpus {
+            let history = self.gpus.entry(gpu.uuid.clone()).or_default();
+            let values = [gpu.gpu_temperature, gpu.utilization, gpu.core_clock, gpu.memory_clock];
+            for (samples, value) in history.iter_mut().zip(values) {
+                if let Some(value) = value {
+                    samples.push_back(value as f64);
+                }
+                while samples.len() > self.width {
+                    samples.pop_front();
+                }
+            }
+
+            let [temperature, utilization, core_clock, memory_clock] = history.each_ref().map(sparkline);
+            gpu.sparklines = GpuSparklines {
+                temperature,
+                utilization,
+                core_clock,
+                memory_clock,
+            };
+        }
+    }
+}
+
+// 按窗口里的最小值到最大值缩放，全部相同时画成最低的一条线
+fn sparkline(samples: &VecDeque<f64>) -> String {
+    let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    samples
+        .iter()
+        .map(|&value| {
+            let level = if max > min { ((value - min) / (max - min) * 7.0).round() as usize } else { 0 };
+            SPARK_LEVELS[level]
+        })
+        .collect()
+}
+
+struct ThrottleHistory {
+    index: u32,
+    samples: u32,
+    active: BTreeMap<String, u32>,
+}
+
+// 按 UUID 统计每种降频原因在多少次采样中出现过
+#[derive(Default)]
+struct ThrottleTracker {
+    gpus: BTreeMap<String, ThrottleHistory>,
+}
+
+impl ThrottleTracker {
+    fn update(&mut self, gpus: &[GpuInfo]) {
+        for gpu in gpus {
+            let history = self.gpus.entry(gpu.uuid.clone()).or_insert_with(|| ThrottleHistory {
+                index: gpu.index,
+                samples: 0,
+                active: BTreeMap::new(),
+            });
+            history.index = gpu.index;
+            history.samples += 1;
+            for reason in &gpu.throttle_reasons {
+                *history.active.entry(reason.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for history in self.gpus.values() {
+            if history.active.is_empty() {
+                lines.push(tf("watch.no_throttle", &[&history.index]));
+                continue;
+            }
+
+            for (reason, count) in &history.active {
+                let percent = *count as f64 * 100.0 / history.samples as f64;
+                lines.push(tf(
+                    "watch.throttle_active",
+                    &[&history.index, reason, &format!("{:.0}", percent)],
+                ));
+            }
+        }
+
+        lines
+    }
+}
+
+struct VramHistory {
+    index: u32,
+    min: f64,
+    max: f64,
+    samples: VecDeque<(Instant, f64)>,
+}
+
+// 按 UUID 记录每块 GPU 的显存占用，设备序号变化时不会串数据
+struct VramGrowthTracker {
+    window: Duration,
+    gpus: HashMap<String, VramHistory>,
+}
+
+impl VramGrowthTracker {
+    fn new(window: Duration) -> Self {
+        VramGrowthTracker {
+            window,
+            gpus: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, gpus: &[GpuInfo], now: Instant) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for gpu in gpus {
+            let Some(memory_used) = gpu.memory_used else {
+                continue;
+            };
+            let history = self.gpus.entry(gpu.uuid.clone()).or_insert_with(|| VramHistory {
+                index: gpu.index,
+                min: memory_used,
+                max: memory_used,
+                samples: VecDeque::new(),
+            });
+            history.index = gpu.index;
+            history.min = history.min.min(memory_used);
+            history.max = history.max.max(memory_used);
+            history.samples.push_back((now, memory_used));
+
+            // 只保留窗口内的样本，外加一个刚好落在窗口起点之前的样本作为基准
+            while history.samples.len() >= 2 && now.duration_since(history.samples[1].0) >= self.window {
+                history.samples.pop_front();
+            }
+
+            lines.push(tf(
+                "watch.vram",
+                &[
+                    &history.index,
+                    &format!("{:.2}", memory_used),
+                    &format!("{:.2}", history.min),
+                    &format!("{:.2}", history.max),
+                ],
+            ));
+
+            if let Some((growth, span)) = monotonic_growth(&history.samples, self.window) {
+                lines.push(tf(
+                    "watch.vram_growth",
+                    &[&history.index, &format!("{:.1}", growth), &format_span(span)],
+                ));
+            }
+        }
+
+        lines
+    }
+}
+
+// 样本覆盖了整个窗口、期间从未下降并且总体上涨时返回 (涨幅 GB, 时间跨度)
+fn monotonic_growth(samples: &VecDeque<(Instant, f64)>, window: Duration) -> Option<(f64, Duration)> {
+    let (first_at, first) = *samples.front()?;
+    let (last_at, last) = *samples.back()?;
+    let span = last_at.duration_since(first_at);
+
+    if span < window || last <= first {
+        return None;
+    }
+
+    let non_decreasing = samples.iter().zip(samples.iter().skip(1)).all(|((_, a), (_, b))| b >= a);
+    non_decreasing.then_some((last - first, span))
+}
+
+fn format_span(span: Duration) -> String {
+    let seconds = span.as_secs();
+    if seconds < 60 {
+        format!("{} s", seconds)
+    } else {
+        format!("{} min", (seconds + 30) / 60)
+    }
+}
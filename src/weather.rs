@@ -0,0 +1,336 @@
+#[cfg(feature = "weather")]
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, NaiveTime};
+use clap::ValueEnum;
+#[cfg(feature = "weather")]
+use reqwest::header::CONTENT_TYPE;
+#[cfg(feature = "weather")]
+use reqwest::{Client, RequestBuilder, StatusCode};
+#[cfg(feature = "weather")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "weather")]
+use tokio::runtime::Builder;
+#[cfg(feature = "weather")]
+use url::Url;
+
+use crate::i18n::t;
+#[cfg(feature = "weather")]
+use crate::i18n::tf;
+#[cfg(feature = "weather")]
+use crate::meizu::Meizu;
+#[cfg(feature = "weather")]
+use crate::openweathermap::OpenWeatherMap;
+#[cfg(feature = "weather")]
+use crate::weather_cache;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// meizu forecast API (cities in China, by city ID)
+    #[default]
+    Meizu,
+    /// oioweb API: raw response only, located by the caller's IP
+    Oioweb,
+    /// OpenWeatherMap, worldwide; needs an API key
+    #[value(name = "openweathermap")]
+    OpenWeatherMap,
+}
+
+// 从哪个接口、查哪里的天气
+#[derive(Debug, Clone, Default)]
+pub struct WeatherQuery {
+    pub provider: Provider,
+    pub city_id: String,
+    pub api_key: Option<String>,
+    pub city: Option<String>,
+    pub coordinates: Option<(f64, f64)>,
+    // None 时不读也不写缓存
+    pub cache_ttl: Option<Duration>,
+    // 主来源失败时按顺序依次尝试的来源
+    pub fallbacks: Vec<Provider>,
+    // --weather-url：代替主来源的默认接口地址，备用来源仍用各自的默认地址
+    pub base_url: Option<String>,
+}
+
+// 一个天气来源；新的接口实现这个 trait，再在 WeatherQuery::provider 里按 --weather-provider 选出来
+pub trait WeatherProvider {
+    fn fetch(&self) -> Result<WeatherReport, String>;
+}
+
+#[cfg(feature = "weather")]
+impl WeatherQuery {
+    // 去重后的尝试顺序；oioweb 没有解析结果，不能当备用来源
+    fn providers(&self) -> Vec<Provider> {
+        let mut providers = vec![self.provider];
+        for &provider in &self.fallbacks {
+            if provider != Provider::Oioweb && !providers.contains(&provider) {
+                providers.push(provider);
+            }
+        }
+        providers
+    }
+
+    // oioweb 只打印原始响应，没有对应的 WeatherProvider
+    pub fn provider(&self) -> Result<Box<dyn WeatherProvider>, String> {
+        match self.provider {
+            Provider::Meizu => Ok(Box::new(Meizu::from_query(self)?)),
+            Provider::OpenWeatherMap => Ok(Box::new(OpenWeatherMap::from_query(self)?)),
+            Provider::Oioweb => Err(t("weather.oioweb_raw_only").to_string()),
+        }
+    }
+}
+
+// 各个接口的数据都整理成这个结构再输出；温度是摄氏度，取不到的项是 None
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WeatherReport {
+    pub provider: Provider,
+    pub city: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city_id: Option<String>,
+    // 省份或国家
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    pub current: CurrentWeather,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub air_quality: Option<AirQuality>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub indexes: Vec<LifeIndex>,
+    pub days: Vec<DailyForecast>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<WeatherAlert>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CurrentWeather {
+    pub weather: String,
+    pub temperature: Option<f64>,
+    // 接口给的温度解析不出数字时（比如 "N/A"、"--"）原样保留，显示时代替 temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_raw: Option<String>,
+    pub feels_like: Option<f64>,
+    pub humidity: Option<f64>,
+    pub wind: String,
+    pub uv_index: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AirQuality {
+    pub aqi: String,
+    pub quality: String,
+}
+
+// 穿衣、洗车之类的生活指数
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LifeIndex {
+    pub name: String,
+    pub level: String,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DailyForecast {
+    // YYYY-MM-DD，当地日期
+    pub date: String,
+    pub weather: String,
+    pub temp_day: Option<f64>,
+    pub temp_night: Option<f64>,
+    // 和 CurrentWeather::temperature_raw 一样，解析不出数字时的原文
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temp_day_raw: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temp_night_raw: Option<String>,
+    pub wind: String,
+    // 当地时间 HH:MM
+    pub sunrise: Option<String>,
+    pub sunset: Option<String>,
+}
+
+impl DailyForecast {
+    // 极地或缺数据时没有日出日落，返回 None
+    pub fn sun_times(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let parse = |value: &Option<String>| NaiveTime::parse_from_str(value.as_deref()?, "%H:%M").ok();
+        Some((parse(&self.sunrise)?, parse(&self.sunset)?))
+    }
+
+    pub fn daylight(&self) -> Option<chrono::Duration> {
+        let (rise, down) = self.sun_times()?;
+        (down > rise).then(|| down - rise)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WeatherAlert {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub level: String,
+    pub kind: String,
+    pub precaution: String,
+    pub published: Option<DateTime<FixedOffset>>,
+}
+
+// 主来源和 --weather-fallback 里的来源依次尝试，第一个成功解析的结果为准；全部失败时每个来源的错误都报出来
+#[cfg(feature = "weather")]
+pub fn fetch(query: &WeatherQuery) -> Result<WeatherReport, String> {
+    let mut errors = Vec::new();
+    for provider in query.providers() {
+        let query = WeatherQuery {
+            provider,
+            base_url: query.base_url.clone().filter(|_| provider == query.provider),
+            ..query.clone()
+        };
+        let result = match query.cache_ttl {
+            Some(ttl) => weather_cache::fetch(&query, ttl),
+            None => query.provider().and_then(|provider| provider.fetch()),
+        };
+        match result {
+            Ok(report) => return Ok(report),
+            Err(e) => errors.push((provider, e)),
+        }
+    }
+
+    match errors.as_slice() {
+        [(_, e)] => Err(e.clone()),
+        _ => Err(errors
+            .iter()
+            .map(|(provider, e)| match provider.to_possible_value() {
+                Some(value) => format!("{}: {}", value.get_name(), e),
+                None => e.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("; ")),
+    }
+}
+
+// 没有 weather 特性时 collect_options 不会打开天气部分，这两个函数只是保证调用的地方不用分情况编译
+#[cfg(not(feature = "weather"))]
+pub fn fetch(_query: &WeatherQuery) -> Result<WeatherReport, String> {
+    Err(t("weather.disabled").to_string())
+}
+
+#[cfg(not(feature = "weather"))]
+pub fn print_raw_weather(_base_url: Option<&str>) -> Result<(), String> {
+    Err(t("weather.disabled").to_string())
+}
+
+// 一次性的请求用单线程运行时，结束后不等后台的 DNS 解析等阻塞任务，直接关闭，
+// 保证单次运行不会因为残留的运行时线程拖慢退出
+#[cfg(feature = "weather")]
+pub(crate) fn block_on<F: Future>(future: F) -> Result<F::Output, String> {
+    let rt = Builder::new_current_thread().enable_all().build().map_err(|e| e.to_string())?;
+    let output = rt.block_on(future);
+    rt.shutdown_background();
+
+    Ok(output)
+}
+
+// 接口地址必须是 http/https 的完整 URL；path 接在地址后面，地址末尾有没有 / 结果都一样。
+// 查询参数由调用方再加，地址里原有的参数保留
+#[cfg(feature = "weather")]
+pub(crate) fn endpoint(base: &str, path: &str) -> Result<Url, String> {
+    let mut url = Url::parse(base).map_err(|e| tf("weather.bad_url", &[&base, &e]))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(tf("weather.bad_url_scheme", &[&base]));
+    }
+
+    let trimmed = url.path().trim_end_matches('/').to_string();
+    let joined = if path.is_empty() { trimmed } else { format!("{}/{}", trimmed, path) };
+    url.set_path(if joined.is_empty() { "/" } else { &joined });
+    Ok(url)
+}
+
+// 非 2xx 响应的错误信息：接口一般也返回 JSON，带上其中的 message，例如 "401 Unauthorized: Invalid API key"
+#[cfg(feature = "weather")]
+fn status_error(status: StatusCode, body: &str) -> String {
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| value.get("message")?.as_str().map(str::to_string));
+    match message {
+        Some(message) => format!("{}: {}", status, message),
+        None => status.to_string(),
+    }
+}
+
+// 发请求并把响应解析成 JSON
+#[cfg(feature = "weather")]
+pub(crate) async fn get_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, String> {
+    let response = request.send().await.map_err(|e| e.to_string())?;
+
+    // 检查响应状态码
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(status_error(status, &body));
+    }
+
+    // 验证码、限流之类的 HTML 页面直接报告真实原因，不去解析；没有 Content-Type 时照常尝试解析
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    if let Some(content_type) = content_type.filter(|content_type| !content_type.contains("json")) {
+        return Err(tf("weather.not_json", &[&content_type]));
+    }
+
+    // 读取响应的内容
+    let body = response.text().await.map_err(|e| e.to_string())?;
+    serde_json::from_str(body.as_str()).map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "weather")]
+const OIOWEB_URL: &str = "https://api.oioweb.cn/api/weather/GetWeather";
+
+// base_url 是 oioweb 作为主来源时的 --weather-url；成功时打印原始响应，
+// 离线、DNS 失败或读响应中断时返回错误，由调用方决定怎么报告
+#[cfg(feature = "weather")]
+pub fn print_raw_weather(base_url: Option<&str>) -> Result<(), String> {
+    let body = block_on(async {
+        let url = endpoint(base_url.unwrap_or(OIOWEB_URL), "")?;
+
+        // 创建一个HTTP客户端
+        let client = Client::new();
+
+        // 发送GET请求并等待响应
+        let response = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // 检查响应状态码
+        if !response.status().is_success() {
+            return Err(response.status().to_string());
+        }
+
+        // 读取响应的内容
+        response.text().await.map_err(|e| e.to_string())
+    })
+    .and_then(|body| body)?;
+    println!("{}: {}", t("request_ok"), body);
+    Ok(())
+}
+
+#[cfg(all(test, feature = "weather"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_error_with_json_message() {
+        let body = r#"{"cod": 401, "message": "Invalid API key. Please see https://openweathermap.org/faq#error401"}"#;
+        assert_eq!(
+            status_error(StatusCode::UNAUTHORIZED, body),
+            "401 Unauthorized: Invalid API key. Please see https://openweathermap.org/faq#error401"
+        );
+    }
+
+    #[test]
+    fn status_error_without_message() {
+        assert_eq!(status_error(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>"), "502 Bad Gateway");
+        assert_eq!(status_error(StatusCode::NOT_FOUND, r#"{"cod": "404"}"#), "404 Not Found");
+        assert_eq!(status_error(StatusCode::TOO_MANY_REQUESTS, ""), "429 Too Many Requests");
+    }
+}
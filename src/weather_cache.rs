@@ -0,0 +1,103 @@
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::weather::{endpoint, Provider, WeatherQuery, WeatherReport};
+
+// 天气几分钟内基本不变，结果缓存到磁盘，TTL 内的重复运行不再请求接口；
+// 缓存是否新鲜按文件的修改时间判断，读写失败都当作没有缓存
+pub fn fetch(query: &WeatherQuery, ttl: Duration) -> Result<WeatherReport, String> {
+    let path = path(query);
+    if let Some(report) = path.as_ref().and_then(|path| read(path, ttl)) {
+        return Ok(report);
+    }
+
+    let report = query.provider()?.fetch()?;
+    if let Some(path) = path {
+        write(&path, &report);
+    }
+
+    Ok(report)
+}
+
+fn read(path: &Path, ttl: Duration) -> Option<WeatherReport> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    if SystemTime::now().duration_since(modified).ok()? >= ttl {
+        return None;
+    }
+
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+fn write(path: &Path, report: &WeatherReport) {
+    if let (Some(dir), Ok(json)) = (path.parent(), serde_json::to_vec(report)) {
+        let _ = fs::create_dir_all(dir).and_then(|_| fs::write(path, json));
+    }
+}
+
+// 例如 weather-101200105.json；OpenWeatherMap 按城市名或坐标区分。
+// 用 --weather-url 换了接口地址时再加上规范化后地址的哈希，不同代理、测试服务器的结果不会串用
+fn path(query: &WeatherQuery) -> Option<PathBuf> {
+    let key = match query.provider {
+        Provider::Meizu => query.city_id.clone(),
+        Provider::OpenWeatherMap => match (query.coordinates, &query.city) {
+            (Some((lat, lon)), _) => format!("openweathermap-{}_{}", lat, lon),
+            (None, Some(city)) => format!("openweathermap-{}", city),
+            (None, None) => return None,
+        },
+        Provider::Oioweb => return None,
+    };
+    let key = match &query.base_url {
+        Some(base_url) => {
+            let url = endpoint(base_url, "").map_or_else(|_| base_url.clone(), String::from);
+            let mut hasher = DefaultHasher::new();
+            url.hash(&mut hasher);
+            format!("{}-{:016x}", key, hasher.finish())
+        }
+        None => key,
+    };
+    let key: String = key
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+
+    Some(cache_dir()?.join("rust-system-details").join(format!("weather-{}.json", key)))
+}
+
+// Linux/macOS: $XDG_CACHE_HOME 或 ~/.cache；Windows: %LOCALAPPDATA%
+fn cache_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        return env::var_os("LOCALAPPDATA").map(PathBuf::from);
+    }
+
+    match env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => Some(PathBuf::from(env::var_os("HOME")?).join(".cache")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(base_url: Option<&str>) -> WeatherQuery {
+        WeatherQuery {
+            city_id: "101200105".to_string(),
+            base_url: base_url.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn base_url_is_part_of_the_key() {
+        let default = path(&query(None));
+        let proxy = path(&query(Some("http://127.0.0.1:8080/listWeather")));
+        assert_ne!(default, proxy);
+        assert_ne!(proxy, path(&query(Some("http://127.0.0.1:8081/listWeather"))));
+        // 末尾的 / 不影响接口地址，也不影响缓存
+        assert_eq!(proxy, path(&query(Some("http://127.0.0.1:8080/listWeather/"))));
+    }
+}
@@ -0,0 +1,29 @@
+// 天气接口连不上时（离线、被墙、代理挂了）报告仍要完整输出，不能以 panic 的 101 退出
+use std::process::Command;
+
+// 本机 9 号（discard）端口一般没有服务监听，连接会立刻被拒绝
+const UNREACHABLE: &str = "http://127.0.0.1:9/";
+
+#[test]
+fn unreachable_oioweb_does_not_panic() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-systemDetails"))
+        .args(["--lang", "en", "--weather-provider", "oioweb", "--weather-url", UNREACHABLE])
+        .output()
+        .expect("run rust-systemDetails");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_ne!(output.status.code(), Some(101), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("STATUS:"), "{}", stdout);
+    #[cfg(feature = "weather")]
+    assert!(stdout.contains("Request failed"), "{}", stdout);
+}
+
+#[test]
+fn unreachable_oioweb_weather_command_exits_2() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-systemDetails"))
+        .args(["--lang", "en", "--weather-provider", "oioweb", "--weather-url", UNREACHABLE, "weather"])
+        .output()
+        .expect("run rust-systemDetails");
+
+    assert_eq!(output.status.code(), Some(2), "{}", String::from_utf8_lossy(&output.stderr));
+}
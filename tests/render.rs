@@ -0,0 +1,92 @@
+// --mock 的 SystemInfo 是固定的，文本报告里内存、交换区和磁盘这几行不能随采集方式的改动而变
+use std::process::Command;
+
+const EXPECTED: &[&str] = &[
+    "Total Memory: 32.00 GB",
+    "Used Memory: 8.00 GB",
+    "Used Memory (total - available, as in free): 8.00 GB",
+    "Available Memory: 24.00 GB",
+    "Total Swap: 8.00 GB",
+    "Used Swap: 0.50 GB",
+    "2 disks: 2339.96 GB total, 1169.98 GB used, 1169.98 GB free (50.00% used)",
+    "Name           | Kind | File System | Mount Point | Total      | Available | Inodes          | Busy",
+    "---------------+------+-------------+-------------+------------+-----------+-----------------+------",
+    "/dev/nvme0n1p2 | SSD  | ext4        | /           | 476.94 GB  | 238.47 GB | 8.4M/16.8M free | 0.00%",
+    "/dev/sda1      | HDD  | xfs         | /data       | 1863.02 GB | 931.51 GB | 8.4M/16.8M free | 0.00%",
+];
+
+fn mock_report(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-systemDetails"))
+        .args(["--mock", "--lang", "en"])
+        .args(args)
+        // 不读开发机上的配置文件
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .output()
+        .expect("run rust-systemDetails");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+// 按顺序逐行查找，中间可以夹着别的行
+fn assert_lines(stdout: &str, expected: &[&str]) {
+    let mut lines = stdout.lines();
+    for expected in expected {
+        assert!(lines.any(|line| line == *expected), "missing or out of order: {}\n{}", expected, stdout);
+    }
+}
+
+#[test]
+fn memory_swap_and_disk_lines() {
+    assert_lines(&mock_report(&[]), EXPECTED);
+}
+
+#[test]
+fn precision_0() {
+    assert_lines(
+        &mock_report(&["--precision", "0"]),
+        &[
+            "Power Usage: 212 W",
+            "Memory Used: 10 GB (42%)",
+            "Total Memory: 32 GB",
+            "Average CPU Usage: 12%",
+            "CPU Package Power: 65 W",
+            "Total measured power (CPU + GPUs): 277 W",
+            "2 disks: 2340 GB total, 1170 GB used, 1170 GB free (50% used)",
+            "/dev/nvme0n1p2 | SSD  | ext4        | /           | 477 GB  | 238 GB    | 8.4M/16.8M free | 0%",
+        ],
+    );
+}
+
+#[test]
+fn precision_3() {
+    assert_lines(
+        &mock_report(&["--precision", "3"]),
+        &[
+            "Power Usage: 212.400 W",
+            "Memory Used: 10.000 GB (41.667%)",
+            "Total Memory: 32.000 GB",
+            "Average CPU Usage: 12.500%",
+            "CPU Package Power: 65.000 W",
+            "Total measured power (CPU + GPUs): 277.400 W",
+            "2 disks: 2339.960 GB total, 1169.980 GB used, 1169.980 GB free (50.000% used)",
+            "/dev/nvme0n1p2 | SSD  | ext4        | /           | 476.940 GB  | 238.470 GB | 8.4M/16.8M free | 0.000%",
+        ],
+    );
+}
+
+// 没给 --anonymize-salt 时用配置目录下随机生成的盐：多次运行哈希不变，换个盐就不同
+#[test]
+fn anonymize_uses_install_salt() {
+    let host_name = |args: &[&str]| {
+        let report: serde_json::Value = serde_json::from_str(&mock_report(args)).expect("json report");
+        report["system"]["host_name"].as_str().expect("hashed host name").to_string()
+    };
+
+    let first = host_name(&["--anonymize"]);
+    assert_eq!(first, host_name(&["--anonymize"]));
+    assert_ne!(first, host_name(&["--anonymize", "--anonymize-salt", "rust-system-details"]));
+    let salt = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("rust-system-details/anonymize-salt");
+    assert!(salt.exists());
+}